@@ -10,6 +10,9 @@ pub(crate) enum CssStmt {
         selector: ExtendedSelector,
         body: Vec<Self>,
         is_group_end: bool,
+        /// The number of additional levels this rule is indented by in
+        /// [`crate::OutputStyle::Nested`] output
+        tabs: usize,
     },
     Style(Style),
     Media(MediaRule, bool),
@@ -71,11 +74,13 @@ impl CssStmt {
             CssStmt::RuleSet {
                 selector,
                 is_group_end,
+                tabs,
                 ..
             } => CssStmt::RuleSet {
                 selector: selector.clone(),
                 body: Vec::new(),
                 is_group_end: *is_group_end,
+                tabs: *tabs,
             },
             CssStmt::Style(..) | CssStmt::Comment(..) | CssStmt::Import(..) => unreachable!(),
             CssStmt::Media(media, is_group_end) => CssStmt::Media(
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use crate::{
     ast::ArgumentResult,
@@ -12,8 +12,23 @@ pub(crate) use crate::ast::AstMixin as UserDefinedMixin;
 
 #[derive(Clone)]
 pub(crate) enum Mixin {
-    UserDefined(UserDefinedMixin, Environment),
-    Builtin(BuiltinMixin),
+    UserDefined(Arc<UserDefinedMixin>, Environment),
+    Builtin {
+        mixin: BuiltinMixin,
+        accepts_content: bool,
+    },
+}
+
+impl Mixin {
+    /// Whether this mixin may be passed a content block
+    pub fn accepts_content(&self) -> bool {
+        match self {
+            Self::UserDefined(mixin, ..) => mixin.has_content,
+            Self::Builtin {
+                accepts_content, ..
+            } => *accepts_content,
+        }
+    }
 }
 
 impl fmt::Debug for Mixin {
@@ -26,7 +41,12 @@ impl fmt::Debug for Mixin {
                 .field("body", &u.body)
                 .field("has_content", &u.has_content)
                 .finish(),
-            Self::Builtin(..) => f.debug_struct("BuiltinMixin").finish(),
+            Self::Builtin {
+                accepts_content, ..
+            } => f
+                .debug_struct("BuiltinMixin")
+                .field("accepts_content", accepts_content)
+                .finish(),
         }
     }
 }
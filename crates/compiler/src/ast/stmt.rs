@@ -13,6 +13,7 @@ use crate::{
     common::Identifier,
     utils::{BaseMapView, LimitedMapView, MapView, UnprefixedMapView},
     value::Value,
+    Deprecation,
 };
 
 #[derive(Debug, Clone)]
@@ -90,6 +91,7 @@ impl AstStyle {
 pub struct AstEach {
     pub variables: Vec<Identifier>,
     pub list: AstExpr,
+    pub list_span: Span,
     pub body: Vec<AstStmt>,
 }
 
@@ -563,6 +565,16 @@ pub struct StyleSheet {
     pub uses: Vec<usize>,
     /// Array of indices into `body`
     pub forwards: Vec<usize>,
+    /// Deprecation warnings encountered while parsing, which are emitted once
+    /// the stylesheet is evaluated
+    pub(crate) parse_time_warnings: Vec<ParseTimeWarning>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ParseTimeWarning {
+    pub deprecation: Deprecation,
+    pub message: String,
+    pub span: Span,
 }
 
 impl StyleSheet {
@@ -573,6 +585,7 @@ impl StyleSheet {
             is_plain_css,
             uses: Vec::new(),
             forwards: Vec::new(),
+            parse_time_warnings: Vec::new(),
         }
     }
 }
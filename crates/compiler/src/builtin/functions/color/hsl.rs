@@ -5,7 +5,8 @@ use crate::{builtin::builtin_imports::*, serializer::serialize_number, value::Sa
 use super::{
     angle_value,
     rgb::{function_string, parse_channels, percentage_or_unitless},
-    ParsedChannels,
+    space::{color_from_channels, has_missing_channels},
+    warn_deprecated_color_function, ParsedChannels,
 };
 
 fn hsl_3_args(
@@ -82,6 +83,9 @@ fn inner_hsl(
             args.span(),
         )? {
             ParsedChannels::String(s) => Ok(Value::String(s, QuoteKind::None)),
+            ParsedChannels::List(list) if has_missing_channels(&list) => {
+                color_from_channels(ColorSpace::Hsl, &list, args.span())
+            }
             ParsedChannels::List(list) => {
                 let args = ArgumentResult {
                     positional: list,
@@ -165,6 +169,14 @@ pub(crate) fn adjust_hue(mut args: ArgumentResult, visitor: &mut Visitor) -> Sas
         .assert_color_with_name("color", args.span())?;
     let degrees = angle_value(args.get_err(1, "degrees")?, "degrees", args.span())?;
 
+    warn_deprecated_color_function(
+        "adjust-hue",
+        "hue",
+        &format!("{}deg", degrees.inspect()),
+        visitor,
+        args.span(),
+    )?;
+
     Ok(Value::Color(Arc::new(color.adjust_hue(degrees))))
 }
 
@@ -180,6 +192,14 @@ fn lighten(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value>
 
     amount.assert_bounds("amount", 0.0, 100.0, args.span())?;
 
+    warn_deprecated_color_function(
+        "lighten",
+        "lightness",
+        &format!("{}%", amount.num.inspect()),
+        visitor,
+        args.span(),
+    )?;
+
     amount.num /= Number(100.0);
 
     Ok(Value::Color(Arc::new(color.lighten(amount.num))))
@@ -197,6 +217,14 @@ fn darken(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value>
 
     amount.assert_bounds("amount", 0.0, 100.0, args.span())?;
 
+    warn_deprecated_color_function(
+        "darken",
+        "lightness",
+        &format!("-{}%", amount.num.inspect()),
+        visitor,
+        args.span(),
+    )?;
+
     amount.num /= Number(100.0);
 
     Ok(Value::Color(Arc::new(color.darken(amount.num))))
@@ -224,6 +252,14 @@ fn saturate(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value
 
     amount.assert_bounds("amount", 0.0, 100.0, args.span())?;
 
+    warn_deprecated_color_function(
+        "saturate",
+        "saturation",
+        &format!("{}%", amount.num.inspect()),
+        visitor,
+        args.span(),
+    )?;
+
     amount.num /= Number(100.0);
 
     let color = args
@@ -245,6 +281,14 @@ fn desaturate(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Val
 
     amount.assert_bounds("amount", 0.0, 100.0, args.span())?;
 
+    warn_deprecated_color_function(
+        "desaturate",
+        "saturation",
+        &format!("-{}%", amount.num.inspect()),
+        visitor,
+        args.span(),
+    )?;
+
     amount.num /= Number(100.0);
 
     Ok(Value::Color(Arc::new(color.desaturate(amount.num))))
@@ -3,6 +3,7 @@ use crate::builtin::builtin_imports::*;
 use super::{
     angle_value,
     rgb::{parse_channels, percentage_or_unitless},
+    space::{color_from_channels, has_missing_channels},
     ParsedChannels,
 };
 
@@ -81,6 +82,9 @@ pub(crate) fn hwb(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult
                 args.span(),
             )
                 .into()),
+            ParsedChannels::List(list) if has_missing_channels(&list) => {
+                color_from_channels(ColorSpace::Hwb, &list, args.span())
+            }
             ParsedChannels::List(list) => {
                 let args = ArgumentResult {
                     positional: list,
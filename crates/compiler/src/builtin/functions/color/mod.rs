@@ -1,9 +1,11 @@
 use codemap::Span;
 
 use crate::{
-    builtin::builtin_imports::Unit,
+    builtin::builtin_imports::{QuoteKind, Unit},
     error::SassResult,
+    evaluate::Visitor,
     value::{conversion_factor, Number, Value},
+    Deprecation,
 };
 
 use super::GlobalFunctionMap;
@@ -13,6 +15,7 @@ pub mod hwb;
 pub mod opacity;
 pub mod other;
 pub mod rgb;
+pub mod space;
 
 #[derive(Debug, Clone)]
 pub(crate) enum ParsedChannels {
@@ -20,6 +23,11 @@ pub(crate) enum ParsedChannels {
     List(Vec<Value>),
 }
 
+/// Whether `value` is the keyword `none`, representing a missing channel
+pub(crate) fn is_none(value: &Value) -> bool {
+    matches!(value, Value::String(text, QuoteKind::None) if text.eq_ignore_ascii_case("none"))
+}
+
 pub(crate) fn angle_value(num: Value, name: &str, span: Span) -> SassResult<Number> {
     let angle = num.assert_number_with_name(name, span)?;
 
@@ -32,9 +40,29 @@ pub(crate) fn angle_value(num: Value, name: &str, span: Span) -> SassResult<Numb
     Ok(angle.num)
 }
 
+/// Warn that a legacy global color function is deprecated in favor of
+/// adjusting a single channel using `color.adjust(..)`
+pub(crate) fn warn_deprecated_color_function(
+    name: &str,
+    channel: &str,
+    amount: &str,
+    visitor: &mut Visitor,
+    span: Span,
+) -> SassResult<()> {
+    visitor.emit_deprecation(
+        Deprecation::ColorFunctions,
+        &format!(
+            "{}() is deprecated. Suggestion:\n\ncolor.adjust($color, ${}: {})\n\nMore info: https://sass-lang.com/d/color-functions",
+            name, channel, amount
+        ),
+        span,
+    )
+}
+
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     hsl::declare(f);
     opacity::declare(f);
     other::declare(f);
     rgb::declare(f);
+    space::declare(f);
 }
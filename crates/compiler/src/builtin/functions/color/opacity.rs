@@ -1,5 +1,7 @@
 use crate::builtin::builtin_imports::*;
 
+use super::warn_deprecated_color_function;
+
 /// Check if `s` matches the regex `^[a-zA-Z]+\s*=`
 fn is_ms_filter(s: &str) -> bool {
     let mut bytes = s.bytes();
@@ -84,7 +86,15 @@ pub(crate) fn opacity(mut args: ArgumentResult, visitor: &mut Visitor) -> SassRe
     }
 }
 
-fn opacify(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+fn opacify(args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    fade_in("opacify", args, visitor)
+}
+
+fn fade_in_alias(args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    fade_in("fade-in", args, visitor)
+}
+
+fn fade_in(name: &str, mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(2)?;
     let color = args
         .get_err(0, "color")?
@@ -95,10 +105,20 @@ fn opacify(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value>
 
     amount.assert_bounds_with_unit("amount", 0.0, 1.0, &Unit::None, args.span())?;
 
+    warn_deprecated_color_function(name, "alpha", &amount.num.inspect(), visitor, args.span())?;
+
     Ok(Value::Color(Arc::new(color.fade_in(amount.num))))
 }
 
-fn transparentize(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+fn transparentize(args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    fade_out("transparentize", args, visitor)
+}
+
+fn fade_out_alias(args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    fade_out("fade-out", args, visitor)
+}
+
+fn fade_out(name: &str, mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(2)?;
     let color = args
         .get_err(0, "color")?
@@ -110,6 +130,14 @@ fn transparentize(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult
 
     amount.assert_bounds_with_unit("amount", 0.0, 1.0, &Unit::None, args.span())?;
 
+    warn_deprecated_color_function(
+        name,
+        "alpha",
+        &format!("-{}", amount.num.inspect()),
+        visitor,
+        args.span(),
+    )?;
+
     Ok(Value::Color(Arc::new(color.fade_out(amount.num))))
 }
 
@@ -117,7 +145,7 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("alpha", Builtin::new(alpha));
     f.insert("opacity", Builtin::new(opacity));
     f.insert("opacify", Builtin::new(opacify));
-    f.insert("fade-in", Builtin::new(opacify));
+    f.insert("fade-in", Builtin::new(fade_in_alias));
     f.insert("transparentize", Builtin::new(transparentize));
-    f.insert("fade-out", Builtin::new(transparentize));
+    f.insert("fade-out", Builtin::new(fade_out_alias));
 }
@@ -1,5 +1,10 @@
 use crate::{
-    builtin::{builtin_imports::*, color::angle_value},
+    builtin::{
+        builtin_imports::*,
+        color::{angle_value, is_none, space::space_arg},
+    },
+    color::ChannelInfo,
+    error::SassError,
     utils::to_sentence,
     value::fuzzy_round,
 };
@@ -11,6 +16,149 @@ enum UpdateComponents {
     Scale,
 }
 
+fn unknown_arguments(args: &ArgumentResult) -> Box<SassError> {
+    let argument_word = if args.named.len() == 1 {
+        "argument"
+    } else {
+        "arguments"
+    };
+
+    let argument_names = to_sentence(
+        args.named
+            .keys()
+            .map(|key| format!("${key}", key = key))
+            .collect(),
+        "or",
+    );
+
+    (
+        format!(
+            "No {argument_word} named {argument_names}.",
+            argument_word = argument_word,
+            argument_names = argument_names
+        ),
+        args.span(),
+    )
+        .into()
+}
+
+/// Update the channels of `color` after converting it to `space`, used for
+/// non-legacy colors and when `$space` is passed explicitly
+fn update_in_space(
+    mut args: ArgumentResult,
+    color: &Color,
+    space: ColorSpace,
+    update: UpdateComponents,
+) -> SassResult<Value> {
+    let converted = color.to_space(space);
+    let mut channels = converted.channels();
+
+    let update_channel = |value: Spanned<Value>,
+                          current: Option<Number>,
+                          info: ChannelInfo|
+     -> SassResult<Option<Number>> {
+        let span = value.span;
+
+        if update == UpdateComponents::Change && is_none(&value.node) {
+            return Ok(None);
+        }
+
+        if info.is_hue && update == UpdateComponents::Scale {
+            return Err((format!("${}: Channel isn't scalable.", info.name), span).into());
+        } else if info.is_hue {
+            let hue = angle_value(value.node, info.name, span)?;
+            return Ok(Some(match update {
+                UpdateComponents::Change => hue,
+                _ => current.unwrap_or_else(Number::zero) + hue,
+            }));
+        }
+
+        let number = value.node.assert_number_with_name(info.name, span)?;
+        let current = current.unwrap_or_else(Number::zero);
+
+        if update == UpdateComponents::Scale {
+            number.assert_unit(&Unit::Percent, info.name, span)?;
+            number.assert_bounds(info.name, -100.0, 100.0, span)?;
+
+            let scale = number.num / Number(100.0);
+            return Ok(Some(
+                current
+                    + if scale > Number::zero() {
+                        Number(info.max) - current
+                    } else {
+                        current - Number(info.min)
+                    } * scale,
+            ));
+        }
+
+        let amount = match number.unit {
+            Unit::Percent => number.num / Number(100.0) * Number(info.percent_reference),
+            Unit::None => number.num,
+            _ => {
+                return Err((
+                    format!(
+                        "${}: Expected {} to have unit \"%\" or no units.",
+                        info.name,
+                        Value::Dimension(number).inspect(span)?
+                    ),
+                    span,
+                )
+                    .into())
+            }
+        };
+
+        Ok(Some(match update {
+            UpdateComponents::Change => amount,
+            _ => current + amount,
+        }))
+    };
+
+    for (idx, info) in space.channels().iter().enumerate() {
+        if let Some(value) = args.get_named(info.name) {
+            channels[idx] = update_channel(value, channels[idx], *info)?;
+        }
+    }
+
+    let mut alpha = if converted.is_alpha_missing() {
+        None
+    } else {
+        Some(converted.alpha())
+    };
+
+    if let Some(value) = args.get_named("alpha") {
+        let info = ChannelInfo {
+            name: "alpha",
+            min: 0.0,
+            max: 1.0,
+            percent_reference: 1.0,
+            is_hue: false,
+        };
+
+        alpha = update_channel(value, alpha, info)?;
+    }
+
+    if !args.named.is_empty() {
+        return Err(unknown_arguments(&args));
+    }
+
+    for (channel, info) in channels.iter_mut().zip(space.channels().iter()) {
+        // keep lightness and chroma meaningful, as other implementations do
+        if let Some(value) = channel {
+            match info.name {
+                "lightness" if !space.is_legacy() => {
+                    *value = value.clamp(info.min, info.max);
+                }
+                "chroma" => *value = value.clamp(0.0, f64::INFINITY),
+                _ => {}
+            }
+        }
+    }
+
+    let result = Color::for_space(space, channels, alpha.map(|alpha| alpha.clamp(0.0, 1.0)));
+
+    Ok(Value::Color(Arc::new(result.to_space(color.space()))))
+}
+
 fn update_components(
     mut args: ArgumentResult,
     visitor: &mut Visitor,
@@ -30,6 +178,14 @@ fn update_components(
             .into());
     }
 
+    if let Some(space) = args.get_named("space") {
+        let space = space_arg(space.node, "space", space.span)?;
+        return update_in_space(args, &color, space, update);
+    } else if !color.is_legacy() {
+        let space = color.space();
+        return update_in_space(args, &color, space, update);
+    }
+
     let check_num = |num: Spanned<Value>,
                      name: &str,
                      mut max: f64,
@@ -112,29 +268,7 @@ fn update_components(
     let blackness = get_arg(&mut args, "blackness", 100.0, true, true)?;
 
     if !args.named.is_empty() {
-        let argument_word = if args.named.len() == 1 {
-            "argument"
-        } else {
-            "arguments"
-        };
-
-        let argument_names = to_sentence(
-            args.named
-                .keys()
-                .map(|key| format!("${key}", key = key))
-                .collect(),
-            "or",
-        );
-
-        return Err((
-            format!(
-                "No {argument_word} named {argument_names}.",
-                argument_word = argument_word,
-                argument_names = argument_names
-            ),
-            span,
-        )
-            .into());
+        return Err(unknown_arguments(&args));
     }
 
     let has_rgb = red.is_some() || green.is_some() || blue.is_some();
@@ -1,6 +1,13 @@
-use crate::{builtin::builtin_imports::*, serializer::inspect_number, value::fuzzy_round};
+use crate::{
+    builtin::builtin_imports::*, color::HueInterpolationMethod, serializer::inspect_number,
+    value::fuzzy_round,
+};
 
-use super::ParsedChannels;
+use super::{
+    is_none,
+    space::{color_from_channels, has_missing_channels, space_arg},
+    ParsedChannels,
+};
 
 pub(crate) fn function_string(
     name: &'static str,
@@ -217,7 +224,9 @@ pub(crate) fn parse_channels(
         channels = list[0].clone();
         let inner_alpha_from_slash_list = list[1].clone();
 
-        if !inner_alpha_from_slash_list.is_special_function() {
+        if !inner_alpha_from_slash_list.is_special_function()
+            && !is_none(&inner_alpha_from_slash_list)
+        {
             inner_alpha_from_slash_list
                 .clone()
                 .assert_number_with_name("alpha", span)?;
@@ -322,6 +331,9 @@ fn inner_rgb(
                 args.span(),
             )? {
                 ParsedChannels::String(s) => Ok(Value::String(s, QuoteKind::None)),
+                ParsedChannels::List(list) if has_missing_channels(&list) => {
+                    color_from_channels(ColorSpace::Rgb, &list, args.span())
+                }
                 ParsedChannels::List(list) => {
                     let args = ArgumentResult {
                         positional: list,
@@ -375,8 +387,72 @@ pub(crate) fn blue(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResul
     Ok(Value::Dimension(SassNumber::new_unitless(color.blue())))
 }
 
+/// Parse the `$method` argument to `mix(..)`, e.g. `oklch longer hue`
+fn interpolation_method(
+    method: Value,
+    span: Span,
+) -> SassResult<(ColorSpace, HueInterpolationMethod)> {
+    let mut words = method.clone().as_list().into_iter();
+
+    let space = match words.next() {
+        Some(space) => space_arg(space, "method", span)?,
+        None => return Err(("$method: Expected a color space.", span).into()),
+    };
+
+    let hue_method = match words.next() {
+        Some(hue_method) => {
+            let (name, ..) = hue_method.assert_string_with_name("method", span)?;
+
+            if !space.is_polar() {
+                return Err((
+                    format!(
+                        "$method: Hue interpolation method \"{} hue\" may not be set for rectangular color space {}.",
+                        name, space
+                    ),
+                    span,
+                )
+                    .into());
+            }
+
+            match (HueInterpolationMethod::from_name(&name), words.next()) {
+                (Some(hue_method), Some(Value::String(hue, QuoteKind::None))) if hue == "hue" => {
+                    hue_method
+                }
+                (Some(..), _) => {
+                    return Err((
+                        "$method: Expected unquoted string \"hue\" after hue interpolation method.",
+                        span,
+                    )
+                        .into())
+                }
+                (None, _) => {
+                    return Err((
+                        format!("$method: Unknown hue interpolation method {}.", name),
+                        span,
+                    )
+                        .into())
+                }
+            }
+        }
+        None => HueInterpolationMethod::default(),
+    };
+
+    if words.next().is_some() {
+        return Err((
+            format!(
+                "$method: Expected a color interpolation method, got {}.",
+                method.inspect(span)?
+            ),
+            span,
+        )
+            .into());
+    }
+
+    Ok((space, hue_method))
+}
+
 pub(crate) fn mix(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
-    args.max_args(3)?;
+    args.max_args(4)?;
     let color1 = args
         .get_err(0, "color1")?
         .assert_color_with_name("color1", args.span())?;
@@ -406,7 +482,24 @@ pub(crate) fn mix(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult
                 .into())
         }
     };
-    Ok(Value::Color(Arc::new(color1.mix(&color2, weight))))
+
+    match args.default_arg(3, "method", Value::Null) {
+        Value::Null if !color1.is_legacy() || !color2.is_legacy() => Err((
+            "$method: To use color.mix() with non-legacy colors, you must provide a $method.",
+            args.span(),
+        )
+            .into()),
+        Value::Null => Ok(Value::Color(Arc::new(color1.mix(&color2, weight)))),
+        method => {
+            let (space, hue_method) = interpolation_method(method, args.span())?;
+
+            Ok(Value::Color(Arc::new(
+                color1
+                    .interpolate(&color2, space, hue_method, weight)
+                    .to_space(color1.space()),
+            )))
+        }
+    }
 }
 
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
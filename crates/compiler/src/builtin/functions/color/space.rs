@@ -0,0 +1,463 @@
+use crate::{builtin::builtin_imports::*, color::GamutMapMethod, value::fuzzy_equals};
+
+use super::{
+    angle_value, is_none,
+    rgb::{function_string, parse_channels},
+    ParsedChannels,
+};
+
+fn channel_value(
+    value: &Value,
+    space: ColorSpace,
+    idx: usize,
+    span: Span,
+) -> SassResult<Option<f64>> {
+    if is_none(value) {
+        return Ok(None);
+    }
+
+    let info = space.channels()[idx];
+
+    if info.is_hue {
+        return Ok(Some(angle_value(value.clone(), info.name, span)?.0));
+    }
+
+    let number = value.clone().assert_number_with_name(info.name, span)?;
+
+    let channel = match number.unit {
+        Unit::Percent => number.num.0 / 100.0 * info.percent_reference,
+        Unit::None => number.num.0,
+        _ => {
+            return Err((
+                format!(
+                    "${}: Expected {} to have unit \"%\" or no units.",
+                    info.name,
+                    value.inspect(span)?
+                ),
+                span,
+            )
+                .into())
+        }
+    };
+
+    Ok(Some(match (space, info.name) {
+        (
+            ColorSpace::Lab | ColorSpace::Lch | ColorSpace::Oklab | ColorSpace::Oklch,
+            "lightness",
+        ) => channel.clamp(info.min, info.max),
+        (_, "chroma") => channel.max(0.0),
+        _ => channel,
+    }))
+}
+
+fn alpha_value(value: Option<&Value>, span: Span) -> SassResult<Option<Number>> {
+    let value = match value {
+        Some(value) if is_none(value) => return Ok(None),
+        Some(value) => value,
+        None => return Ok(Some(Number::one())),
+    };
+
+    let number = value.clone().assert_number_with_name("alpha", span)?;
+
+    let alpha = match number.unit {
+        Unit::Percent => number.num / Number(100.0),
+        _ => number.num,
+    };
+
+    Ok(Some(alpha.clamp(0.0, 1.0)))
+}
+
+/// Create a color in `space` from the already-parsed channels of a color
+/// function, with the alpha channel (if any) as the final element
+pub(crate) fn color_from_channels(
+    space: ColorSpace,
+    list: &[Value],
+    span: Span,
+) -> SassResult<Value> {
+    let channels = [
+        channel_value(&list[0], space, 0, span)?.map(Number),
+        channel_value(&list[1], space, 1, span)?.map(Number),
+        channel_value(&list[2], space, 2, span)?.map(Number),
+    ];
+
+    let alpha = alpha_value(list.get(3), span)?;
+
+    Ok(Value::Color(Arc::new(Color::for_space(
+        space, channels, alpha,
+    ))))
+}
+
+/// Whether the channels passed to a legacy color function use syntax which
+/// is only supported by the CSS Color Level 4 representation
+pub(crate) fn has_missing_channels(list: &[Value]) -> bool {
+    list.iter().any(is_none) && !has_special_channels(list)
+}
+
+/// Whether any channel can only be resolved by the browser, such as `var()`
+fn has_special_channels(list: &[Value]) -> bool {
+    list.iter()
+        .any(|channel| channel.is_var() || channel.is_special_function())
+}
+
+fn space_function(
+    name: &'static str,
+    space: ColorSpace,
+    mut args: ArgumentResult,
+    visitor: &mut Visitor,
+) -> SassResult<Value> {
+    args.max_args(1)?;
+
+    let arg_names = space.channels().map(|info| info.name);
+    let channels = args.get_err(0, "channels")?;
+
+    match parse_channels(name, &arg_names, channels.clone(), visitor, args.span())? {
+        ParsedChannels::String(s) => Ok(Value::String(s, QuoteKind::None)),
+        ParsedChannels::List(list) if has_special_channels(&list) => Ok(Value::String(
+            function_string(name, &[channels], visitor, args.span())?,
+            QuoteKind::None,
+        )),
+        ParsedChannels::List(list) => color_from_channels(space, &list, args.span()),
+    }
+}
+
+pub(crate) fn lab(args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    space_function("lab", ColorSpace::Lab, args, visitor)
+}
+
+pub(crate) fn lch(args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    space_function("lch", ColorSpace::Lch, args, visitor)
+}
+
+pub(crate) fn oklab(args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    space_function("oklab", ColorSpace::Oklab, args, visitor)
+}
+
+pub(crate) fn oklch(args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    space_function("oklch", ColorSpace::Oklch, args, visitor)
+}
+
+pub(crate) fn color(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(1)?;
+    let span = args.span();
+
+    let description = args.get_err(0, "description")?;
+
+    if description.is_var() {
+        return Ok(Value::String(
+            function_string("color", &[description], visitor, span)?,
+            QuoteKind::None,
+        ));
+    }
+
+    let original = description.clone();
+
+    // split the space name off of the channels, keeping any slash-separated alpha
+    let (space_name, channels) = if description.separator() == ListSeparator::Slash {
+        let mut list = description.as_list();
+        if list.len() != 2 {
+            return Err((
+                format!(
+                    "Only 2 slash-separated elements allowed, but {} {} passed.",
+                    list.len(),
+                    if list.len() == 1 { "was" } else { "were" }
+                ),
+                span,
+            )
+                .into());
+        }
+
+        let alpha = list.pop().unwrap();
+        let mut inner = list.pop().unwrap().as_list();
+        let space_name = (!inner.is_empty()).then(|| inner.remove(0));
+
+        (
+            space_name,
+            Value::List(
+                vec![
+                    Value::List(inner, ListSeparator::Space, Brackets::None),
+                    alpha,
+                ],
+                ListSeparator::Slash,
+                Brackets::None,
+            ),
+        )
+    } else {
+        let mut list = description.as_list();
+        let space_name = (!list.is_empty()).then(|| list.remove(0));
+
+        (
+            space_name,
+            Value::List(list, ListSeparator::Space, Brackets::None),
+        )
+    };
+
+    let space = match &space_name {
+        Some(Value::String(name, QuoteKind::None)) => ColorSpace::from_name(name)
+            .filter(|space| space.uses_color_function())
+            .ok_or_else(|| (format!("Unknown color space \"{}\".", name), span))?,
+        Some(value) if value.is_var() => {
+            return Ok(Value::String(
+                function_string("color", &[original], visitor, span)?,
+                QuoteKind::None,
+            ));
+        }
+        Some(value) => {
+            return Err((
+                format!(
+                    "$description: Expected {} to be an unquoted string.",
+                    value.inspect(span)?
+                ),
+                span,
+            )
+                .into())
+        }
+        None => return Err(("$description: Expected a color space.", span).into()),
+    };
+
+    let arg_names = space.channels().map(|info| info.name);
+
+    match parse_channels("color", &arg_names, channels, visitor, span)? {
+        ParsedChannels::List(list) if !has_special_channels(&list) => {
+            color_from_channels(space, &list, span)
+        }
+        ParsedChannels::String(..) | ParsedChannels::List(..) => Ok(Value::String(
+            function_string("color", &[original], visitor, span)?,
+            QuoteKind::None,
+        )),
+    }
+}
+
+/// Parse the name of a color space passed to a color module function
+pub(crate) fn space_arg(value: Value, name: &str, span: Span) -> SassResult<ColorSpace> {
+    let (text, ..) = value.assert_string_with_name(name, span)?;
+
+    ColorSpace::from_name(&text).ok_or_else(|| {
+        (
+            format!("${}: Unknown color space \"{}\".", name, text),
+            span,
+        )
+            .into()
+    })
+}
+
+/// Parse an optional `$space` argument, defaulting to the space of `color`
+fn optional_space_arg(
+    args: &mut ArgumentResult,
+    position: usize,
+    color: &Color,
+) -> SassResult<ColorSpace> {
+    match args.default_arg(position, "space", Value::Null) {
+        Value::Null => Ok(color.space()),
+        value => space_arg(value, "space", args.span()),
+    }
+}
+
+/// The index of the channel named by `$channel`, or `None` for `alpha`
+fn channel_arg(
+    value: Value,
+    color: &Color,
+    space: ColorSpace,
+    span: Span,
+) -> SassResult<Option<usize>> {
+    let (name, ..) = value.assert_string_with_name("channel", span)?;
+
+    if name == "alpha" {
+        return Ok(None);
+    }
+
+    match space.channels().iter().position(|info| info.name == name) {
+        Some(idx) => Ok(Some(idx)),
+        None => Err((
+            format!(
+                "$channel: Color {} has no channel named {}.",
+                Value::Color(Arc::new(color.to_space(space))).inspect(span)?,
+                name
+            ),
+            span,
+        )
+            .into()),
+    }
+}
+
+pub(crate) fn space(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(1)?;
+    let color = args
+        .get_err(0, "color")?
+        .assert_color_with_name("color", args.span())?;
+
+    Ok(Value::String(
+        color.space().name().to_owned(),
+        QuoteKind::None,
+    ))
+}
+
+pub(crate) fn to_space(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(2)?;
+    let color = args
+        .get_err(0, "color")?
+        .assert_color_with_name("color", args.span())?;
+    let space = space_arg(args.get_err(1, "space")?, "space", args.span())?;
+
+    Ok(Value::Color(Arc::new(color.to_space(space))))
+}
+
+pub(crate) fn is_legacy(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(1)?;
+    let color = args
+        .get_err(0, "color")?
+        .assert_color_with_name("color", args.span())?;
+
+    Ok(Value::bool(color.is_legacy()))
+}
+
+pub(crate) fn is_missing(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(2)?;
+    let color = args
+        .get_err(0, "color")?
+        .assert_color_with_name("color", args.span())?;
+    let channel = channel_arg(
+        args.get_err(1, "channel")?,
+        &color,
+        color.space(),
+        args.span(),
+    )?;
+
+    Ok(Value::bool(match channel {
+        Some(idx) => color.channels()[idx].is_none(),
+        None => color.is_alpha_missing(),
+    }))
+}
+
+pub(crate) fn is_powerless(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(3)?;
+    let color = args
+        .get_err(0, "color")?
+        .assert_color_with_name("color", args.span())?;
+    let channel = args.get_err(1, "channel")?;
+    let space = optional_space_arg(&mut args, 2, &color)?;
+    let color = color.to_space(space);
+
+    Ok(Value::bool(
+        match channel_arg(channel, &color, space, args.span())? {
+            Some(idx) => color.is_channel_powerless(idx),
+            None => false,
+        },
+    ))
+}
+
+pub(crate) fn is_in_gamut(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(2)?;
+    let color = args
+        .get_err(0, "color")?
+        .assert_color_with_name("color", args.span())?;
+    let space = optional_space_arg(&mut args, 1, &color)?;
+
+    Ok(Value::bool(color.is_in_gamut(space)))
+}
+
+pub(crate) fn to_gamut(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(3)?;
+    let span = args.span();
+    let color = args
+        .get_err(0, "color")?
+        .assert_color_with_name("color", span)?;
+    let space = optional_space_arg(&mut args, 1, &color)?;
+
+    let method = match args.default_arg(2, "method", Value::Null) {
+        Value::Null => {
+            return Err((
+                "$method: color.to-gamut() requires a $method argument for forwards-compatibility with changes in the CSS spec. Suggestion:\n\n$method: local-minde",
+                span,
+            )
+                .into())
+        }
+        value => {
+            let (name, ..) = value.assert_string_with_name("method", span)?;
+            GamutMapMethod::from_name(&name).ok_or_else(|| {
+                (
+                    format!("$method: Unknown gamut map method \"{}\".", name),
+                    span,
+                )
+            })?
+        }
+    };
+
+    Ok(Value::Color(Arc::new(color.to_gamut(space, method))))
+}
+
+pub(crate) fn same(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(2)?;
+    let color1 = args
+        .get_err(0, "color1")?
+        .assert_color_with_name("color1", args.span())?;
+    let color2 = args
+        .get_err(1, "color2")?
+        .assert_color_with_name("color2", args.span())?;
+
+    // missing channels are equivalent to zero once converted
+    let xyz1 = color1.to_space(ColorSpace::XyzD65);
+    let xyz2 = color2.to_space(ColorSpace::XyzD65);
+
+    Ok(Value::bool(
+        fuzzy_equals(xyz1.alpha().0, xyz2.alpha().0)
+            && xyz1
+                .channels()
+                .iter()
+                .zip(xyz2.channels().iter())
+                .all(|(channel1, channel2)| {
+                    fuzzy_equals(
+                        channel1.map_or(0.0, |channel| channel.0),
+                        channel2.map_or(0.0, |channel| channel.0),
+                    )
+                }),
+    ))
+}
+
+pub(crate) fn channel(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(3)?;
+    let color = args
+        .get_err(0, "color")?
+        .assert_color_with_name("color", args.span())?;
+    let channel = args.get_err(1, "channel")?;
+    let space = optional_space_arg(&mut args, 2, &color)?;
+    let color = color.to_space(space);
+
+    let idx = match channel_arg(channel, &color, space, args.span())? {
+        Some(idx) => idx,
+        None => {
+            return Ok(Value::Dimension(SassNumber::new_unitless(
+                if color.is_alpha_missing() {
+                    Number::zero()
+                } else {
+                    color.alpha()
+                },
+            )))
+        }
+    };
+
+    let info = space.channels()[idx];
+    let value = color.channels()[idx].unwrap_or_else(Number::zero);
+
+    let (num, unit) = match (space, info.name) {
+        (_, "hue") => (value, Unit::Deg),
+        (ColorSpace::Oklab | ColorSpace::Oklch, "lightness") => {
+            (value * Number(100.0), Unit::Percent)
+        }
+        (_, "saturation" | "lightness" | "whiteness" | "blackness") => (value, Unit::Percent),
+        _ => (value, Unit::None),
+    };
+
+    Ok(Value::Dimension(SassNumber {
+        num,
+        unit,
+        as_slash: None,
+    }))
+}
+
+pub(crate) fn declare(f: &mut GlobalFunctionMap) {
+    f.insert("lab", Builtin::new(lab));
+    f.insert("lch", Builtin::new(lch));
+    f.insert("oklab", Builtin::new(oklab));
+    f.insert("oklch", Builtin::new(oklch));
+    f.insert("color", Builtin::new(color));
+}
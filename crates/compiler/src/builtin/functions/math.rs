@@ -20,10 +20,6 @@ pub(crate) fn round(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResu
         .get_err(0, "number")?
         .assert_number_with_name("number", args.span())?;
 
-    if !number.num.is_finite() {
-        return Err(("Infinity or NaN toInt", args.span()).into());
-    }
-
     number.num = number.num.round();
 
     Ok(Value::Dimension(number))
@@ -35,10 +31,6 @@ pub(crate) fn ceil(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResul
         .get_err(0, "number")?
         .assert_number_with_name("number", args.span())?;
 
-    if !number.num.is_finite() {
-        return Err(("Infinity or NaN toInt", args.span()).into());
-    }
-
     number.num = number.num.ceil();
 
     Ok(Value::Dimension(number))
@@ -50,10 +42,6 @@ pub(crate) fn floor(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResu
         .get_err(0, "number")?
         .assert_number_with_name("number", args.span())?;
 
-    if !number.num.is_finite() {
-        return Err(("Infinity or NaN toInt", args.span()).into());
-    }
-
     number.num = number.num.floor();
 
     Ok(Value::Dimension(number))
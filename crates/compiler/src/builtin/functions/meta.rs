@@ -33,6 +33,12 @@ fn if_(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
 
 pub(crate) fn feature_exists(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(1)?;
+    visitor.emit_deprecation(
+        Deprecation::FeatureExists,
+        "The feature-exists() function is deprecated.\n\nMore info: https://sass-lang.com/d/feature-exists",
+        args.span(),
+    )?;
+
     let feature = args
         .get_err(0, "feature")?
         .assert_string_with_name("feature", args.span())?
@@ -270,7 +276,16 @@ pub(crate) fn call(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResul
     let span = args.span();
     let func = match args.get_err(0, "function")? {
         Value::FunctionRef(f) => *f,
-        Value::String(name, ..) => {
+        Value::String(name, quotes) => {
+            visitor.emit_deprecation(
+                Deprecation::CallString,
+                &format!(
+                    "Passing a string to call() is deprecated and will be illegal in Dart Sass 2.0.0.\n\nRecommendation: call(get-function({}))",
+                    Value::String(name.clone(), quotes).inspect(span)?
+                ),
+                span,
+            )?;
+
             let name = Identifier::from(name);
 
             match visitor.env.get_fn(name, None)? {
@@ -4,7 +4,10 @@
 use std::{
     collections::{BTreeSet, HashMap},
     fmt,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use once_cell::sync::Lazy;
@@ -22,6 +25,10 @@ pub mod string;
 // todo: maybe Identifier instead of str?
 pub(crate) type GlobalFunctionMap = HashMap<&'static str, Builtin>;
 
+/// The body of a [`Builtin`]
+pub(crate) type BuiltinFn =
+    Arc<dyn Fn(ArgumentResult, &mut Visitor) -> SassResult<Value> + Send + Sync>;
+
 static FUNCTION_COUNT: AtomicUsize = AtomicUsize::new(0);
 
 /// A function implemented in rust that is accessible from within Sass
@@ -52,23 +59,46 @@ static FUNCTION_COUNT: AtomicUsize = AtomicUsize::new(0);
 /// ```
 #[derive(Clone)]
 pub struct Builtin(
-    pub(crate) fn(ArgumentResult, &mut Visitor) -> SassResult<Value>,
-    usize,
+    pub(crate) BuiltinFn,
+    pub(crate) usize,
+    /// The Sass argument declaration this function accepts, e.g. `($a, $b: 1)`
+    pub(crate) Option<Arc<str>>,
 );
 
 impl fmt::Debug for Builtin {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Builtin")
-            .field("id", &self.1)
-            .field("fn_ptr", &(self.0 as usize))
-            .finish()
+        f.debug_struct("Builtin").field("id", &self.1).finish()
     }
 }
 
 impl Builtin {
     pub fn new(body: fn(ArgumentResult, &mut Visitor) -> SassResult<Value>) -> Builtin {
+        Self::from_closure(body)
+    }
+
+    /// Create a builtin from a closure, which unlike [`Builtin::new`] allows
+    /// the function to capture state from its environment
+    pub fn from_closure<F>(body: F) -> Builtin
+    where
+        F: Fn(ArgumentResult, &mut Visitor) -> SassResult<Value> + Send + Sync + 'static,
+    {
         let count = FUNCTION_COUNT.fetch_add(1, Ordering::Relaxed);
-        Self(body, count)
+        Self(Arc::new(body), count, None)
+    }
+
+    /// Create a builtin whose arguments are declared using Sass syntax, e.g.
+    /// `($color, $amount: 10%)`
+    ///
+    /// Arguments are bound to the declaration exactly as they would be for a
+    /// user-defined `@function`, including default values and argument lists.
+    /// The function is then passed one positional argument for each declared
+    /// argument, in order, followed by the rest argument if one was declared.
+    pub fn with_signature<F>(signature: &str, body: F) -> Builtin
+    where
+        F: Fn(ArgumentResult, &mut Visitor) -> SassResult<Value> + Send + Sync + 'static,
+    {
+        let count = FUNCTION_COUNT.fetch_add(1, Ordering::Relaxed);
+        Self(Arc::new(body), count, Some(Arc::from(signature)))
     }
 }
 
@@ -112,3 +142,75 @@ pub(crate) static DISALLOWED_PLAIN_CSS_FUNCTION_NAMES: Lazy<BTreeSet<&str>> = La
         })
         .collect()
 });
+
+/// The module member which should be used in place of a global built-in
+/// function, if the global function is deprecated
+///
+/// Functions which share a name with a plain CSS function, as well as the
+/// legacy color functions which have their own deprecation, are not included.
+pub(crate) fn deprecated_global_function(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "red" => "color.red",
+        "green" => "color.green",
+        "blue" => "color.blue",
+        "mix" => "color.mix",
+        "hue" => "color.hue",
+        "saturation" => "color.saturation",
+        "lightness" => "color.lightness",
+        "complement" => "color.complement",
+        "adjust-color" => "color.adjust",
+        "scale-color" => "color.scale",
+        "change-color" => "color.change",
+        "ie-hex-str" => "color.ie-hex-str",
+        "length" => "list.length",
+        "nth" => "list.nth",
+        "set-nth" => "list.set-nth",
+        "join" => "list.join",
+        "append" => "list.append",
+        "zip" => "list.zip",
+        "index" => "list.index",
+        "list-separator" => "list.separator",
+        "is-bracketed" => "list.is-bracketed",
+        "map-get" => "map.get",
+        "map-merge" => "map.merge",
+        "map-remove" => "map.remove",
+        "map-keys" => "map.keys",
+        "map-values" => "map.values",
+        "map-has-key" => "map.has-key",
+        "ceil" => "math.ceil",
+        "floor" => "math.floor",
+        "percentage" => "math.percentage",
+        "random" => "math.random",
+        "unit" => "math.unit",
+        "unitless" => "math.is-unitless",
+        "comparable" => "math.compatible",
+        "inspect" => "meta.inspect",
+        "type-of" => "meta.type-of",
+        "keywords" => "meta.keywords",
+        "global-variable-exists" => "meta.global-variable-exists",
+        "variable-exists" => "meta.variable-exists",
+        "function-exists" => "meta.function-exists",
+        "mixin-exists" => "meta.mixin-exists",
+        "content-exists" => "meta.content-exists",
+        "get-function" => "meta.get-function",
+        "call" => "meta.call",
+        "selector-nest" => "selector.nest",
+        "selector-append" => "selector.append",
+        "selector-extend" => "selector.extend",
+        "selector-replace" => "selector.replace",
+        "selector-unify" => "selector.unify",
+        "is-superselector" => "selector.is-superselector",
+        "simple-selectors" => "selector.simple-selectors",
+        "selector-parse" => "selector.parse",
+        "unquote" => "string.unquote",
+        "quote" => "string.quote",
+        "str-length" => "string.length",
+        "str-insert" => "string.insert",
+        "str-index" => "string.index",
+        "str-slice" => "string.slice",
+        "to-upper-case" => "string.to-upper-case",
+        "to-lower-case" => "string.to-lower-case",
+        "unique-id" => "string.unique-id",
+        _ => return None,
+    })
+}
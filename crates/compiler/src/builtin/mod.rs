@@ -2,8 +2,8 @@ mod functions;
 pub(crate) mod modules;
 
 pub(crate) use functions::{
-    color, list, map, math, meta, selector, string, DISALLOWED_PLAIN_CSS_FUNCTION_NAMES,
-    GLOBAL_FUNCTIONS,
+    color, deprecated_global_function, list, map, math, meta, selector, string,
+    DISALLOWED_PLAIN_CSS_FUNCTION_NAMES, GLOBAL_FUNCTIONS,
 };
 
 pub use functions::Builtin;
@@ -19,13 +19,13 @@ mod builtin_imports {
 
     pub(crate) use crate::{
         ast::{Argument, ArgumentDeclaration, ArgumentResult, MaybeEvaledArguments},
-        color::Color,
+        color::{Color, ColorSpace},
         common::{BinaryOp, Brackets, Identifier, ListSeparator, QuoteKind},
         error::SassResult,
         evaluate::Visitor,
         unit::Unit,
         value::{CalculationArg, Number, SassFunction, SassMap, SassNumber, Value},
-        Options,
+        Deprecation, Options,
     };
 
     pub(crate) use std::{
@@ -5,6 +5,10 @@ use crate::builtin::{
         opacity::alpha,
         other::{adjust_color, change_color, ie_hex_str, scale_color},
         rgb::{blue, green, mix, red},
+        space::{
+            channel, is_in_gamut, is_legacy, is_missing, is_powerless, same, space, to_gamut,
+            to_space,
+        },
     },
     modules::Module,
 };
@@ -28,4 +32,13 @@ pub(crate) fn declare(f: &mut Module) {
     f.insert_builtin("blackness", blackness);
     f.insert_builtin("whiteness", whiteness);
     f.insert_builtin("hwb", hwb);
+    f.insert_builtin("channel", channel);
+    f.insert_builtin("is-in-gamut", is_in_gamut);
+    f.insert_builtin("is-legacy", is_legacy);
+    f.insert_builtin("is-missing", is_missing);
+    f.insert_builtin("is-powerless", is_powerless);
+    f.insert_builtin("same", same);
+    f.insert_builtin("space", space);
+    f.insert_builtin("to-gamut", to_gamut);
+    f.insert_builtin("to-space", to_space);
 }
@@ -17,7 +17,7 @@ use crate::{
     utils::{
         BaseMapView, LimitedMapView, MapView, MergedMapView, PrefixedMapView, PublicMemberMapView,
     },
-    value::{SassFunction, SassMap, Value},
+    value::{SassFunction, SassMap, SassMixin, Value},
 };
 
 use super::builtin_imports::QuoteKind;
@@ -216,9 +216,8 @@ impl ModuleScope {
 pub(crate) enum Module {
     Environment {
         scope: ModuleScope,
-        #[allow(dead_code)]
-        upstream: Vec<Module>,
-        #[allow(dead_code)]
+        /// The user-defined modules used or forwarded by this module.
+        upstream: Vec<Arc<RefCell<Module>>>,
         extension_store: ExtensionStore,
         #[allow(dead_code)]
         env: Environment,
@@ -310,7 +309,11 @@ fn member_map<V: fmt::Debug + Clone + 'static>(
 }
 
 impl Module {
-    pub fn new_env(env: Environment, extension_store: ExtensionStore) -> Self {
+    pub fn new_env(
+        env: Environment,
+        extension_store: ExtensionStore,
+        upstream: Vec<Arc<RefCell<Module>>>,
+    ) -> Self {
         let variables = {
             let variables = (*env.forwarded_modules).borrow();
             let variables = variables
@@ -346,7 +349,7 @@ impl Module {
 
         Module::Environment {
             scope,
-            upstream: Vec::new(),
+            upstream,
             extension_store,
             env,
         }
@@ -427,7 +430,26 @@ impl Module {
     pub fn insert_builtin_mixin(&mut self, name: &'static str, mixin: BuiltinMixin) {
         let scope = self.scope();
 
-        scope.mixins.insert(name.into(), Mixin::Builtin(mixin));
+        scope.mixins.insert(
+            name.into(),
+            Mixin::Builtin {
+                mixin,
+                accepts_content: false,
+            },
+        );
+    }
+
+    /// Insert a builtin mixin which may be passed a content block
+    pub fn insert_builtin_mixin_with_content(&mut self, name: &'static str, mixin: BuiltinMixin) {
+        let scope = self.scope();
+
+        scope.mixins.insert(
+            name.into(),
+            Mixin::Builtin {
+                mixin,
+                accepts_content: true,
+            },
+        );
     }
 
     pub fn insert_builtin_var(&mut self, name: &'static str, value: Value) {
@@ -479,6 +501,23 @@ impl Module {
             .insert(ident, SassFunction::Builtin(Builtin::new(function), ident));
     }
 
+    pub fn mixins(&self, span: Span) -> SassMap {
+        SassMap::new_with(
+            self.scope()
+                .mixins
+                .iter()
+                .into_iter()
+                .filter(|(key, _)| !key.as_str().starts_with('-'))
+                .map(|(key, mixin)| {
+                    (
+                        Value::String(key.to_string(), QuoteKind::Quoted).span(span),
+                        Value::MixinRef(Box::new(SassMixin { mixin, name: key })),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
     pub fn functions(&self, span: Span) -> SassMap {
         SassMap::new_with(
             self.scope()
@@ -1,3 +1,5 @@
+use crate::builtin::builtin_imports::*;
+
 use crate::builtin::{
     modules::Module,
     string::{
@@ -8,12 +10,71 @@ use crate::builtin::{
 #[cfg(feature = "random")]
 use crate::builtin::string::unique_id;
 
+fn split(mut args: ArgumentResult, _visitor: &mut Visitor) -> SassResult<Value> {
+    args.max_args(3)?;
+    let span = args.span();
+
+    let (string, quotes) = args
+        .get_err(0, "string")?
+        .assert_string_with_name("string", span)?;
+
+    let separator = args
+        .get_err(1, "separator")?
+        .assert_string_with_name("separator", span)?
+        .0;
+
+    let limit = match args.default_arg(2, "limit", Value::Null) {
+        Value::Null => None,
+        v => {
+            let limit = v
+                .assert_number_with_name("limit", span)?
+                .assert_int_with_name("limit", span)?;
+
+            if limit < 1 {
+                return Err((
+                    format!("$limit: Must be 1 or greater, was {}.", limit),
+                    span,
+                )
+                    .into());
+            }
+
+            Some(limit as usize)
+        }
+    };
+
+    let chunks: Vec<Value> = if string.is_empty() {
+        Vec::new()
+    } else if separator.is_empty() {
+        string
+            .chars()
+            .map(|c| Value::String(c.to_string(), quotes))
+            .collect()
+    } else {
+        let chunks: Vec<&str> = match limit {
+            Some(limit) => string.splitn(limit + 1, separator.as_str()).collect(),
+            None => string.split(separator.as_str()).collect(),
+        };
+
+        chunks
+            .into_iter()
+            .map(|chunk| Value::String(chunk.to_owned(), quotes))
+            .collect()
+    };
+
+    Ok(Value::List(
+        chunks,
+        ListSeparator::Comma,
+        Brackets::Bracketed,
+    ))
+}
+
 pub(crate) fn declare(f: &mut Module) {
     f.insert_builtin("quote", quote);
     f.insert_builtin("index", str_index);
     f.insert_builtin("insert", str_insert);
     f.insert_builtin("length", str_length);
     f.insert_builtin("slice", str_slice);
+    f.insert_builtin("split", split);
     f.insert_builtin("to-lower-case", to_lower_case);
     f.insert_builtin("to-upper-case", to_upper_case);
     #[cfg(feature = "random")]
@@ -1,5 +1,10 @@
 //! A color is internally represented as either RGBA or HSLA.
 //!
+//! Colors in the non-legacy spaces from CSS Color Level 4 (e.g. `lab()` or
+//! `color(display-p3 ...)`), as well as legacy colors with missing channels,
+//! additionally carry their original space and channels. The RGBA
+//! representation of such a color is a gamut-clamped approximation.
+//!
 //! Colors can be constructed in Sass through names (e.g. red, blue, aqua)
 //! or the builtin functions `rgb()`, `rgba()`, `hsl()`, and `hsla()`,
 //! all of which can accept 1-4 arguments.
@@ -15,10 +20,13 @@
 //! Named colors retain their original casing,
 //! so `rEd` should be emitted as `rEd`.
 
-use crate::value::{fuzzy_round, Number};
+use crate::value::{fuzzy_equals, fuzzy_round, Number};
 pub(crate) use name::NAMED_COLORS;
+pub(crate) use space::ChannelInfo;
+pub use space::{ColorSpace, GamutMapMethod, HueInterpolationMethod};
 
 mod name;
+mod space;
 
 // todo: only store alpha once on color
 #[derive(Debug, Clone)]
@@ -26,9 +34,20 @@ pub struct Color {
     rgba: Rgb,
     hsla: Option<Hsl>,
     alpha: Number,
+    space: Option<SpaceData>,
     pub(crate) format: ColorFormat,
 }
 
+/// The original representation of a color which can't be described by its
+/// RGBA or HSLA values alone
+#[derive(Debug, Clone)]
+struct SpaceData {
+    space: ColorSpace,
+    /// Channels in the units of `space`, with `None` for missing channels
+    channels: [Option<f64>; 3],
+    alpha_missing: bool,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum ColorFormat {
     Rgb,
@@ -40,8 +59,38 @@ pub(crate) enum ColorFormat {
     Infer,
 }
 
+fn channels_equal(channels1: [Option<Number>; 3], channels2: [Option<Number>; 3]) -> bool {
+    channels1
+        .iter()
+        .zip(channels2.iter())
+        .all(|(channel1, channel2)| match (channel1, channel2) {
+            (Some(channel1), Some(channel2)) => fuzzy_equals(channel1.0, channel2.0),
+            (None, None) => true,
+            _ => false,
+        })
+}
+
 impl PartialEq for Color {
     fn eq(&self, other: &Self) -> bool {
+        if self.space.is_some() || other.space.is_some() {
+            if self.is_alpha_missing() != other.is_alpha_missing()
+                || !fuzzy_equals(self.alpha().0, other.alpha().0)
+            {
+                return false;
+            }
+
+            let space = self.space();
+
+            if space == other.space() {
+                return channels_equal(self.channels(), other.channels());
+            }
+
+            // legacy colors in different spaces are compared by their RGB values
+            if !space.is_legacy() || !other.space().is_legacy() {
+                return false;
+            }
+        }
+
         if self.alpha != other.alpha
             && !(self.alpha >= Number::one() && other.alpha >= Number::one())
         {
@@ -66,6 +115,7 @@ impl Color {
             rgba: Rgb::new(red, green, blue),
             alpha,
             hsla: None,
+            space: None,
             format,
         }
     }
@@ -75,6 +125,7 @@ impl Color {
             rgba: Rgb::new(red, green, blue),
             alpha,
             hsla: Some(hsla),
+            space: None,
             format: ColorFormat::Infer,
         }
     }
@@ -148,6 +199,7 @@ impl Color {
             rgba: Rgb::new(red.into(), green.into(), blue.into()),
             hsla: None,
             alpha: alpha.into(),
+            space: None,
             format: ColorFormat::Literal(format),
         }
     }
@@ -515,3 +567,355 @@ impl Color {
         Number(1.0) - (self.red().max(self.green()).max(self.blue()) / Number(255.0))
     }
 }
+
+/// Color space functions
+impl Color {
+    /// Create a color from channels in the given space, where `None` represents
+    /// a missing (`none`) channel.
+    ///
+    /// Legacy colors without missing channels use the same representation as
+    /// colors created through `rgb()`, `hsl()` and `hwb()`.
+    pub fn for_space(
+        space: ColorSpace,
+        channels: [Option<Number>; 3],
+        alpha: Option<Number>,
+    ) -> Self {
+        let mut channels = channels.map(|channel| channel.map(|channel| channel.0));
+
+        if space.is_polar() {
+            let hue = if space.channels()[0].is_hue { 0 } else { 2 };
+            channels[hue] = channels[hue].map(|hue| hue.rem_euclid(360.0));
+        }
+
+        let alpha_number = alpha.map_or(Number::zero(), |alpha| alpha.clamp(0.0, 1.0));
+
+        if space.is_legacy() && alpha.is_some() && channels.iter().all(Option::is_some) {
+            let [first, second, third] = channels.map(|channel| Number(channel.unwrap()));
+
+            return match space {
+                ColorSpace::Hsl => Color::from_hsla_fn(
+                    first,
+                    second / Number(100.0),
+                    third / Number(100.0),
+                    alpha_number,
+                ),
+                // hwb colors are otherwise indistinguishable from rgb colors
+                ColorSpace::Hwb => {
+                    let mut color = Color::from_hwb(first, second, third, alpha_number);
+                    color.space = Some(SpaceData {
+                        space,
+                        channels,
+                        alpha_missing: false,
+                    });
+                    color
+                }
+                _ => Color::from_rgba(first, second, third, alpha_number),
+            };
+        }
+
+        let [red, green, blue] = space.convert(
+            ColorSpace::Rgb,
+            channels.map(|channel| channel.unwrap_or(0.0)),
+        );
+
+        let mut color = Color::new_rgba(
+            Number(red).clamp(0.0, 255.0),
+            Number(green).clamp(0.0, 255.0),
+            Number(blue).clamp(0.0, 255.0),
+            alpha_number,
+            ColorFormat::Infer,
+        );
+
+        color.space = Some(SpaceData {
+            space,
+            channels,
+            alpha_missing: alpha.is_none(),
+        });
+
+        color
+    }
+
+    /// The space this color was defined in
+    pub fn space(&self) -> ColorSpace {
+        match &self.space {
+            Some(data) => data.space,
+            None if self.format == ColorFormat::Hsl => ColorSpace::Hsl,
+            None => ColorSpace::Rgb,
+        }
+    }
+
+    /// The channels of this color in its own space, with `None` for missing
+    /// channels
+    pub fn channels(&self) -> [Option<Number>; 3] {
+        if let Some(data) = &self.space {
+            return data.channels.map(|channel| channel.map(Number));
+        }
+
+        match self.space() {
+            ColorSpace::Hsl => {
+                let (hue, saturation, lightness, ..) = self.as_hsla();
+                [
+                    Some(hue),
+                    Some(saturation * Number(100.0)),
+                    Some(lightness * Number(100.0)),
+                ]
+            }
+            _ => [
+                Some(self.rgba.red),
+                Some(self.rgba.green),
+                Some(self.rgba.blue),
+            ],
+        }
+    }
+
+    /// Whether this color is in one of the spaces which predate CSS Color Level 4
+    pub fn is_legacy(&self) -> bool {
+        self.space().is_legacy()
+    }
+
+    pub fn is_alpha_missing(&self) -> bool {
+        self.space.as_ref().map_or(false, |data| data.alpha_missing)
+    }
+
+    /// Whether this color has missing channels, or a missing alpha
+    pub(crate) fn has_missing_channels(&self) -> bool {
+        self.space.as_ref().map_or(false, |data| {
+            data.alpha_missing || data.channels.iter().any(Option::is_none)
+        })
+    }
+
+    /// Convert this color to the given space. Missing channels are treated as
+    /// `0`.
+    pub fn to_space(&self, space: ColorSpace) -> Self {
+        if self.space() == space {
+            return self.clone();
+        }
+
+        let channels = self
+            .space()
+            .convert(
+                space,
+                self.channels()
+                    .map(|channel| channel.map_or(0.0, |channel| channel.0)),
+            )
+            .map(|channel| Some(Number(channel)));
+
+        Color::for_space(space, channels, Some(self.alpha()))
+    }
+}
+
+/// Gamut mapping and interpolation
+impl Color {
+    fn channels_or_zero(&self) -> [f64; 3] {
+        self.channels()
+            .map(|channel| channel.map_or(0.0, |channel| channel.0))
+    }
+
+    fn channels_in_gamut(space: ColorSpace, channels: [f64; 3]) -> bool {
+        channels
+            .iter()
+            .zip(space.channels().iter())
+            .all(|(&channel, info)| {
+                info.is_hue
+                    || ((channel > info.min || fuzzy_equals(channel, info.min))
+                        && (channel < info.max || fuzzy_equals(channel, info.max)))
+            })
+    }
+
+    fn clip_channels(space: ColorSpace, channels: [f64; 3]) -> [f64; 3] {
+        let infos = space.channels();
+        let mut clipped = channels;
+
+        for (channel, info) in clipped.iter_mut().zip(infos.iter()) {
+            if !info.is_hue {
+                *channel = channel.clamp(info.min, info.max);
+            }
+        }
+
+        clipped
+    }
+
+    /// Whether this color can be displayed in the gamut of `space`. Spaces
+    /// without a gamut, such as `lab`, contain every color.
+    pub fn is_in_gamut(&self, space: ColorSpace) -> bool {
+        match space.gamut_space() {
+            Some(gamut) => {
+                Self::channels_in_gamut(gamut, self.space().convert(gamut, self.channels_or_zero()))
+            }
+            None => true,
+        }
+    }
+
+    /// Map this color into the gamut of `space`, returning a color in this
+    /// color's own space
+    pub fn to_gamut(&self, space: ColorSpace, method: GamutMapMethod) -> Self {
+        let gamut = match space.gamut_space() {
+            Some(gamut) => gamut,
+            None => return self.clone(),
+        };
+
+        let channels = self.space().convert(gamut, self.channels_or_zero());
+
+        if Self::channels_in_gamut(gamut, channels) {
+            return self.clone();
+        }
+
+        let mapped = match method {
+            GamutMapMethod::Clip => Self::clip_channels(gamut, channels),
+            GamutMapMethod::LocalMinde => Self::local_minde(gamut, channels),
+        };
+
+        let alpha = if self.is_alpha_missing() {
+            None
+        } else {
+            Some(self.alpha())
+        };
+
+        Color::for_space(gamut, mapped.map(|channel| Some(Number(channel))), alpha)
+            .to_space(self.space())
+    }
+
+    /// The gamut mapping algorithm from CSS Color Level 4, which reduces
+    /// chroma in OKLCH until the clipped color is a just noticeable
+    /// difference away
+    fn local_minde(gamut: ColorSpace, channels: [f64; 3]) -> [f64; 3] {
+        const JND: f64 = 0.02;
+        const EPSILON: f64 = 0.0001;
+
+        let [lightness, chroma, hue] = gamut.convert(ColorSpace::Oklch, channels);
+
+        if lightness >= 1.0 || fuzzy_equals(lightness, 1.0) {
+            return ColorSpace::Oklab.convert(gamut, [1.0, 0.0, 0.0]);
+        } else if lightness <= 0.0 {
+            return ColorSpace::Oklab.convert(gamut, [0.0, 0.0, 0.0]);
+        }
+
+        let delta_eok = |a: [f64; 3], b: [f64; 3]| {
+            let a = gamut.convert(ColorSpace::Oklab, a);
+            let b = gamut.convert(ColorSpace::Oklab, b);
+            ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+        };
+
+        let mut clipped = Self::clip_channels(gamut, channels);
+
+        if delta_eok(clipped, channels) < JND {
+            return clipped;
+        }
+
+        let mut min = 0.0;
+        let mut max = chroma;
+        let mut min_in_gamut = true;
+
+        while max - min > EPSILON {
+            let current_chroma = (min + max) / 2.0;
+            let current = ColorSpace::Oklch.convert(gamut, [lightness, current_chroma, hue]);
+
+            if min_in_gamut && Self::channels_in_gamut(gamut, current) {
+                min = current_chroma;
+                continue;
+            }
+
+            clipped = Self::clip_channels(gamut, current);
+            let error = delta_eok(clipped, current);
+
+            if error < JND {
+                if JND - error < EPSILON {
+                    return clipped;
+                }
+
+                min_in_gamut = false;
+                min = current_chroma;
+            } else {
+                max = current_chroma;
+            }
+        }
+
+        clipped
+    }
+
+    /// Whether the channel at `idx` has no effect on this color, such as the
+    /// hue of a gray
+    pub fn is_channel_powerless(&self, idx: usize) -> bool {
+        let [_, second, third] = self.channels_or_zero();
+
+        match (self.space(), idx) {
+            (ColorSpace::Hsl, 0) => fuzzy_equals(second, 0.0),
+            (ColorSpace::Hwb, 0) => second + third >= 100.0 || fuzzy_equals(second + third, 100.0),
+            (ColorSpace::Lch | ColorSpace::Oklch, 2) => fuzzy_equals(second, 0.0),
+            _ => false,
+        }
+    }
+
+    /// Interpolate between this color and `other` in `space`, where `weight`
+    /// is the proportion of this color in the result. The result is in
+    /// `space`.
+    pub fn interpolate(
+        &self,
+        other: &Color,
+        space: ColorSpace,
+        hue_method: HueInterpolationMethod,
+        weight: Number,
+    ) -> Self {
+        let weight = weight.0.clamp(0.0, 1.0);
+
+        let color1 = self.to_space(space);
+        let color2 = other.to_space(space);
+
+        let mut channels1 = color1.channels();
+        let mut channels2 = color2.channels();
+
+        // powerless hues are treated as missing, so they take on the hue of
+        // the other color
+        for (channels, color) in [(&mut channels1, &color1), (&mut channels2, &color2)] {
+            for (idx, channel) in channels.iter_mut().enumerate() {
+                if color.is_channel_powerless(idx) {
+                    *channel = None;
+                }
+            }
+        }
+
+        let alpha1 = (!self.is_alpha_missing()).then(|| self.alpha().0);
+        let alpha2 = (!other.is_alpha_missing()).then(|| other.alpha().0);
+
+        let alpha = match (alpha1, alpha2) {
+            (Some(alpha1), Some(alpha2)) => Some(alpha1 * weight + alpha2 * (1.0 - weight)),
+            (alpha1, alpha2) => alpha1.or(alpha2),
+        };
+
+        // a missing alpha takes on the alpha of the other color
+        let premultiply1 = alpha1.or(alpha2).unwrap_or(1.0);
+        let premultiply2 = alpha2.or(alpha1).unwrap_or(1.0);
+
+        let infos = space.channels();
+        let mut result = [None; 3];
+
+        for idx in 0..3 {
+            let (channel1, channel2) = match (channels1[idx], channels2[idx]) {
+                (None, None) => continue,
+                (Some(channel), None) | (None, Some(channel)) => (channel.0, channel.0),
+                (Some(channel1), Some(channel2)) => (channel1.0, channel2.0),
+            };
+
+            result[idx] = Some(if infos[idx].is_hue {
+                let channel2 = hue_method.adjust(channel1, channel2);
+                channel1 * weight + channel2 * (1.0 - weight)
+            } else {
+                // interpolate with premultiplied alpha
+                let premultiplied1 = channel1 * premultiply1;
+                let premultiplied2 = channel2 * premultiply2;
+                let mixed = premultiplied1 * weight + premultiplied2 * (1.0 - weight);
+
+                match alpha {
+                    Some(alpha) if alpha != 0.0 => mixed / alpha,
+                    _ => mixed,
+                }
+            });
+        }
+
+        Color::for_space(
+            space,
+            result.map(|channel| channel.map(Number)),
+            alpha.map(Number),
+        )
+    }
+}
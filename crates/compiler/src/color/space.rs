@@ -0,0 +1,819 @@
+//! Color spaces from [CSS Color Level 4](https://www.w3.org/TR/css-color-4/),
+//! along with conversions between them.
+//!
+//! Every conversion goes through the CIE XYZ space with a D65 white point.
+//! The matrices and transfer functions are taken from the sample code in the
+//! specification.
+
+use std::fmt;
+
+type Matrix = [[f64; 3]; 3];
+
+/// A color space in which a [`crate::sass_value::Color`] can be represented
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// The legacy RGB space, with channels ranging from 0 to 255
+    Rgb,
+    /// The legacy HSL space
+    Hsl,
+    /// The legacy HWB space
+    Hwb,
+    Srgb,
+    SrgbLinear,
+    DisplayP3,
+    A98Rgb,
+    ProphotoRgb,
+    Rec2020,
+    XyzD65,
+    XyzD50,
+    Lab,
+    Lch,
+    Oklab,
+    Oklch,
+}
+
+/// The range a channel is expected to be in, and how percentages map onto it
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChannelInfo {
+    pub name: &'static str,
+    pub min: f64,
+    pub max: f64,
+    /// The value which `100%` refers to
+    pub percent_reference: f64,
+    pub is_hue: bool,
+}
+
+const fn linear(name: &'static str, min: f64, max: f64, percent_reference: f64) -> ChannelInfo {
+    ChannelInfo {
+        name,
+        min,
+        max,
+        percent_reference,
+        is_hue: false,
+    }
+}
+
+const HUE: ChannelInfo = ChannelInfo {
+    name: "hue",
+    min: 0.0,
+    max: 360.0,
+    percent_reference: 360.0,
+    is_hue: true,
+};
+
+const RGB_CHANNELS: [ChannelInfo; 3] = [
+    linear("red", 0.0, 1.0, 1.0),
+    linear("green", 0.0, 1.0, 1.0),
+    linear("blue", 0.0, 1.0, 1.0),
+];
+
+const XYZ_CHANNELS: [ChannelInfo; 3] = [
+    linear("x", 0.0, 1.0, 1.0),
+    linear("y", 0.0, 1.0, 1.0),
+    linear("z", 0.0, 1.0, 1.0),
+];
+
+impl ColorSpace {
+    /// Every color space known to `grass`
+    pub const ALL: &'static [ColorSpace] = &[
+        ColorSpace::Rgb,
+        ColorSpace::Hsl,
+        ColorSpace::Hwb,
+        ColorSpace::Srgb,
+        ColorSpace::SrgbLinear,
+        ColorSpace::DisplayP3,
+        ColorSpace::A98Rgb,
+        ColorSpace::ProphotoRgb,
+        ColorSpace::Rec2020,
+        ColorSpace::XyzD65,
+        ColorSpace::XyzD50,
+        ColorSpace::Lab,
+        ColorSpace::Lch,
+        ColorSpace::Oklab,
+        ColorSpace::Oklch,
+    ];
+
+    /// The name of this space as written in CSS, e.g. `display-p3`
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Rgb => "rgb",
+            Self::Hsl => "hsl",
+            Self::Hwb => "hwb",
+            Self::Srgb => "srgb",
+            Self::SrgbLinear => "srgb-linear",
+            Self::DisplayP3 => "display-p3",
+            Self::A98Rgb => "a98-rgb",
+            Self::ProphotoRgb => "prophoto-rgb",
+            Self::Rec2020 => "rec2020",
+            Self::XyzD65 => "xyz",
+            Self::XyzD50 => "xyz-d50",
+            Self::Lab => "lab",
+            Self::Lch => "lch",
+            Self::Oklab => "oklab",
+            Self::Oklch => "oklch",
+        }
+    }
+
+    /// Look up a space by its name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+
+        if name == "xyz-d65" {
+            return Some(Self::XyzD65);
+        }
+
+        Self::ALL.iter().copied().find(|space| space.name() == name)
+    }
+
+    /// Whether colors in this space are serialized and manipulated using the
+    /// pre-Color 4 behavior
+    pub const fn is_legacy(self) -> bool {
+        matches!(self, Self::Rgb | Self::Hsl | Self::Hwb)
+    }
+
+    /// Whether this space has a hue channel
+    pub const fn is_polar(self) -> bool {
+        matches!(self, Self::Hsl | Self::Hwb | Self::Lch | Self::Oklch)
+    }
+
+    /// Whether colors in this space are written using the `color()` function
+    pub(crate) const fn uses_color_function(self) -> bool {
+        matches!(
+            self,
+            Self::Srgb
+                | Self::SrgbLinear
+                | Self::DisplayP3
+                | Self::A98Rgb
+                | Self::ProphotoRgb
+                | Self::Rec2020
+                | Self::XyzD65
+                | Self::XyzD50
+        )
+    }
+
+    pub(crate) const fn channels(self) -> [ChannelInfo; 3] {
+        match self {
+            Self::Rgb => [
+                linear("red", 0.0, 255.0, 255.0),
+                linear("green", 0.0, 255.0, 255.0),
+                linear("blue", 0.0, 255.0, 255.0),
+            ],
+            Self::Hsl => [
+                HUE,
+                linear("saturation", 0.0, 100.0, 100.0),
+                linear("lightness", 0.0, 100.0, 100.0),
+            ],
+            Self::Hwb => [
+                HUE,
+                linear("whiteness", 0.0, 100.0, 100.0),
+                linear("blackness", 0.0, 100.0, 100.0),
+            ],
+            Self::Srgb
+            | Self::SrgbLinear
+            | Self::DisplayP3
+            | Self::A98Rgb
+            | Self::ProphotoRgb
+            | Self::Rec2020 => RGB_CHANNELS,
+            Self::XyzD65 | Self::XyzD50 => XYZ_CHANNELS,
+            Self::Lab => [
+                linear("lightness", 0.0, 100.0, 100.0),
+                linear("a", -125.0, 125.0, 125.0),
+                linear("b", -125.0, 125.0, 125.0),
+            ],
+            Self::Lch => [
+                linear("lightness", 0.0, 100.0, 100.0),
+                linear("chroma", 0.0, 150.0, 150.0),
+                HUE,
+            ],
+            Self::Oklab => [
+                linear("lightness", 0.0, 1.0, 1.0),
+                linear("a", -0.4, 0.4, 0.4),
+                linear("b", -0.4, 0.4, 0.4),
+            ],
+            Self::Oklch => [
+                linear("lightness", 0.0, 1.0, 1.0),
+                linear("chroma", 0.0, 0.4, 0.4),
+                HUE,
+            ],
+        }
+    }
+
+    /// The space whose gamut colors in this space must fit within, or `None`
+    /// if colors in this space can't be out of gamut
+    pub(crate) const fn gamut_space(self) -> Option<ColorSpace> {
+        match self {
+            Self::Hsl | Self::Hwb => Some(Self::Rgb),
+            Self::XyzD65 | Self::XyzD50 | Self::Lab | Self::Lch | Self::Oklab | Self::Oklch => None,
+            _ => Some(self),
+        }
+    }
+
+    /// Convert channels in this space to channels in `to`
+    pub(crate) fn convert(self, to: ColorSpace, channels: [f64; 3]) -> [f64; 3] {
+        if self == to {
+            return channels;
+        }
+
+        // avoid a round trip through XYZ between spaces which are simple
+        // transformations of one another, which would lose precision
+        match (self, to) {
+            (Self::Lab, Self::Lch) | (Self::Oklab, Self::Oklch) => return to_polar(channels),
+            (Self::Lch, Self::Lab) | (Self::Oklch, Self::Oklab) => return from_polar(channels),
+            _ => {}
+        }
+
+        from_xyz_d65(to, to_xyz_d65(self, channels))
+    }
+}
+
+/// The method used by `color.to-gamut(..)` to bring a color into gamut
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamutMapMethod {
+    /// Clamp each channel to the bounds of the gamut
+    Clip,
+    /// Reduce chroma in the OKLCH space until the color is within a just
+    /// noticeable difference of its clipped form, as described by CSS Color
+    /// Level 4
+    LocalMinde,
+}
+
+impl GamutMapMethod {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "clip" => Some(Self::Clip),
+            "local-minde" => Some(Self::LocalMinde),
+            _ => None,
+        }
+    }
+}
+
+/// How hues are interpolated when mixing colors in a polar space
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HueInterpolationMethod {
+    #[default]
+    Shorter,
+    Longer,
+    Increasing,
+    Decreasing,
+}
+
+impl HueInterpolationMethod {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "shorter" => Some(Self::Shorter),
+            "longer" => Some(Self::Longer),
+            "increasing" => Some(Self::Increasing),
+            "decreasing" => Some(Self::Decreasing),
+            _ => None,
+        }
+    }
+
+    /// Adjust `hue2` so that linearly interpolating from `hue1` travels in
+    /// the direction this method describes
+    pub(crate) fn adjust(self, hue1: f64, hue2: f64) -> f64 {
+        let diff = hue2 - hue1;
+
+        match self {
+            Self::Shorter if diff > 180.0 => hue2 - 360.0,
+            Self::Shorter if diff < -180.0 => hue2 + 360.0,
+            Self::Longer if diff > 0.0 && diff < 180.0 => hue2 - 360.0,
+            Self::Longer if diff > -180.0 && diff <= 0.0 => hue2 + 360.0,
+            Self::Increasing if diff < 0.0 => hue2 + 360.0,
+            Self::Decreasing if diff > 0.0 => hue2 - 360.0,
+            _ => hue2,
+        }
+    }
+}
+
+impl fmt::Display for ColorSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+const SRGB_TO_XYZ: Matrix = [
+    [
+        0.412_390_799_265_959_34,
+        0.357_584_339_383_878,
+        0.180_480_788_401_834_3,
+    ],
+    [
+        0.212_639_005_871_510_27,
+        0.715_168_678_767_756,
+        0.072_192_315_360_733_71,
+    ],
+    [
+        0.019_330_818_715_591_82,
+        0.119_194_779_794_625_98,
+        0.950_532_152_249_660_7,
+    ],
+];
+
+const XYZ_TO_SRGB: Matrix = [
+    [
+        3.240_969_941_904_522_6,
+        -1.537_383_177_570_094,
+        -0.498_610_760_293_003_4,
+    ],
+    [
+        -0.969_243_636_280_879_6,
+        1.875_967_501_507_720_2,
+        0.041_555_057_407_175_59,
+    ],
+    [
+        0.055_630_079_696_993_66,
+        -0.203_976_958_888_976_52,
+        1.056_971_514_242_878_6,
+    ],
+];
+
+const DISPLAY_P3_TO_XYZ: Matrix = [
+    [
+        0.486_570_948_648_216_2,
+        0.265_667_693_169_093_06,
+        0.198_217_285_234_362_5,
+    ],
+    [
+        0.228_974_564_069_748_8,
+        0.691_738_521_836_506_4,
+        0.079_286_914_093_745,
+    ],
+    [0.0, 0.045_113_381_858_902_64, 1.043_944_368_900_976],
+];
+
+const XYZ_TO_DISPLAY_P3: Matrix = [
+    [
+        2.493_496_911_941_425,
+        -0.931_383_617_919_123_9,
+        -0.402_710_784_450_716_84,
+    ],
+    [
+        -0.829_488_969_561_574_7,
+        1.762_664_060_318_346_3,
+        0.023_624_685_841_943_577,
+    ],
+    [
+        0.035_845_830_243_784_47,
+        -0.076_172_389_268_041_82,
+        0.956_884_524_007_687_2,
+    ],
+];
+
+const A98_RGB_TO_XYZ: Matrix = [
+    [
+        0.576_669_042_910_130_5,
+        0.185_558_237_906_546_3,
+        0.188_228_646_234_994_7,
+    ],
+    [
+        0.297_344_975_250_536_05,
+        0.627_363_566_255_466_1,
+        0.075_291_458_493_997_88,
+    ],
+    [
+        0.027_031_361_386_412_34,
+        0.070_688_852_535_827_23,
+        0.991_337_536_837_638_8,
+    ],
+];
+
+const XYZ_TO_A98_RGB: Matrix = [
+    [
+        2.041_587_903_810_746_5,
+        -0.565_006_974_278_859_6,
+        -0.344_731_350_778_329_56,
+    ],
+    [
+        -0.969_243_636_280_879_5,
+        1.875_967_501_507_720_2,
+        0.041_555_057_407_175_57,
+    ],
+    [
+        0.013_444_280_632_031_142,
+        -0.118_362_392_231_018_38,
+        1.015_174_994_391_205_4,
+    ],
+];
+
+const PROPHOTO_RGB_TO_XYZ_D50: Matrix = [
+    [
+        0.797_766_644_900_642_3,
+        0.135_181_297_400_533_08,
+        0.031_347_734_128_392_2,
+    ],
+    [
+        0.288_074_828_819_401_3,
+        0.711_835_234_241_873,
+        0.000_089_936_938_725_64,
+    ],
+    [0.0, 0.0, 0.825_104_602_510_460_2],
+];
+
+const XYZ_D50_TO_PROPHOTO_RGB: Matrix = [
+    [
+        1.345_786_881_647_158_3,
+        -0.255_572_087_379_794_64,
+        -0.051_101_864_975_545_26,
+    ],
+    [
+        -0.544_630_705_124_901_9,
+        1.508_247_742_845_146_8,
+        0.020_527_447_436_421_39,
+    ],
+    [0.0, 0.0, 1.211_967_545_638_945_2],
+];
+
+const REC2020_TO_XYZ: Matrix = [
+    [
+        0.636_958_048_301_291_4,
+        0.144_616_903_586_208_32,
+        0.168_880_975_164_172_1,
+    ],
+    [
+        0.262_700_212_011_267_1,
+        0.677_998_071_518_870_8,
+        0.059_301_716_469_861_96,
+    ],
+    [0.0, 0.028_072_693_049_087_428, 1.060_985_057_710_791],
+];
+
+const XYZ_TO_REC2020: Matrix = [
+    [
+        1.716_651_187_971_268,
+        -0.355_670_783_776_392,
+        -0.253_366_281_373_66,
+    ],
+    [
+        -0.666_684_351_832_489,
+        1.616_481_236_634_939,
+        0.015_768_545_813_911_1,
+    ],
+    [
+        0.017_639_857_445_311,
+        -0.042_770_613_257_809,
+        0.942_103_121_235_474,
+    ],
+];
+
+const D65_TO_D50: Matrix = [
+    [
+        1.047_929_820_840_548_8,
+        0.022_946_793_341_019_088,
+        -0.050_192_229_543_135_57,
+    ],
+    [
+        0.029_627_815_688_159_344,
+        0.990_434_484_573_249,
+        -0.017_073_825_029_385_14,
+    ],
+    [
+        -0.009_243_058_152_591_178,
+        0.015_055_144_896_577_895,
+        0.751_874_289_958_000_8,
+    ],
+];
+
+const D50_TO_D65: Matrix = [
+    [
+        0.955_473_452_704_218_2,
+        -0.023_098_536_874_261_423,
+        0.063_259_308_661_021_7,
+    ],
+    [
+        -0.028_369_706_963_208_136,
+        1.009_995_458_005_822_6,
+        0.021_041_398_966_943_008,
+    ],
+    [
+        0.012_314_001_688_319_899,
+        -0.020_507_696_433_477_912,
+        1.330_365_936_608_075_3,
+    ],
+];
+
+const XYZ_TO_LMS: Matrix = [
+    [
+        0.819_022_437_996_703,
+        0.361_906_260_052_890_4,
+        -0.128_873_781_520_987_9,
+    ],
+    [
+        0.032_983_653_932_388_5,
+        0.929_286_861_586_343_4,
+        0.036_144_666_350_642_4,
+    ],
+    [
+        0.048_177_189_359_624_2,
+        0.264_239_531_752_730_8,
+        0.633_547_828_469_430_9,
+    ],
+];
+
+const LMS_TO_OKLAB: Matrix = [
+    [
+        0.210_454_268_309_314,
+        0.793_617_774_702_305_4,
+        -0.004_072_043_011_619_3,
+    ],
+    [
+        1.977_998_532_431_168_4,
+        -2.428_592_242_048_58,
+        0.450_593_709_617_411,
+    ],
+    [
+        0.025_904_042_465_547_8,
+        0.782_771_712_457_529_6,
+        -0.808_675_754_923_077_4,
+    ],
+];
+
+const OKLAB_TO_LMS: Matrix = [
+    [1.0, 0.396_337_777_376_174_9, 0.215_803_757_309_913_6],
+    [1.0, -0.105_561_345_815_658_6, -0.063_854_172_825_813_3],
+    [1.0, -0.089_484_177_529_811_9, -1.291_485_548_019_409_2],
+];
+
+const LMS_TO_XYZ: Matrix = [
+    [
+        1.226_879_875_845_924_3,
+        -0.557_814_994_460_217_1,
+        0.281_391_045_665_964_7,
+    ],
+    [
+        -0.040_575_745_214_800_8,
+        1.112_286_803_280_317,
+        -0.071_711_058_065_516_4,
+    ],
+    [
+        -0.076_372_936_674_660_1,
+        -0.421_493_332_402_243_2,
+        1.586_924_019_836_781_6,
+    ],
+];
+
+/// The D50 white point used by the Lab space
+const D50_WHITE: [f64; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+const LAB_EPSILON: f64 = 216.0 / 24389.0;
+const LAB_KAPPA: f64 = 24389.0 / 27.0;
+
+fn multiply(matrix: &Matrix, [a, b, c]: [f64; 3]) -> [f64; 3] {
+    [
+        matrix[0][0] * a + matrix[0][1] * b + matrix[0][2] * c,
+        matrix[1][0] * a + matrix[1][1] * b + matrix[1][2] * c,
+        matrix[2][0] * a + matrix[2][1] * b + matrix[2][2] * c,
+    ]
+}
+
+fn map(channels: [f64; 3], f: impl Fn(f64) -> f64) -> [f64; 3] {
+    [f(channels[0]), f(channels[1]), f(channels[2])]
+}
+
+fn srgb_to_linear(channel: f64) -> f64 {
+    let abs = channel.abs();
+    if abs <= 0.04045 {
+        channel / 12.92
+    } else {
+        channel.signum() * ((abs + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn srgb_from_linear(channel: f64) -> f64 {
+    let abs = channel.abs();
+    if abs > 0.003_130_8 {
+        channel.signum() * (1.055 * abs.powf(1.0 / 2.4) - 0.055)
+    } else {
+        channel * 12.92
+    }
+}
+
+fn a98_to_linear(channel: f64) -> f64 {
+    channel.signum() * channel.abs().powf(563.0 / 256.0)
+}
+
+fn a98_from_linear(channel: f64) -> f64 {
+    channel.signum() * channel.abs().powf(256.0 / 563.0)
+}
+
+fn prophoto_to_linear(channel: f64) -> f64 {
+    let abs = channel.abs();
+    if abs <= 16.0 / 512.0 {
+        channel / 16.0
+    } else {
+        channel.signum() * abs.powf(1.8)
+    }
+}
+
+fn prophoto_from_linear(channel: f64) -> f64 {
+    let abs = channel.abs();
+    if abs >= 1.0 / 512.0 {
+        channel.signum() * abs.powf(1.0 / 1.8)
+    } else {
+        channel * 16.0
+    }
+}
+
+const REC2020_ALPHA: f64 = 1.099_296_826_809_44;
+const REC2020_BETA: f64 = 0.018_053_968_510_807;
+
+fn rec2020_to_linear(channel: f64) -> f64 {
+    let abs = channel.abs();
+    if abs < REC2020_BETA * 4.5 {
+        channel / 4.5
+    } else {
+        channel.signum() * ((abs + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+    }
+}
+
+fn rec2020_from_linear(channel: f64) -> f64 {
+    let abs = channel.abs();
+    if abs > REC2020_BETA {
+        channel.signum() * (REC2020_ALPHA * abs.powf(0.45) - (REC2020_ALPHA - 1.0))
+    } else {
+        channel * 4.5
+    }
+}
+
+fn to_polar([lightness, a, b]: [f64; 3]) -> [f64; 3] {
+    let chroma = a.hypot(b);
+    let hue = if chroma.abs() < 1e-11 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    };
+
+    [lightness, chroma, hue]
+}
+
+fn from_polar([lightness, chroma, hue]: [f64; 3]) -> [f64; 3] {
+    let radians = hue.to_radians();
+    [lightness, chroma * radians.cos(), chroma * radians.sin()]
+}
+
+fn hsl_to_srgb([hue, saturation, lightness]: [f64; 3]) -> [f64; 3] {
+    let hue = hue.rem_euclid(360.0);
+    let saturation = saturation / 100.0;
+    let lightness = lightness / 100.0;
+
+    let f = |n: f64| {
+        let k = (n + hue / 30.0) % 12.0;
+        let a = saturation * lightness.min(1.0 - lightness);
+        lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+
+    [f(0.0), f(8.0), f(4.0)]
+}
+
+fn srgb_to_hsl([red, green, blue]: [f64; 3]) -> [f64; 3] {
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let delta = max - min;
+    let lightness = (min + max) / 2.0;
+
+    let mut hue = 0.0;
+    let mut saturation = 0.0;
+
+    if delta != 0.0 {
+        saturation = if lightness == 0.0 || lightness == 1.0 {
+            0.0
+        } else {
+            (max - lightness) / lightness.min(1.0 - lightness)
+        };
+
+        hue = if max == red {
+            (green - blue) / delta + if green < blue { 6.0 } else { 0.0 }
+        } else if max == green {
+            (blue - red) / delta + 2.0
+        } else {
+            (red - green) / delta + 4.0
+        } * 60.0;
+    }
+
+    [hue.rem_euclid(360.0), saturation * 100.0, lightness * 100.0]
+}
+
+fn hwb_to_srgb([hue, whiteness, blackness]: [f64; 3]) -> [f64; 3] {
+    let mut whiteness = whiteness / 100.0;
+    let mut blackness = blackness / 100.0;
+
+    if whiteness + blackness >= 1.0 {
+        let gray = whiteness / (whiteness + blackness);
+        return [gray, gray, gray];
+    }
+
+    if whiteness + blackness > 1.0 {
+        let sum = whiteness + blackness;
+        whiteness /= sum;
+        blackness /= sum;
+    }
+
+    map(hsl_to_srgb([hue, 100.0, 50.0]), |channel| {
+        channel * (1.0 - whiteness - blackness) + whiteness
+    })
+}
+
+fn srgb_to_hwb(srgb: [f64; 3]) -> [f64; 3] {
+    let [hue, ..] = srgb_to_hsl(srgb);
+    let whiteness = srgb[0].min(srgb[1]).min(srgb[2]);
+    let blackness = 1.0 - srgb[0].max(srgb[1]).max(srgb[2]);
+
+    [hue, whiteness * 100.0, blackness * 100.0]
+}
+
+fn lab_to_xyz_d50([lightness, a, b]: [f64; 3]) -> [f64; 3] {
+    let f1 = (lightness + 16.0) / 116.0;
+    let f0 = a / 500.0 + f1;
+    let f2 = f1 - b / 200.0;
+
+    let x = if f0.powi(3) > LAB_EPSILON {
+        f0.powi(3)
+    } else {
+        (116.0 * f0 - 16.0) / LAB_KAPPA
+    };
+    let y = if lightness > LAB_KAPPA * LAB_EPSILON {
+        f1.powi(3)
+    } else {
+        lightness / LAB_KAPPA
+    };
+    let z = if f2.powi(3) > LAB_EPSILON {
+        f2.powi(3)
+    } else {
+        (116.0 * f2 - 16.0) / LAB_KAPPA
+    };
+
+    [x * D50_WHITE[0], y * D50_WHITE[1], z * D50_WHITE[2]]
+}
+
+fn xyz_d50_to_lab(xyz: [f64; 3]) -> [f64; 3] {
+    let f = |i: usize| {
+        let value = xyz[i] / D50_WHITE[i];
+        if value > LAB_EPSILON {
+            value.cbrt()
+        } else {
+            (LAB_KAPPA * value + 16.0) / 116.0
+        }
+    };
+
+    let (f0, f1, f2) = (f(0), f(1), f(2));
+
+    [116.0 * f1 - 16.0, 500.0 * (f0 - f1), 200.0 * (f1 - f2)]
+}
+
+fn oklab_to_xyz(oklab: [f64; 3]) -> [f64; 3] {
+    let lms = map(multiply(&OKLAB_TO_LMS, oklab), |c| c.powi(3));
+    multiply(&LMS_TO_XYZ, lms)
+}
+
+fn xyz_to_oklab(xyz: [f64; 3]) -> [f64; 3] {
+    let lms = map(multiply(&XYZ_TO_LMS, xyz), f64::cbrt);
+    multiply(&LMS_TO_OKLAB, lms)
+}
+
+fn to_xyz_d65(space: ColorSpace, channels: [f64; 3]) -> [f64; 3] {
+    match space {
+        ColorSpace::Rgb => to_xyz_d65(ColorSpace::Srgb, map(channels, |c| c / 255.0)),
+        ColorSpace::Hsl => to_xyz_d65(ColorSpace::Srgb, hsl_to_srgb(channels)),
+        ColorSpace::Hwb => to_xyz_d65(ColorSpace::Srgb, hwb_to_srgb(channels)),
+        ColorSpace::Srgb => multiply(&SRGB_TO_XYZ, map(channels, srgb_to_linear)),
+        ColorSpace::SrgbLinear => multiply(&SRGB_TO_XYZ, channels),
+        ColorSpace::DisplayP3 => multiply(&DISPLAY_P3_TO_XYZ, map(channels, srgb_to_linear)),
+        ColorSpace::A98Rgb => multiply(&A98_RGB_TO_XYZ, map(channels, a98_to_linear)),
+        ColorSpace::ProphotoRgb => multiply(
+            &D50_TO_D65,
+            multiply(&PROPHOTO_RGB_TO_XYZ_D50, map(channels, prophoto_to_linear)),
+        ),
+        ColorSpace::Rec2020 => multiply(&REC2020_TO_XYZ, map(channels, rec2020_to_linear)),
+        ColorSpace::XyzD65 => channels,
+        ColorSpace::XyzD50 => multiply(&D50_TO_D65, channels),
+        ColorSpace::Lab => multiply(&D50_TO_D65, lab_to_xyz_d50(channels)),
+        ColorSpace::Lch => to_xyz_d65(ColorSpace::Lab, from_polar(channels)),
+        ColorSpace::Oklab => oklab_to_xyz(channels),
+        ColorSpace::Oklch => oklab_to_xyz(from_polar(channels)),
+    }
+}
+
+fn from_xyz_d65(space: ColorSpace, xyz: [f64; 3]) -> [f64; 3] {
+    match space {
+        ColorSpace::Rgb => map(from_xyz_d65(ColorSpace::Srgb, xyz), |c| c * 255.0),
+        ColorSpace::Hsl => srgb_to_hsl(from_xyz_d65(ColorSpace::Srgb, xyz)),
+        ColorSpace::Hwb => srgb_to_hwb(from_xyz_d65(ColorSpace::Srgb, xyz)),
+        ColorSpace::Srgb => map(multiply(&XYZ_TO_SRGB, xyz), srgb_from_linear),
+        ColorSpace::SrgbLinear => multiply(&XYZ_TO_SRGB, xyz),
+        ColorSpace::DisplayP3 => map(multiply(&XYZ_TO_DISPLAY_P3, xyz), srgb_from_linear),
+        ColorSpace::A98Rgb => map(multiply(&XYZ_TO_A98_RGB, xyz), a98_from_linear),
+        ColorSpace::ProphotoRgb => map(
+            multiply(&XYZ_D50_TO_PROPHOTO_RGB, multiply(&D65_TO_D50, xyz)),
+            prophoto_from_linear,
+        ),
+        ColorSpace::Rec2020 => map(multiply(&XYZ_TO_REC2020, xyz), rec2020_from_linear),
+        ColorSpace::XyzD65 => xyz,
+        ColorSpace::XyzD50 => multiply(&D65_TO_D50, xyz),
+        ColorSpace::Lab => xyz_d50_to_lab(multiply(&D65_TO_D50, xyz)),
+        ColorSpace::Lch => to_polar(from_xyz_d65(ColorSpace::Lab, xyz)),
+        ColorSpace::Oklab => xyz_to_oklab(xyz),
+        ColorSpace::Oklch => to_polar(xyz_to_oklab(xyz)),
+    }
+}
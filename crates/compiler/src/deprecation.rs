@@ -0,0 +1,80 @@
+use std::fmt::{self, Display};
+
+/// A deprecated Sass feature which `grass` warns about when it is used.
+///
+/// Each deprecation can be individually silenced using
+/// [`crate::Options::silence_deprecation`] or turned into a hard error using
+/// [`crate::Options::fatal_deprecation`]. The identifiers used on the command
+/// line and returned by [`Deprecation::id`] match those used by `dart-sass`.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Deprecation {
+    /// Passing a string directly to `meta.call()`.
+    CallString,
+
+    /// Using `@elseif` rather than `@else if`.
+    Elseif,
+
+    /// Declaring new variables with `!global`.
+    NewGlobal,
+
+    /// Using `/` for division outside of `calc()`.
+    SlashDiv,
+
+    /// Using the `feature-exists()` function.
+    FeatureExists,
+
+    /// Using the global color adjustment functions such as `lighten()` and
+    /// `darken()`.
+    ColorFunctions,
+
+    /// Using `@import` to load stylesheets.
+    Import,
+
+    /// Calling built-in functions from the global namespace rather than
+    /// through their `sass:` module.
+    GlobalBuiltin,
+}
+
+impl Deprecation {
+    /// Every deprecation known to `grass`.
+    pub const ALL: &'static [Deprecation] = &[
+        Deprecation::CallString,
+        Deprecation::Elseif,
+        Deprecation::NewGlobal,
+        Deprecation::SlashDiv,
+        Deprecation::FeatureExists,
+        Deprecation::ColorFunctions,
+        Deprecation::Import,
+        Deprecation::GlobalBuiltin,
+    ];
+
+    /// The name used to refer to this deprecation on the command line and in
+    /// warning messages, e.g. `slash-div`.
+    #[must_use]
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::CallString => "call-string",
+            Self::Elseif => "elseif",
+            Self::NewGlobal => "new-global",
+            Self::SlashDiv => "slash-div",
+            Self::FeatureExists => "feature-exists",
+            Self::ColorFunctions => "color-functions",
+            Self::Import => "import",
+            Self::GlobalBuiltin => "global-builtin",
+        }
+    }
+
+    /// Look up a deprecation by its [`Deprecation::id`]
+    #[must_use]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|d| d.id() == id)
+    }
+}
+
+impl Display for Deprecation {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
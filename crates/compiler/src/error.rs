@@ -8,6 +8,8 @@ use std::{
 
 use codemap::{Span, SpanLoc};
 
+use crate::serializer::serialize_quoted_string;
+
 pub type SassResult<T> = Result<T, Box<SassError>>;
 
 /// `SassError`s can be either a structured error specific to `grass` or an
@@ -49,6 +51,43 @@ impl SassError {
         }
     }
 
+    /// Render this error as a stylesheet which displays the error message and
+    /// its location at the top of the page.
+    ///
+    /// This mirrors the CSS `dart-sass` emits when compilation fails and
+    /// `--error-css` is enabled, allowing a failed build to surface its error
+    /// in the browser rather than silently leaving stale CSS in place.
+    #[must_use]
+    pub fn to_css_string(&self) -> String {
+        // Don't render the error message in unicode for the inline comment, since
+        // we can't be sure the user's default encoding is UTF-8.
+        let mut ascii = self.clone();
+        if let SassErrorKind::ParseError { unicode, .. } = &mut ascii.kind {
+            *unicode = false;
+        }
+
+        // Replace comment-closing sequences in the error message with
+        // visually-similar sequences that won't actually close the comment.
+        let comment_message = ascii.to_string().replace("*/", "*∕").replace("\r\n", "\n");
+
+        // For the string, render all non-ASCII characters as escape sequences so
+        // that they'll show up even if the HTTP headers are set incorrectly.
+        let mut string_message = String::new();
+        for c in serialize_quoted_string(self.to_string().trim_end()).chars() {
+            if c.is_ascii() {
+                string_message.push(c);
+            } else {
+                string_message.push_str(&format!("\\{:x} ", c as u32));
+            }
+        }
+
+        format!(
+            "/* {} */\n\nbody::before {{\n  font-family: \"Source Code Pro\", \"SF Mono\", Monaco, Inconsolata, \"Fira Mono\",\n      \"Droid Sans Mono\", monospace, monospace;\n  white-space: pre;\n  display: block;\n  padding: 1em;\n  margin-bottom: 1em;\n  border-bottom: 2px solid black;\n  content: {};\n}}\n",
+            comment_message.trim_end().split('\n').collect::<Vec<_>>().join("\n * "),
+            string_message,
+        )
+    }
+
     pub(crate) fn raw(self) -> (String, Span) {
         match self.kind {
             SassErrorKind::Raw(string, span) => (string, span),
@@ -0,0 +1,922 @@
+use std::collections::HashMap;
+
+use Browser::{Android, Chrome, Edge, Firefox, IosSafari, Opera, Safari, Samsung};
+
+/// A browser included in the bundled compatibility data
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) enum Browser {
+    Chrome,
+    Edge,
+    Firefox,
+    Safari,
+    IosSafari,
+    Opera,
+    Samsung,
+    Android,
+}
+
+impl Browser {
+    const ALL: [Self; 8] = [
+        Self::Chrome,
+        Self::Edge,
+        Self::Firefox,
+        Self::Safari,
+        Self::IosSafari,
+        Self::Opera,
+        Self::Samsung,
+        Self::Android,
+    ];
+
+    /// Looks up a browser by its browserslist name or alias
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "chrome" => Self::Chrome,
+            "edge" => Self::Edge,
+            "firefox" | "ff" => Self::Firefox,
+            "safari" => Self::Safari,
+            "ios_saf" | "ios" => Self::IosSafari,
+            "opera" => Self::Opera,
+            "samsung" => Self::Samsung,
+            "android" => Self::Android,
+            _ => return None,
+        })
+    }
+
+    /// The major versions of this browser, newest first, as of the bundled
+    /// compatibility data
+    fn major_versions(self) -> Vec<u32> {
+        match self {
+            Self::Safari | Self::IosSafari => vec![26, 18, 17, 16, 15, 14, 13, 12, 11, 10, 9, 8, 7],
+            Self::Chrome | Self::Android => (4..=141).rev().collect(),
+            Self::Edge => (12..=141).rev().collect(),
+            Self::Firefox => (2..=144).rev().collect(),
+            Self::Opera => (9..=122).rev().collect(),
+            Self::Samsung => (4..=28).rev().collect(),
+        }
+    }
+}
+
+/// A browser version, as its major and minor version numbers
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) struct Version(u32, u32);
+
+impl Version {
+    /// Used for browsers which still require a prefix in their latest version
+    const ALWAYS: Self = Self(u32::MAX, 0);
+
+    fn parse(version: &str) -> Option<Self> {
+        let mut parts = version.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = match parts.next() {
+            Some(minor) => minor.parse().ok()?,
+            None => 0,
+        };
+
+        // Patch versions, as in `android 4.4.4`, don't affect prefixing
+        if parts.any(|patch| patch.parse::<u32>().is_err()) {
+            return None;
+        }
+
+        Some(Self(major, minor))
+    }
+}
+
+/// A vendor prefix which may be added to the output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Prefix {
+    Webkit,
+    Moz,
+}
+
+/// A feature which some browsers only support with a vendor prefix
+pub(super) struct Feature {
+    /// The standard, unprefixed name of this feature
+    pub name: &'static str,
+
+    /// The name this feature has when prefixed
+    pub prefixed: &'static str,
+
+    pub prefix: Prefix,
+
+    /// The last version of each browser which requires `prefixed`
+    pub prefixed_until: &'static [(Browser, Version)],
+}
+
+macro_rules! feature {
+    ($name:literal, $prefixed:literal, $prefix:ident, $prefixed_until:expr) => {
+        Feature {
+            name: $name,
+            prefixed: $prefixed,
+            prefix: Prefix::$prefix,
+            prefixed_until: $prefixed_until,
+        }
+    };
+}
+
+const ANIMATION_WEBKIT: &[(Browser, Version)] = &[
+    (Chrome, Version(42, 0)),
+    (Safari, Version(8, 0)),
+    (IosSafari, Version(8, 4)),
+    (Opera, Version(29, 0)),
+    (Android, Version(4, 4)),
+];
+
+const ANIMATION_MOZ: &[(Browser, Version)] = &[(Firefox, Version(15, 0))];
+
+const TRANSFORM_WEBKIT: &[(Browser, Version)] = &[
+    (Chrome, Version(35, 0)),
+    (Safari, Version(8, 0)),
+    (IosSafari, Version(8, 4)),
+    (Opera, Version(22, 0)),
+    (Android, Version(4, 4)),
+];
+
+const TRANSFORM_MOZ: &[(Browser, Version)] = &[(Firefox, Version(15, 0))];
+
+/// Browsers which support the final flexbox syntax only with a prefix
+const FLEX_WEBKIT: &[(Browser, Version)] = &[
+    (Chrome, Version(28, 0)),
+    (Safari, Version(8, 0)),
+    (IosSafari, Version(8, 4)),
+    (Opera, Version(16, 0)),
+];
+
+const MASK_WEBKIT: &[(Browser, Version)] = &[
+    (Chrome, Version(119, 0)),
+    (Edge, Version(119, 0)),
+    (Safari, Version(15, 3)),
+    (IosSafari, Version(15, 3)),
+    (Opera, Version(105, 0)),
+    (Samsung, Version(24, 0)),
+    (Android, Version(119, 0)),
+];
+
+const INTRINSIC_SIZE_WEBKIT: &[(Browser, Version)] = &[
+    (Chrome, Version(45, 0)),
+    (Safari, Version(10, 1)),
+    (IosSafari, Version(10, 3)),
+    (Opera, Version(32, 0)),
+    (Android, Version(4, 4)),
+];
+
+const INTRINSIC_SIZE_MOZ: &[(Browser, Version)] = &[(Firefox, Version(65, 0))];
+
+/// Properties which require a prefix
+pub(super) const PROPERTIES: &[Feature] = &[
+    feature!(
+        "align-content",
+        "-webkit-align-content",
+        Webkit,
+        FLEX_WEBKIT
+    ),
+    feature!("align-items", "-webkit-align-items", Webkit, FLEX_WEBKIT),
+    feature!("align-self", "-webkit-align-self", Webkit, FLEX_WEBKIT),
+    feature!("animation", "-webkit-animation", Webkit, ANIMATION_WEBKIT),
+    feature!("animation", "-moz-animation", Moz, ANIMATION_MOZ),
+    feature!(
+        "animation-delay",
+        "-webkit-animation-delay",
+        Webkit,
+        ANIMATION_WEBKIT
+    ),
+    feature!(
+        "animation-delay",
+        "-moz-animation-delay",
+        Moz,
+        ANIMATION_MOZ
+    ),
+    feature!(
+        "animation-direction",
+        "-webkit-animation-direction",
+        Webkit,
+        ANIMATION_WEBKIT
+    ),
+    feature!(
+        "animation-direction",
+        "-moz-animation-direction",
+        Moz,
+        ANIMATION_MOZ
+    ),
+    feature!(
+        "animation-duration",
+        "-webkit-animation-duration",
+        Webkit,
+        ANIMATION_WEBKIT
+    ),
+    feature!(
+        "animation-duration",
+        "-moz-animation-duration",
+        Moz,
+        ANIMATION_MOZ
+    ),
+    feature!(
+        "animation-fill-mode",
+        "-webkit-animation-fill-mode",
+        Webkit,
+        ANIMATION_WEBKIT
+    ),
+    feature!(
+        "animation-fill-mode",
+        "-moz-animation-fill-mode",
+        Moz,
+        ANIMATION_MOZ
+    ),
+    feature!(
+        "animation-iteration-count",
+        "-webkit-animation-iteration-count",
+        Webkit,
+        ANIMATION_WEBKIT
+    ),
+    feature!(
+        "animation-iteration-count",
+        "-moz-animation-iteration-count",
+        Moz,
+        ANIMATION_MOZ
+    ),
+    feature!(
+        "animation-name",
+        "-webkit-animation-name",
+        Webkit,
+        ANIMATION_WEBKIT
+    ),
+    feature!("animation-name", "-moz-animation-name", Moz, ANIMATION_MOZ),
+    feature!(
+        "animation-play-state",
+        "-webkit-animation-play-state",
+        Webkit,
+        ANIMATION_WEBKIT
+    ),
+    feature!(
+        "animation-play-state",
+        "-moz-animation-play-state",
+        Moz,
+        ANIMATION_MOZ
+    ),
+    feature!(
+        "animation-timing-function",
+        "-webkit-animation-timing-function",
+        Webkit,
+        ANIMATION_WEBKIT
+    ),
+    feature!(
+        "animation-timing-function",
+        "-moz-animation-timing-function",
+        Moz,
+        ANIMATION_MOZ
+    ),
+    feature!(
+        "appearance",
+        "-webkit-appearance",
+        Webkit,
+        &[
+            (Chrome, Version(83, 0)),
+            (Edge, Version(83, 0)),
+            (Safari, Version(15, 3)),
+            (IosSafari, Version(15, 3)),
+            (Opera, Version(69, 0)),
+            (Samsung, Version(13, 0)),
+            (Android, Version(83, 0))
+        ]
+    ),
+    feature!(
+        "appearance",
+        "-moz-appearance",
+        Moz,
+        &[(Firefox, Version(79, 0))]
+    ),
+    feature!(
+        "backdrop-filter",
+        "-webkit-backdrop-filter",
+        Webkit,
+        &[(Safari, Version(17, 6)), (IosSafari, Version(17, 6))]
+    ),
+    feature!(
+        "backface-visibility",
+        "-webkit-backface-visibility",
+        Webkit,
+        &[
+            (Chrome, Version(35, 0)),
+            (Safari, Version(15, 3)),
+            (IosSafari, Version(15, 3)),
+            (Opera, Version(22, 0)),
+            (Android, Version(4, 4))
+        ]
+    ),
+    feature!(
+        "box-decoration-break",
+        "-webkit-box-decoration-break",
+        Webkit,
+        &[
+            (Chrome, Version(129, 0)),
+            (Edge, Version(129, 0)),
+            (Safari, Version::ALWAYS),
+            (IosSafari, Version::ALWAYS),
+            (Opera, Version(114, 0)),
+            (Samsung, Version(27, 0)),
+            (Android, Version(129, 0))
+        ]
+    ),
+    feature!(
+        "box-sizing",
+        "-webkit-box-sizing",
+        Webkit,
+        &[
+            (Safari, Version(5, 0)),
+            (IosSafari, Version(4, 3)),
+            (Android, Version(3, 0))
+        ]
+    ),
+    feature!(
+        "box-sizing",
+        "-moz-box-sizing",
+        Moz,
+        &[(Firefox, Version(28, 0))]
+    ),
+    feature!(
+        "clip-path",
+        "-webkit-clip-path",
+        Webkit,
+        &[
+            (Chrome, Version(54, 0)),
+            (Safari, Version(13, 0)),
+            (IosSafari, Version(13, 0)),
+            (Opera, Version(41, 0)),
+            (Samsung, Version(5, 0)),
+            (Android, Version(54, 0))
+        ]
+    ),
+    feature!("flex", "-webkit-flex", Webkit, FLEX_WEBKIT),
+    feature!("flex-basis", "-webkit-flex-basis", Webkit, FLEX_WEBKIT),
+    feature!(
+        "flex-direction",
+        "-webkit-flex-direction",
+        Webkit,
+        FLEX_WEBKIT
+    ),
+    feature!("flex-flow", "-webkit-flex-flow", Webkit, FLEX_WEBKIT),
+    feature!("flex-grow", "-webkit-flex-grow", Webkit, FLEX_WEBKIT),
+    feature!("flex-shrink", "-webkit-flex-shrink", Webkit, FLEX_WEBKIT),
+    feature!("flex-wrap", "-webkit-flex-wrap", Webkit, FLEX_WEBKIT),
+    feature!(
+        "hyphens",
+        "-webkit-hyphens",
+        Webkit,
+        &[(Safari, Version(16, 6)), (IosSafari, Version(16, 6))]
+    ),
+    feature!("hyphens", "-moz-hyphens", Moz, &[(Firefox, Version(42, 0))]),
+    feature!(
+        "justify-content",
+        "-webkit-justify-content",
+        Webkit,
+        FLEX_WEBKIT
+    ),
+    feature!("mask", "-webkit-mask", Webkit, MASK_WEBKIT),
+    feature!("mask-clip", "-webkit-mask-clip", Webkit, MASK_WEBKIT),
+    feature!(
+        "mask-composite",
+        "-webkit-mask-composite",
+        Webkit,
+        MASK_WEBKIT
+    ),
+    feature!("mask-image", "-webkit-mask-image", Webkit, MASK_WEBKIT),
+    feature!("mask-origin", "-webkit-mask-origin", Webkit, MASK_WEBKIT),
+    feature!(
+        "mask-position",
+        "-webkit-mask-position",
+        Webkit,
+        MASK_WEBKIT
+    ),
+    feature!("mask-repeat", "-webkit-mask-repeat", Webkit, MASK_WEBKIT),
+    feature!("mask-size", "-webkit-mask-size", Webkit, MASK_WEBKIT),
+    feature!("order", "-webkit-order", Webkit, FLEX_WEBKIT),
+    feature!(
+        "perspective",
+        "-webkit-perspective",
+        Webkit,
+        TRANSFORM_WEBKIT
+    ),
+    feature!("perspective", "-moz-perspective", Moz, TRANSFORM_MOZ),
+    feature!(
+        "perspective-origin",
+        "-webkit-perspective-origin",
+        Webkit,
+        TRANSFORM_WEBKIT
+    ),
+    feature!(
+        "perspective-origin",
+        "-moz-perspective-origin",
+        Moz,
+        TRANSFORM_MOZ
+    ),
+    feature!(
+        "print-color-adjust",
+        "-webkit-print-color-adjust",
+        Webkit,
+        &[
+            (Chrome, Version::ALWAYS),
+            (Edge, Version::ALWAYS),
+            (Safari, Version(15, 3)),
+            (IosSafari, Version(15, 3)),
+            (Opera, Version::ALWAYS),
+            (Samsung, Version::ALWAYS),
+            (Android, Version::ALWAYS)
+        ]
+    ),
+    feature!(
+        "tab-size",
+        "-moz-tab-size",
+        Moz,
+        &[(Firefox, Version(90, 0))]
+    ),
+    feature!(
+        "text-size-adjust",
+        "-webkit-text-size-adjust",
+        Webkit,
+        &[(IosSafari, Version::ALWAYS)]
+    ),
+    feature!("transform", "-webkit-transform", Webkit, TRANSFORM_WEBKIT),
+    feature!("transform", "-moz-transform", Moz, TRANSFORM_MOZ),
+    feature!(
+        "transform-origin",
+        "-webkit-transform-origin",
+        Webkit,
+        TRANSFORM_WEBKIT
+    ),
+    feature!(
+        "transform-origin",
+        "-moz-transform-origin",
+        Moz,
+        TRANSFORM_MOZ
+    ),
+    feature!(
+        "transform-style",
+        "-webkit-transform-style",
+        Webkit,
+        TRANSFORM_WEBKIT
+    ),
+    feature!(
+        "transform-style",
+        "-moz-transform-style",
+        Moz,
+        TRANSFORM_MOZ
+    ),
+    feature!("transition", "-webkit-transition", Webkit, TRANSFORM_WEBKIT),
+    feature!("transition", "-moz-transition", Moz, TRANSFORM_MOZ),
+    feature!(
+        "transition-delay",
+        "-webkit-transition-delay",
+        Webkit,
+        TRANSFORM_WEBKIT
+    ),
+    feature!(
+        "transition-delay",
+        "-moz-transition-delay",
+        Moz,
+        TRANSFORM_MOZ
+    ),
+    feature!(
+        "transition-duration",
+        "-webkit-transition-duration",
+        Webkit,
+        TRANSFORM_WEBKIT
+    ),
+    feature!(
+        "transition-duration",
+        "-moz-transition-duration",
+        Moz,
+        TRANSFORM_MOZ
+    ),
+    feature!(
+        "transition-property",
+        "-webkit-transition-property",
+        Webkit,
+        TRANSFORM_WEBKIT
+    ),
+    feature!(
+        "transition-property",
+        "-moz-transition-property",
+        Moz,
+        TRANSFORM_MOZ
+    ),
+    feature!(
+        "transition-timing-function",
+        "-webkit-transition-timing-function",
+        Webkit,
+        TRANSFORM_WEBKIT
+    ),
+    feature!(
+        "transition-timing-function",
+        "-moz-transition-timing-function",
+        Moz,
+        TRANSFORM_MOZ
+    ),
+    feature!(
+        "user-select",
+        "-webkit-user-select",
+        Webkit,
+        &[
+            (Chrome, Version(53, 0)),
+            (Safari, Version::ALWAYS),
+            (IosSafari, Version::ALWAYS),
+            (Opera, Version(40, 0)),
+            (Samsung, Version(5, 0)),
+            (Android, Version(53, 0))
+        ]
+    ),
+    feature!(
+        "user-select",
+        "-moz-user-select",
+        Moz,
+        &[(Firefox, Version(68, 0))]
+    ),
+];
+
+/// Properties which accept intrinsic sizing keywords
+const SIZING_PROPERTIES: &[&str] = &[
+    "block-size",
+    "height",
+    "inline-size",
+    "max-block-size",
+    "max-height",
+    "max-inline-size",
+    "max-width",
+    "min-block-size",
+    "min-height",
+    "min-inline-size",
+    "min-width",
+    "width",
+];
+
+/// Keywords and functions which require a prefix, along with the properties
+/// they're restricted to, or `None` if they may be used in any property
+///
+/// Functions are written with a trailing `(`.
+pub(super) const VALUES: &[(Option<&[&str]>, Feature)] = &[
+    (
+        Some(&["display"]),
+        feature!("flex", "-webkit-flex", Webkit, FLEX_WEBKIT),
+    ),
+    (
+        Some(&["display"]),
+        feature!("inline-flex", "-webkit-inline-flex", Webkit, FLEX_WEBKIT),
+    ),
+    (
+        Some(&["position"]),
+        feature!(
+            "sticky",
+            "-webkit-sticky",
+            Webkit,
+            &[(Safari, Version(12, 1)), (IosSafari, Version(12, 5))]
+        ),
+    ),
+    (
+        Some(SIZING_PROPERTIES),
+        feature!(
+            "fit-content",
+            "-webkit-fit-content",
+            Webkit,
+            INTRINSIC_SIZE_WEBKIT
+        ),
+    ),
+    (
+        Some(SIZING_PROPERTIES),
+        feature!(
+            "fit-content",
+            "-moz-fit-content",
+            Moz,
+            &[(Firefox, Version(93, 0))]
+        ),
+    ),
+    (
+        Some(SIZING_PROPERTIES),
+        feature!(
+            "max-content",
+            "-webkit-max-content",
+            Webkit,
+            INTRINSIC_SIZE_WEBKIT
+        ),
+    ),
+    (
+        Some(SIZING_PROPERTIES),
+        feature!("max-content", "-moz-max-content", Moz, INTRINSIC_SIZE_MOZ),
+    ),
+    (
+        Some(SIZING_PROPERTIES),
+        feature!(
+            "min-content",
+            "-webkit-min-content",
+            Webkit,
+            INTRINSIC_SIZE_WEBKIT
+        ),
+    ),
+    (
+        Some(SIZING_PROPERTIES),
+        feature!("min-content", "-moz-min-content", Moz, INTRINSIC_SIZE_MOZ),
+    ),
+    (
+        None,
+        feature!(
+            "image-set(",
+            "-webkit-image-set(",
+            Webkit,
+            &[
+                (Chrome, Version(112, 0)),
+                (Edge, Version(112, 0)),
+                (Safari, Version(16, 6)),
+                (IosSafari, Version(16, 6)),
+                (Opera, Version(98, 0)),
+                (Samsung, Version(22, 0)),
+                (Android, Version(112, 0))
+            ]
+        ),
+    ),
+];
+
+/// Pseudo-classes and pseudo-elements which require a prefix
+pub(super) const PSEUDOS: &[Feature] = &[
+    feature!(
+        "any-link",
+        "-webkit-any-link",
+        Webkit,
+        &[
+            (Chrome, Version(64, 0)),
+            (Safari, Version(8, 0)),
+            (IosSafari, Version(8, 4)),
+            (Opera, Version(51, 0)),
+            (Android, Version(64, 0))
+        ]
+    ),
+    feature!(
+        "any-link",
+        "-moz-any-link",
+        Moz,
+        &[(Firefox, Version(49, 0))]
+    ),
+    feature!(
+        "file-selector-button",
+        "-webkit-file-upload-button",
+        Webkit,
+        &[
+            (Chrome, Version(88, 0)),
+            (Edge, Version(88, 0)),
+            (Safari, Version(14, 0)),
+            (IosSafari, Version(14, 0)),
+            (Opera, Version(74, 0)),
+            (Samsung, Version(15, 0)),
+            (Android, Version(88, 0))
+        ]
+    ),
+    feature!(
+        "fullscreen",
+        "-webkit-full-screen",
+        Webkit,
+        &[
+            (Chrome, Version(70, 0)),
+            (Edge, Version(78, 0)),
+            (Safari, Version(16, 3)),
+            (IosSafari, Version(16, 3)),
+            (Opera, Version(57, 0)),
+            (Samsung, Version(10, 0)),
+            (Android, Version(70, 0))
+        ]
+    ),
+    feature!(
+        "fullscreen",
+        "-moz-full-screen",
+        Moz,
+        &[(Firefox, Version(63, 0))]
+    ),
+    feature!(
+        "placeholder",
+        "-webkit-input-placeholder",
+        Webkit,
+        &[
+            (Chrome, Version(56, 0)),
+            (Safari, Version(10, 0)),
+            (IosSafari, Version(10, 2)),
+            (Opera, Version(43, 0)),
+            (Samsung, Version(6, 0)),
+            (Android, Version(56, 0))
+        ]
+    ),
+    feature!(
+        "placeholder",
+        "-moz-placeholder",
+        Moz,
+        &[(Firefox, Version(50, 0))]
+    ),
+    feature!(
+        "read-only",
+        "-moz-read-only",
+        Moz,
+        &[(Firefox, Version(77, 0))]
+    ),
+    feature!(
+        "read-write",
+        "-moz-read-write",
+        Moz,
+        &[(Firefox, Version(77, 0))]
+    ),
+    feature!(
+        "selection",
+        "-moz-selection",
+        Moz,
+        &[(Firefox, Version(61, 0))]
+    ),
+];
+
+/// At-rules which require a prefix
+pub(super) const AT_RULES: &[Feature] = &[
+    feature!("keyframes", "-webkit-keyframes", Webkit, ANIMATION_WEBKIT),
+    feature!("keyframes", "-moz-keyframes", Moz, ANIMATION_MOZ),
+];
+
+/// The oldest version of a browser that is targeted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bound {
+    version: Version,
+    /// Whether `version` itself is targeted, or only those after it
+    inclusive: bool,
+}
+
+impl Bound {
+    fn is_before(self, other: Self) -> bool {
+        self.version < other.version || (self.version == other.version && self.inclusive)
+    }
+}
+
+/// The browsers which the output should support, as parsed from a
+/// [browserslist](https://github.com/browserslist/browserslist)-style query
+///
+/// See [`crate::Options::browser_targets`] for additional information
+///
+/// ```
+/// # use grass_compiler as grass;
+/// let targets = grass::BrowserTargets::parse("last 2 versions, safari >= 14").unwrap();
+///
+/// assert!(grass::BrowserTargets::parse("> 1%").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BrowserTargets {
+    oldest: HashMap<Browser, Bound>,
+}
+
+impl BrowserTargets {
+    /// The query used for `defaults`, as of the bundled compatibility data
+    const DEFAULTS: &'static str =
+        "chrome >= 109, edge >= 130, firefox >= 115, safari >= 16, ios_saf >= 16, \
+         opera >= 110, samsung >= 25, android >= 130";
+
+    /// Parses a comma-separated list of queries, each one of:
+    ///
+    /// - `defaults`
+    /// - `last N versions`
+    /// - `last N <browser> versions`
+    /// - `<browser> <version>`
+    /// - `<browser> <version>-<version>`
+    /// - `<browser> >= <version>`, as well as `>`, `<=`, and `<`
+    ///
+    /// Queries may also be separated by `or`. Queries based on usage
+    /// statistics, as well as `not` queries, aren't supported, and result in
+    /// an error describing the unsupported part of the query.
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut targets = Self {
+            oldest: HashMap::new(),
+        };
+
+        let query = query.to_ascii_lowercase();
+
+        for part in query
+            .split(',')
+            .flat_map(|part| part.split(" or "))
+            .map(str::trim)
+        {
+            if part.is_empty() {
+                continue;
+            }
+
+            if !targets.add_query(part) {
+                return Err(format!("Unsupported browser query \"{}\".", part));
+            }
+        }
+
+        Ok(targets)
+    }
+
+    fn add_query(&mut self, query: &str) -> bool {
+        if query == "defaults" {
+            return Self::DEFAULTS
+                .split(',')
+                .all(|part| self.add_query(part.trim()));
+        }
+
+        let words: Vec<&str> = query.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["last", count, "versions" | "version"] => match count.parse::<usize>() {
+                Ok(count) if count > 0 => {
+                    for browser in Browser::ALL {
+                        self.add_last_versions(browser, count);
+                    }
+                    true
+                }
+                _ => false,
+            },
+            ["last", count, browser, "versions" | "version"] => {
+                match (count.parse::<usize>(), Browser::from_name(browser)) {
+                    (Ok(count), Some(browser)) if count > 0 => {
+                        self.add_last_versions(browser, count);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            [browser, operator, version] => {
+                let (browser, version) =
+                    match (Browser::from_name(browser), Version::parse(version)) {
+                        (Some(browser), Some(version)) => (browser, version),
+                        _ => return false,
+                    };
+
+                let bound = match *operator {
+                    ">=" => Bound {
+                        version,
+                        inclusive: true,
+                    },
+                    ">" => Bound {
+                        version,
+                        inclusive: false,
+                    },
+                    "<=" | "<" => Bound {
+                        version: Version(0, 0),
+                        inclusive: true,
+                    },
+                    _ => return false,
+                };
+
+                self.add(browser, bound);
+                true
+            }
+            [browser, version] => {
+                let browser = match Browser::from_name(browser) {
+                    Some(browser) => browser,
+                    None => return false,
+                };
+
+                // A range such as `safari 14-15` targets everything from its
+                // first version
+                let oldest = version.split('-').next().unwrap_or(version);
+
+                match Version::parse(oldest) {
+                    Some(version) => {
+                        self.add(
+                            browser,
+                            Bound {
+                                version,
+                                inclusive: true,
+                            },
+                        );
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn add_last_versions(&mut self, browser: Browser, count: usize) {
+        let versions = browser.major_versions();
+        let oldest = versions[count.min(versions.len()) - 1];
+
+        self.add(
+            browser,
+            Bound {
+                version: Version(oldest, 0),
+                inclusive: true,
+            },
+        );
+    }
+
+    fn add(&mut self, browser: Browser, bound: Bound) {
+        let oldest = self.oldest.entry(browser).or_insert(bound);
+
+        if bound.is_before(*oldest) {
+            *oldest = bound;
+        }
+    }
+
+    /// Whether any targeted browser requires the prefixed form of `feature`
+    pub(super) fn requires(&self, feature: &Feature) -> bool {
+        feature
+            .prefixed_until
+            .iter()
+            .any(
+                |&(browser, last_prefixed)| match self.oldest.get(&browser) {
+                    Some(oldest) if oldest.inclusive => oldest.version <= last_prefixed,
+                    Some(oldest) => oldest.version < last_prefixed,
+                    None => false,
+                },
+            )
+    }
+}
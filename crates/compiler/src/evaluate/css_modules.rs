@@ -0,0 +1,386 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem,
+};
+
+use codemap::Span;
+
+use crate::{
+    ast::CssStmt,
+    common::{unvendor, QuoteKind},
+    error::SassResult,
+    lexer::Lexer,
+    selector::{
+        ComplexSelector, ComplexSelectorComponent, CompoundSelector, ExtendedSelector,
+        SelectorList, SelectorParser, SimpleSelector,
+    },
+    utils::{fnv1a, FNV_OFFSET_BASIS},
+    value::Value,
+};
+
+use super::purge::unescape;
+
+/// The output of compiling a stylesheet as a CSS Module
+///
+/// See [`crate::from_path_as_css_module`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CssModule {
+    /// The compiled CSS, using the generated names
+    pub css: String,
+    /// Maps each local class and `@keyframes` name, as written, to the name
+    /// generated for it
+    pub exports: BTreeMap<String, String>,
+}
+
+impl CssModule {
+    /// Serializes [`Self::exports`] as a JSON object, as expected by bundlers
+    /// which load CSS Modules
+    #[must_use]
+    pub fn exports_json(&self) -> String {
+        fn write_json_string(buffer: &mut String, s: &str) {
+            buffer.push('"');
+
+            for c in s.chars() {
+                match c {
+                    '"' => buffer.push_str("\\\""),
+                    '\\' => buffer.push_str("\\\\"),
+                    '\n' => buffer.push_str("\\n"),
+                    '\r' => buffer.push_str("\\r"),
+                    '\t' => buffer.push_str("\\t"),
+                    c if c.is_control() => buffer.push_str(&format!("\\u{:04x}", c as u32)),
+                    c => buffer.push(c),
+                }
+            }
+
+            buffer.push('"');
+        }
+
+        if self.exports.is_empty() {
+            return "{}".to_owned();
+        }
+
+        let mut json = String::from("{\n");
+
+        for (idx, (name, generated)) in self.exports.iter().enumerate() {
+            if idx != 0 {
+                json.push_str(",\n");
+            }
+
+            json.push_str("  ");
+            write_json_string(&mut json, name);
+            json.push_str(": ");
+            write_json_string(&mut json, generated);
+        }
+
+        json.push_str("\n}");
+
+        json
+    }
+}
+
+/// Rewrites the class names and `@keyframes` names in a stylesheet to names
+/// which are unique to the file, as in CSS Modules.
+///
+/// Names within `:global(...)` are left as written, and names within
+/// `:local(...)` are rewritten as usual. The generated names are recorded in
+/// `exports`, keyed by the names as written.
+pub(super) struct CssModules {
+    /// A hash of the path of the stylesheet being compiled
+    file_hash: u64,
+    exports: BTreeMap<String, String>,
+    /// Maps the names of local `@keyframes` to those generated for them, both
+    /// as written in the stylesheet
+    keyframes: HashMap<String, String>,
+}
+
+impl CssModules {
+    pub fn new(path: &str) -> Self {
+        Self {
+            file_hash: fnv1a(FNV_OFFSET_BASIS, path.as_bytes()),
+            exports: BTreeMap::new(),
+            keyframes: HashMap::new(),
+        }
+    }
+
+    pub fn finish(self) -> BTreeMap<String, String> {
+        self.exports
+    }
+
+    pub fn rewrite(&mut self, stmts: Vec<CssStmt>) -> SassResult<Vec<CssStmt>> {
+        // Keyframes are renamed first so that animations referring to them can
+        // be rewritten regardless of the order in which they appear
+        let stmts = self.rewrite_keyframes(stmts);
+        self.rewrite_stmts(stmts)
+    }
+
+    /// Returns the name generated for the local name `name`, recording it in
+    /// the exports
+    fn local_name(&mut self, name: &str) -> String {
+        let generated = format!(
+            "{}_{}",
+            name,
+            to_base36(fnv1a(self.file_hash, name.as_bytes()))
+        );
+
+        self.exports
+            .entry(unescape(name).into_owned())
+            .or_insert_with(|| unescape(&generated).into_owned());
+
+        generated
+    }
+
+    fn rewrite_keyframes(&mut self, stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+        stmts
+            .into_iter()
+            .map(|stmt| match stmt {
+                CssStmt::UnknownAtRule(mut at_rule, is_group_end)
+                    if unvendor(&at_rule.name).eq_ignore_ascii_case("keyframes") =>
+                {
+                    let name = at_rule.params.trim();
+
+                    at_rule.params = match global_argument(name, "global") {
+                        Some(global) => global.to_owned(),
+                        None => {
+                            let local = global_argument(name, "local").unwrap_or(name).to_owned();
+                            let generated = self.local_name(&local);
+                            self.keyframes.insert(local, generated.clone());
+                            generated
+                        }
+                    };
+
+                    CssStmt::UnknownAtRule(at_rule, is_group_end)
+                }
+                CssStmt::Media(mut media, is_group_end) => {
+                    media.body = self.rewrite_keyframes(media.body);
+                    CssStmt::Media(media, is_group_end)
+                }
+                CssStmt::Supports(mut supports, is_group_end) => {
+                    supports.body = self.rewrite_keyframes(supports.body);
+                    CssStmt::Supports(supports, is_group_end)
+                }
+                stmt => stmt,
+            })
+            .collect()
+    }
+
+    fn rewrite_stmts(&mut self, stmts: Vec<CssStmt>) -> SassResult<Vec<CssStmt>> {
+        stmts
+            .into_iter()
+            .map(|stmt| {
+                Ok(match stmt {
+                    CssStmt::RuleSet {
+                        selector,
+                        body,
+                        is_group_end,
+                        tabs,
+                    } => {
+                        let list = selector.as_selector_list().clone();
+
+                        CssStmt::RuleSet {
+                            selector: ExtendedSelector::new(self.rewrite_selector_list(list)?),
+                            body: self.rewrite_stmts(body)?,
+                            is_group_end,
+                            tabs,
+                        }
+                    }
+                    CssStmt::Style(mut style) => {
+                        let property = style.property.to_string().to_ascii_lowercase();
+
+                        if !style.declared_as_custom_property
+                            && matches!(unvendor(&property), "animation" | "animation-name")
+                        {
+                            self.rewrite_animation(&mut style.value.node);
+                        }
+
+                        CssStmt::Style(style)
+                    }
+                    CssStmt::Media(mut media, is_group_end) => {
+                        media.body = self.rewrite_stmts(media.body)?;
+                        CssStmt::Media(media, is_group_end)
+                    }
+                    CssStmt::Supports(mut supports, is_group_end) => {
+                        supports.body = self.rewrite_stmts(supports.body)?;
+                        CssStmt::Supports(supports, is_group_end)
+                    }
+                    CssStmt::UnknownAtRule(mut at_rule, is_group_end) => {
+                        at_rule.body = self.rewrite_stmts(at_rule.body)?;
+                        CssStmt::UnknownAtRule(at_rule, is_group_end)
+                    }
+                    stmt @ (CssStmt::Comment(..)
+                    | CssStmt::KeyframesRuleSet(..)
+                    | CssStmt::Import(..)) => stmt,
+                })
+            })
+            .collect()
+    }
+
+    /// Renames references to local `@keyframes` in the value of an `animation`
+    /// or `animation-name` declaration
+    fn rewrite_animation(&self, value: &mut Value) {
+        match value {
+            Value::String(s, QuoteKind::None) => {
+                if let Some(generated) = self.keyframes.get(s.as_str()) {
+                    *s = generated.clone();
+                }
+            }
+            Value::List(elems, ..) => {
+                for elem in elems {
+                    self.rewrite_animation(elem);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rewrite_selector_list(&mut self, list: SelectorList) -> SassResult<SelectorList> {
+        Ok(SelectorList {
+            components: list
+                .components
+                .into_iter()
+                .map(|complex| self.rewrite_complex(complex))
+                .collect::<SassResult<Vec<_>>>()?,
+            span: list.span,
+        })
+    }
+
+    fn rewrite_complex(&mut self, mut complex: ComplexSelector) -> SassResult<ComplexSelector> {
+        let mut components = Vec::with_capacity(complex.components.len());
+
+        for component in mem::take(&mut complex.components) {
+            match component {
+                ComplexSelectorComponent::Compound(compound) => {
+                    self.rewrite_compound(compound, &mut components)?;
+                }
+                combinator @ ComplexSelectorComponent::Combinator(..) => {
+                    components.push(combinator);
+                }
+            }
+        }
+
+        complex.components = components;
+
+        Ok(complex)
+    }
+
+    /// Rewrites `compound`, adding the resulting components to `output`
+    ///
+    /// A `:global(...)` or `:local(...)` argument that is a complex selector
+    /// may expand to several components.
+    fn rewrite_compound(
+        &mut self,
+        compound: CompoundSelector,
+        output: &mut Vec<ComplexSelectorComponent>,
+    ) -> SassResult<()> {
+        let is_single = compound.components.len() == 1;
+        let mut simples = Vec::with_capacity(compound.components.len());
+
+        for simple in compound.components {
+            let pseudo = match simple {
+                SimpleSelector::Class(name) => {
+                    simples.push(SimpleSelector::Class(self.local_name(&name)));
+                    continue;
+                }
+                SimpleSelector::Pseudo(mut pseudo) => {
+                    let is_scope = pseudo.is_class
+                        && pseudo.selector.is_none()
+                        && pseudo.argument.is_some()
+                        && matches!(pseudo.normalized_name(), "global" | "local");
+
+                    if !is_scope {
+                        if let Some(selector) = pseudo.selector.take() {
+                            pseudo.selector =
+                                Some(Box::new(self.rewrite_selector_list(*selector)?));
+                        }
+
+                        simples.push(SimpleSelector::Pseudo(pseudo));
+                        continue;
+                    }
+
+                    pseudo
+                }
+                simple => {
+                    simples.push(simple);
+                    continue;
+                }
+            };
+
+            let argument = parse_scope_argument(pseudo.argument.as_deref().unwrap(), pseudo.span)?;
+
+            let argument = if pseudo.normalized_name() == "global" {
+                argument
+            } else {
+                self.rewrite_complex(argument)?
+            };
+
+            match argument.components.as_slice() {
+                [ComplexSelectorComponent::Compound(inner)] => {
+                    simples.extend(inner.components.iter().cloned());
+                }
+                // A complex selector may only replace an entire compound selector
+                _ if is_single => {
+                    output.extend(argument.components);
+                    return Ok(());
+                }
+                _ => {
+                    return Err((
+                        format!(
+                            "Only a compound selector may be combined with :{}().",
+                            pseudo.name
+                        ),
+                        pseudo.span,
+                    )
+                        .into())
+                }
+            }
+        }
+
+        output.push(ComplexSelectorComponent::Compound(CompoundSelector {
+            components: simples,
+        }));
+
+        Ok(())
+    }
+}
+
+/// Parses the argument of `:global()` or `:local()`, which must be a single
+/// complex selector
+fn parse_scope_argument(argument: &str, span: Span) -> SassResult<ComplexSelector> {
+    let list =
+        SelectorParser::new(Lexer::new_from_string(argument, span), false, false, span).parse()?;
+
+    let mut components = list.components;
+
+    if components.len() != 1 {
+        return Err(("Expected a single selector.", span).into());
+    }
+
+    Ok(components.remove(0))
+}
+
+/// Returns the argument of a `@keyframes` name such as `:global(fade)`
+fn global_argument<'a>(name: &'a str, pseudo: &str) -> Option<&'a str> {
+    let rest = name.strip_prefix(':')?;
+
+    if rest.len() < pseudo.len() || !rest[..pseudo.len()].eq_ignore_ascii_case(pseudo) {
+        return None;
+    }
+
+    rest[pseudo.len()..]
+        .strip_prefix('(')?
+        .strip_suffix(')')
+        .map(str::trim)
+}
+
+/// Encodes the low bits of `hash` as six base 36 digits
+fn to_base36(mut hash: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    let mut encoded = String::with_capacity(6);
+
+    for _ in 0..6 {
+        encoded.push(char::from(DIGITS[(hash % 36) as usize]));
+        hash /= 36;
+    }
+
+    encoded
+}
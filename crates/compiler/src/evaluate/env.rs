@@ -315,6 +315,12 @@ impl Environment {
         Ok(self.scopes.var_exists(name))
     }
 
+    /// Whether a variable with the given name exists in the global scope of
+    /// this module, or in a module loaded without a namespace
+    pub fn global_var_exists(&self, name: Identifier) -> bool {
+        self.scopes.global_var_exists(name) || self.get_variable_from_global_modules(name).is_some()
+    }
+
     pub fn get_var(
         &mut self,
         name: Spanned<Identifier>,
@@ -455,10 +461,18 @@ impl Environment {
         Ok(())
     }
 
-    pub fn to_module(self, extension_store: ExtensionStore) -> Arc<RefCell<Module>> {
+    pub fn to_module(
+        self,
+        extension_store: ExtensionStore,
+        upstream: Vec<Arc<RefCell<Module>>>,
+    ) -> Arc<RefCell<Module>> {
         debug_assert!(self.at_root());
 
-        Arc::new(RefCell::new(Module::new_env(self, extension_store)))
+        Arc::new(RefCell::new(Module::new_env(
+            self,
+            extension_store,
+            upstream,
+        )))
     }
 
     fn from_one_module<T>(
@@ -0,0 +1,724 @@
+use std::{collections::HashMap, slice};
+
+use crate::{
+    ast::{CssStmt, MediaQuery, MediaRule},
+    common::unvendor,
+    MediaQueryGrouping,
+};
+
+/// A declaration which may be affected by the order in which rules are
+/// emitted
+struct Declaration {
+    property: String,
+    /// The minimum and maximum specificity of the rule containing this
+    /// declaration, or `None` if it isn't known
+    specificity: Option<(i32, i32)>,
+}
+
+impl Declaration {
+    fn conflicts_with(&self, other: &Self) -> bool {
+        let specificities_overlap = match (self.specificity, other.specificity) {
+            (Some((min1, max1)), Some((min2, max2))) => min1 <= max2 && min2 <= max1,
+            _ => true,
+        };
+
+        specificities_overlap && properties_overlap(&self.property, &other.property)
+    }
+}
+
+/// Shorthand properties and the properties they set
+///
+/// Shorthands which set other shorthands, such as `border` setting
+/// `border-top`, list only those shorthands. Legacy aliases are listed as
+/// shorthands of the property they alias.
+const SHORTHANDS: &[(&str, &[&str])] = &[
+    (
+        "animation",
+        &[
+            "animation-name",
+            "animation-duration",
+            "animation-timing-function",
+            "animation-delay",
+            "animation-iteration-count",
+            "animation-direction",
+            "animation-fill-mode",
+            "animation-play-state",
+            "animation-timeline",
+            "animation-composition",
+            "animation-range",
+        ],
+    ),
+    (
+        "animation-range",
+        &["animation-range-start", "animation-range-end"],
+    ),
+    (
+        "background",
+        &[
+            "background-attachment",
+            "background-clip",
+            "background-color",
+            "background-image",
+            "background-origin",
+            "background-position",
+            "background-repeat",
+            "background-size",
+        ],
+    ),
+    (
+        "background-position",
+        &["background-position-x", "background-position-y"],
+    ),
+    (
+        "border",
+        &[
+            "border-top",
+            "border-right",
+            "border-bottom",
+            "border-left",
+            "border-image",
+        ],
+    ),
+    (
+        "border-top",
+        &["border-top-width", "border-top-style", "border-top-color"],
+    ),
+    (
+        "border-right",
+        &[
+            "border-right-width",
+            "border-right-style",
+            "border-right-color",
+        ],
+    ),
+    (
+        "border-bottom",
+        &[
+            "border-bottom-width",
+            "border-bottom-style",
+            "border-bottom-color",
+        ],
+    ),
+    (
+        "border-left",
+        &[
+            "border-left-width",
+            "border-left-style",
+            "border-left-color",
+        ],
+    ),
+    (
+        "border-width",
+        &[
+            "border-top-width",
+            "border-right-width",
+            "border-bottom-width",
+            "border-left-width",
+        ],
+    ),
+    (
+        "border-style",
+        &[
+            "border-top-style",
+            "border-right-style",
+            "border-bottom-style",
+            "border-left-style",
+        ],
+    ),
+    (
+        "border-color",
+        &[
+            "border-top-color",
+            "border-right-color",
+            "border-bottom-color",
+            "border-left-color",
+        ],
+    ),
+    ("border-block", &["border-block-start", "border-block-end"]),
+    (
+        "border-inline",
+        &["border-inline-start", "border-inline-end"],
+    ),
+    (
+        "border-block-start",
+        &[
+            "border-block-start-width",
+            "border-block-start-style",
+            "border-block-start-color",
+        ],
+    ),
+    (
+        "border-block-end",
+        &[
+            "border-block-end-width",
+            "border-block-end-style",
+            "border-block-end-color",
+        ],
+    ),
+    (
+        "border-inline-start",
+        &[
+            "border-inline-start-width",
+            "border-inline-start-style",
+            "border-inline-start-color",
+        ],
+    ),
+    (
+        "border-inline-end",
+        &[
+            "border-inline-end-width",
+            "border-inline-end-style",
+            "border-inline-end-color",
+        ],
+    ),
+    (
+        "border-block-width",
+        &["border-block-start-width", "border-block-end-width"],
+    ),
+    (
+        "border-block-style",
+        &["border-block-start-style", "border-block-end-style"],
+    ),
+    (
+        "border-block-color",
+        &["border-block-start-color", "border-block-end-color"],
+    ),
+    (
+        "border-inline-width",
+        &["border-inline-start-width", "border-inline-end-width"],
+    ),
+    (
+        "border-inline-style",
+        &["border-inline-start-style", "border-inline-end-style"],
+    ),
+    (
+        "border-inline-color",
+        &["border-inline-start-color", "border-inline-end-color"],
+    ),
+    (
+        "border-image",
+        &[
+            "border-image-source",
+            "border-image-slice",
+            "border-image-width",
+            "border-image-outset",
+            "border-image-repeat",
+        ],
+    ),
+    (
+        "border-radius",
+        &[
+            "border-top-left-radius",
+            "border-top-right-radius",
+            "border-bottom-right-radius",
+            "border-bottom-left-radius",
+        ],
+    ),
+    (
+        "column-rule",
+        &[
+            "column-rule-width",
+            "column-rule-style",
+            "column-rule-color",
+        ],
+    ),
+    ("columns", &["column-width", "column-count"]),
+    (
+        "contain-intrinsic-size",
+        &["contain-intrinsic-width", "contain-intrinsic-height"],
+    ),
+    ("container", &["container-name", "container-type"]),
+    ("flex", &["flex-grow", "flex-shrink", "flex-basis"]),
+    ("flex-flow", &["flex-direction", "flex-wrap"]),
+    (
+        "font",
+        &[
+            "font-style",
+            "font-variant",
+            "font-weight",
+            "font-width",
+            "font-size",
+            "line-height",
+            "font-family",
+            "font-size-adjust",
+            "font-kerning",
+            "font-language-override",
+            "font-optical-sizing",
+            "font-feature-settings",
+            "font-variation-settings",
+            "font-palette",
+        ],
+    ),
+    ("font-stretch", &["font-width"]),
+    (
+        "font-variant",
+        &[
+            "font-variant-caps",
+            "font-variant-ligatures",
+            "font-variant-numeric",
+            "font-variant-east-asian",
+            "font-variant-alternates",
+            "font-variant-position",
+            "font-variant-emoji",
+        ],
+    ),
+    (
+        "font-synthesis",
+        &[
+            "font-synthesis-weight",
+            "font-synthesis-style",
+            "font-synthesis-small-caps",
+            "font-synthesis-position",
+        ],
+    ),
+    ("gap", &["row-gap", "column-gap"]),
+    ("grid-gap", &["row-gap", "column-gap"]),
+    ("grid-row-gap", &["row-gap"]),
+    ("grid-column-gap", &["column-gap"]),
+    (
+        "grid",
+        &[
+            "grid-template",
+            "grid-auto-rows",
+            "grid-auto-columns",
+            "grid-auto-flow",
+        ],
+    ),
+    (
+        "grid-template",
+        &[
+            "grid-template-rows",
+            "grid-template-columns",
+            "grid-template-areas",
+        ],
+    ),
+    ("grid-area", &["grid-row", "grid-column"]),
+    ("grid-row", &["grid-row-start", "grid-row-end"]),
+    ("grid-column", &["grid-column-start", "grid-column-end"]),
+    ("inset", &["top", "right", "bottom", "left"]),
+    ("inset-block", &["inset-block-start", "inset-block-end"]),
+    ("inset-inline", &["inset-inline-start", "inset-inline-end"]),
+    (
+        "list-style",
+        &["list-style-type", "list-style-position", "list-style-image"],
+    ),
+    (
+        "margin",
+        &["margin-top", "margin-right", "margin-bottom", "margin-left"],
+    ),
+    ("margin-block", &["margin-block-start", "margin-block-end"]),
+    (
+        "margin-inline",
+        &["margin-inline-start", "margin-inline-end"],
+    ),
+    ("marker", &["marker-start", "marker-mid", "marker-end"]),
+    (
+        "mask",
+        &[
+            "mask-image",
+            "mask-mode",
+            "mask-position",
+            "mask-size",
+            "mask-repeat",
+            "mask-origin",
+            "mask-clip",
+            "mask-composite",
+            "mask-border",
+        ],
+    ),
+    (
+        "mask-border",
+        &[
+            "mask-border-source",
+            "mask-border-slice",
+            "mask-border-width",
+            "mask-border-outset",
+            "mask-border-repeat",
+            "mask-border-mode",
+        ],
+    ),
+    (
+        "offset",
+        &[
+            "offset-position",
+            "offset-path",
+            "offset-distance",
+            "offset-rotate",
+            "offset-anchor",
+        ],
+    ),
+    (
+        "outline",
+        &["outline-width", "outline-style", "outline-color"],
+    ),
+    ("overflow", &["overflow-x", "overflow-y"]),
+    (
+        "overscroll-behavior",
+        &["overscroll-behavior-x", "overscroll-behavior-y"],
+    ),
+    (
+        "padding",
+        &[
+            "padding-top",
+            "padding-right",
+            "padding-bottom",
+            "padding-left",
+        ],
+    ),
+    (
+        "padding-block",
+        &["padding-block-start", "padding-block-end"],
+    ),
+    (
+        "padding-inline",
+        &["padding-inline-start", "padding-inline-end"],
+    ),
+    ("page-break-after", &["break-after"]),
+    ("page-break-before", &["break-before"]),
+    ("page-break-inside", &["break-inside"]),
+    ("place-content", &["align-content", "justify-content"]),
+    ("place-items", &["align-items", "justify-items"]),
+    ("place-self", &["align-self", "justify-self"]),
+    (
+        "position-try",
+        &["position-try-order", "position-try-fallbacks"],
+    ),
+    (
+        "scroll-margin",
+        &[
+            "scroll-margin-top",
+            "scroll-margin-right",
+            "scroll-margin-bottom",
+            "scroll-margin-left",
+        ],
+    ),
+    (
+        "scroll-margin-block",
+        &["scroll-margin-block-start", "scroll-margin-block-end"],
+    ),
+    (
+        "scroll-margin-inline",
+        &["scroll-margin-inline-start", "scroll-margin-inline-end"],
+    ),
+    (
+        "scroll-padding",
+        &[
+            "scroll-padding-top",
+            "scroll-padding-right",
+            "scroll-padding-bottom",
+            "scroll-padding-left",
+        ],
+    ),
+    (
+        "scroll-padding-block",
+        &["scroll-padding-block-start", "scroll-padding-block-end"],
+    ),
+    (
+        "scroll-padding-inline",
+        &["scroll-padding-inline-start", "scroll-padding-inline-end"],
+    ),
+    (
+        "scroll-timeline",
+        &["scroll-timeline-name", "scroll-timeline-axis"],
+    ),
+    (
+        "text-decoration",
+        &[
+            "text-decoration-line",
+            "text-decoration-style",
+            "text-decoration-color",
+            "text-decoration-thickness",
+        ],
+    ),
+    ("text-box", &["text-box-trim", "text-box-edge"]),
+    (
+        "text-emphasis",
+        &["text-emphasis-style", "text-emphasis-color"],
+    ),
+    ("text-stroke", &["text-stroke-width", "text-stroke-color"]),
+    ("text-wrap", &["text-wrap-mode", "text-wrap-style"]),
+    (
+        "transition",
+        &[
+            "transition-property",
+            "transition-duration",
+            "transition-timing-function",
+            "transition-delay",
+            "transition-behavior",
+        ],
+    ),
+    (
+        "view-timeline",
+        &[
+            "view-timeline-name",
+            "view-timeline-axis",
+            "view-timeline-inset",
+        ],
+    ),
+    (
+        "white-space",
+        &["white-space-collapse", "text-wrap-mode", "white-space-trim"],
+    ),
+    ("word-wrap", &["overflow-wrap"]),
+];
+
+const SIDES: [&str; 4] = ["top", "right", "bottom", "left"];
+
+const CORNERS: [&str; 4] = ["top-left", "top-right", "bottom-right", "bottom-left"];
+
+/// Whether setting `property1` may affect the same value as setting `property2`,
+/// as is the case for a shorthand and any of its longhands
+fn properties_overlap(property1: &str, property2: &str) -> bool {
+    if property1.starts_with("--") || property2.starts_with("--") {
+        return property1 == property2;
+    }
+
+    let property1 = unvendor(property1);
+    let property2 = unvendor(property2);
+
+    if property1 == "all" || property2 == "all" || property1 == property2 {
+        return true;
+    }
+
+    let mut longhands1 = Vec::new();
+    let mut longhands2 = Vec::new();
+    collect_longhands(property1, &mut longhands1);
+    collect_longhands(property2, &mut longhands2);
+
+    longhands1
+        .iter()
+        .any(|longhand| longhands2.contains(longhand))
+}
+
+/// Collects the properties which aren't shorthands that are set by `property`
+///
+/// Logical properties such as `margin-inline-start` may set any of their
+/// physical equivalents depending on the writing mode, so each of those is
+/// included as well.
+fn collect_longhands(property: &str, longhands: &mut Vec<String>) {
+    if let Some((_, properties)) = SHORTHANDS.iter().find(|(name, _)| *name == property) {
+        for property in *properties {
+            collect_longhands(property, longhands);
+        }
+
+        return;
+    }
+
+    longhands.push(property.to_owned());
+
+    let segments: Vec<&str> = property.split('-').collect();
+    let is_logical_axis = |segment: &str| segment == "block" || segment == "inline";
+    let is_logical_edge = |segment: &str| segment == "start" || segment == "end";
+    let replace = |idx: usize, len: usize, physical: &str| {
+        let mut replaced = segments[..idx].to_vec();
+        replaced.push(physical);
+        replaced.extend(&segments[idx + len..]);
+        replaced.join("-")
+    };
+
+    if let Some(idx) = segments
+        .windows(2)
+        .position(|pair| is_logical_axis(pair[0]) && is_logical_edge(pair[1]))
+    {
+        // `inset-block-start` corresponds to `top` rather than `inset-top`
+        let start = if segments[0] == "inset" { 0 } else { idx };
+        longhands.extend(
+            SIDES
+                .iter()
+                .map(|side| replace(start, idx + 2 - start, side)),
+        );
+    } else if let Some(idx) = segments
+        .windows(2)
+        .position(|pair| is_logical_edge(pair[0]) && is_logical_edge(pair[1]))
+    {
+        longhands.extend(CORNERS.iter().map(|corner| replace(idx, 2, corner)));
+    } else if segments.ends_with(&["block", "size"]) || segments.ends_with(&["inline", "size"]) {
+        let idx = segments.len() - 2;
+        longhands.extend(
+            ["width", "height"]
+                .iter()
+                .map(|dimension| replace(idx, 2, dimension)),
+        );
+    } else if segments
+        .last()
+        .map_or(false, |&segment| is_logical_axis(segment))
+    {
+        let idx = segments.len() - 1;
+        longhands.extend(["x", "y"].iter().map(|axis| replace(idx, 1, axis)));
+    }
+}
+
+/// Collects every declaration within `stmts`
+///
+/// Rules nested within another style rule use native CSS nesting, so their
+/// specificity depends on their parent and is treated as unknown.
+fn collect_declarations(
+    stmts: &[CssStmt],
+    within_style_rule: bool,
+    declarations: &mut Vec<Declaration>,
+) {
+    for stmt in stmts {
+        match stmt {
+            CssStmt::RuleSet { selector, body, .. } => {
+                let specificity = if within_style_rule {
+                    None
+                } else {
+                    selector
+                        .as_selector_list()
+                        .components
+                        .iter()
+                        .fold(None, |range, complex| {
+                            let (min, max) = range.unwrap_or((i32::MAX, i32::MIN));
+                            Some((
+                                min.min(complex.min_specificity()),
+                                max.max(complex.max_specificity()),
+                            ))
+                        })
+                };
+
+                for child in body {
+                    match child {
+                        CssStmt::Style(style) => declarations.push(Declaration {
+                            property: style.property.to_string().to_ascii_lowercase(),
+                            specificity,
+                        }),
+                        child => collect_declarations(slice::from_ref(child), true, declarations),
+                    }
+                }
+            }
+            CssStmt::Media(media, ..) => {
+                collect_declarations(&media.body, within_style_rule, declarations);
+            }
+            CssStmt::Supports(supports, ..) => {
+                collect_declarations(&supports.body, within_style_rule, declarations);
+            }
+            CssStmt::UnknownAtRule(at_rule, ..) => {
+                collect_declarations(&at_rule.body, within_style_rule, declarations);
+            }
+            CssStmt::Style(style) => declarations.push(Declaration {
+                property: style.property.to_string().to_ascii_lowercase(),
+                specificity: None,
+            }),
+            CssStmt::Comment(..) | CssStmt::KeyframesRuleSet(..) | CssStmt::Import(..) => {}
+        }
+    }
+}
+
+/// Merges `@media` rules with identical queries.
+///
+/// With [`MediaQueryGrouping::Safe`], a `@media` rule is only merged into an
+/// earlier rule with the same query if none of the CSS between the two could
+/// be overridden differently as a result. With
+/// [`MediaQueryGrouping::Aggressive`], every `@media` rule is merged and moved to
+/// the end of the stylesheet.
+pub(super) fn group_media_queries(
+    stmts: Vec<CssStmt>,
+    grouping: MediaQueryGrouping,
+) -> Vec<CssStmt> {
+    let stmts = stmts
+        .into_iter()
+        .map(|stmt| match stmt {
+            CssStmt::Media(mut media, is_group_end) => {
+                media.body = group_media_queries(media.body, grouping);
+                CssStmt::Media(media, is_group_end)
+            }
+            CssStmt::Supports(mut supports, is_group_end) => {
+                supports.body = group_media_queries(supports.body, grouping);
+                CssStmt::Supports(supports, is_group_end)
+            }
+            CssStmt::UnknownAtRule(mut at_rule, is_group_end) => {
+                at_rule.body = group_media_queries(at_rule.body, grouping);
+                CssStmt::UnknownAtRule(at_rule, is_group_end)
+            }
+            stmt => stmt,
+        })
+        .collect();
+
+    match grouping {
+        MediaQueryGrouping::Safe => group_safely(stmts),
+        MediaQueryGrouping::Aggressive => group_aggressively(stmts),
+    }
+}
+
+fn group_safely(stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+    let mut output: Vec<CssStmt> = Vec::with_capacity(stmts.len());
+    let mut groups: HashMap<Vec<MediaQuery>, usize> = HashMap::new();
+
+    for stmt in stmts {
+        let (media, is_group_end) = match stmt {
+            CssStmt::Media(media, is_group_end) => (media, is_group_end),
+            stmt => {
+                output.push(stmt);
+                continue;
+            }
+        };
+
+        if let Some(&idx) = groups.get(&media.query) {
+            let mut moved = Vec::new();
+            collect_declarations(&media.body, false, &mut moved);
+
+            let mut skipped = Vec::new();
+            collect_declarations(&output[idx + 1..], false, &mut skipped);
+
+            let is_safe = !moved
+                .iter()
+                .any(|moved| skipped.iter().any(|skipped| moved.conflicts_with(skipped)));
+
+            if is_safe {
+                if let CssStmt::Media(group, ..) = &mut output[idx] {
+                    group.body.extend(media.body);
+                }
+
+                if is_group_end {
+                    if let Some(prev) = output.last_mut() {
+                        prev.set_group_end();
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        groups.insert(media.query.clone(), output.len());
+        output.push(CssStmt::Media(media, is_group_end));
+    }
+
+    output
+}
+
+fn group_aggressively(stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+    let mut output: Vec<CssStmt> = Vec::with_capacity(stmts.len());
+    let mut groups: Vec<MediaRule> = Vec::new();
+    let mut group_indices: HashMap<Vec<MediaQuery>, usize> = HashMap::new();
+
+    for stmt in stmts {
+        match stmt {
+            CssStmt::Media(media, is_group_end) => {
+                if is_group_end {
+                    if let Some(prev) = output.last_mut() {
+                        prev.set_group_end();
+                    }
+                }
+
+                match group_indices.get(&media.query) {
+                    Some(&idx) => groups[idx].body.extend(media.body),
+                    None => {
+                        group_indices.insert(media.query.clone(), groups.len());
+                        groups.push(media);
+                    }
+                }
+            }
+            stmt => output.push(stmt),
+        }
+    }
+
+    if !groups.is_empty() {
+        if let Some(prev) = output.last_mut() {
+            prev.set_group_end();
+        }
+    }
+
+    output.extend(groups.into_iter().map(|media| CssStmt::Media(media, true)));
+
+    output
+}
@@ -0,0 +1,509 @@
+use std::{collections::HashMap, mem, sync::Arc};
+
+use codemap::Span;
+
+use crate::{
+    ast::{CssStmt, Style},
+    color::ColorFormat,
+    common::{unvendor, Brackets, ListSeparator, QuoteKind},
+    selector::{ComplexSelectorComponent, ExtendedSelector, Pseudo, SelectorList, SimpleSelector},
+    unit::Unit,
+    value::{SassNumber, Value},
+};
+
+/// Optimizes the final CSS tree to reduce the size of the output.
+///
+/// Every transformation preserves the cascade: rules are only merged with the
+/// rule immediately preceding them, and a declaration is only removed if a
+/// later declaration in the same rule is guaranteed to take precedence over it
+/// in every browser. Comments other than those starting with `/*!` are removed.
+pub(super) struct Minifier;
+
+impl Minifier {
+    pub fn minify(&self, stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+        let mut output: Vec<CssStmt> = Vec::with_capacity(stmts.len());
+
+        for stmt in stmts {
+            let is_group_end = stmt.is_group_end();
+
+            let stmt = match self.minify_stmt(stmt) {
+                Some(stmt) => stmt,
+                None => {
+                    if is_group_end {
+                        if let Some(prev) = output.last_mut() {
+                            prev.set_group_end();
+                        }
+                    }
+
+                    continue;
+                }
+            };
+
+            let unmerged = match output.last_mut() {
+                Some(prev) => self.merge_rules(prev, stmt),
+                None => Some(stmt),
+            };
+
+            if let Some(stmt) = unmerged {
+                output.push(stmt);
+            }
+        }
+
+        output
+    }
+
+    /// Minifies `stmt` and its children, returning `None` if it should be
+    /// removed entirely
+    fn minify_stmt(&self, stmt: CssStmt) -> Option<CssStmt> {
+        let stmt = match stmt {
+            CssStmt::RuleSet {
+                selector,
+                body,
+                is_group_end,
+                tabs,
+            } => CssStmt::RuleSet {
+                selector,
+                body: self.minify_declarations(body),
+                is_group_end,
+                tabs,
+            },
+            CssStmt::Media(mut media, is_group_end) => {
+                media.body = self.minify(media.body);
+                CssStmt::Media(media, is_group_end)
+            }
+            CssStmt::Supports(mut supports, is_group_end) => {
+                supports.body = self.minify(supports.body);
+                CssStmt::Supports(supports, is_group_end)
+            }
+            CssStmt::UnknownAtRule(mut at_rule, is_group_end) => {
+                at_rule.body = self.minify(at_rule.body);
+
+                // Empty `@layer` rules still establish the order of layers, and
+                // empty `@keyframes` rules override earlier ones of the same name
+                let is_significant_when_empty = matches!(
+                    unvendor(&at_rule.name).to_ascii_lowercase().as_str(),
+                    "layer" | "keyframes"
+                );
+
+                if at_rule.has_body
+                    && !is_significant_when_empty
+                    && at_rule.body.iter().all(CssStmt::is_invisible)
+                {
+                    return None;
+                }
+
+                CssStmt::UnknownAtRule(at_rule, is_group_end)
+            }
+            CssStmt::Comment(ref comment, ..) if !comment.starts_with("/*!") => return None,
+            CssStmt::Style(..)
+            | CssStmt::Comment(..)
+            | CssStmt::KeyframesRuleSet(..)
+            | CssStmt::Import(..) => stmt,
+        };
+
+        if stmt.is_invisible() && !matches!(stmt, CssStmt::UnknownAtRule(..)) {
+            return None;
+        }
+
+        Some(stmt)
+    }
+
+    /// Minifies the body of a style rule, removing overridden declarations and
+    /// shortening the values of those that remain
+    fn minify_declarations(&self, body: Vec<CssStmt>) -> Vec<CssStmt> {
+        let body = self.minify(body);
+
+        // Walk backwards so that each declaration can be compared against the
+        // nearest declaration of the same property that follows it
+        let mut kept = Vec::with_capacity(body.len());
+        let mut later_declarations: HashMap<String, usize> = HashMap::new();
+
+        for stmt in body.into_iter().rev() {
+            if let CssStmt::Style(style) = &stmt {
+                let property = property_key(style);
+
+                if let Some(&idx) = later_declarations.get(&property) {
+                    let later = match &kept[idx] {
+                        CssStmt::Style(later) => later,
+                        _ => unreachable!(),
+                    };
+
+                    if is_overridden_by(style, later) {
+                        continue;
+                    }
+                }
+
+                later_declarations.insert(property, kept.len());
+            }
+
+            kept.push(stmt);
+        }
+
+        kept.reverse();
+
+        kept.into_iter()
+            .map(|stmt| match stmt {
+                CssStmt::Style(style) => CssStmt::Style(minify_style(style)),
+                stmt => stmt,
+            })
+            .collect()
+    }
+
+    /// Attempts to merge `next` into the immediately preceding `prev`, either
+    /// because they have the same selector or because they have the same
+    /// declarations. Returns `next` if the rules couldn't be merged.
+    fn merge_rules(&self, prev: &mut CssStmt, next: CssStmt) -> Option<CssStmt> {
+        let (prev_selector, prev_body, prev_is_group_end) = match prev {
+            CssStmt::RuleSet {
+                selector,
+                body,
+                is_group_end,
+                ..
+            } => (selector, body, is_group_end),
+            _ => return Some(next),
+        };
+
+        let (next_selector, next_body, next_is_group_end, next_tabs) = match next {
+            CssStmt::RuleSet {
+                selector,
+                body,
+                is_group_end,
+                tabs,
+            } => (selector, body, is_group_end, tabs),
+            next => return Some(next),
+        };
+
+        if *prev_selector.as_selector_list() == *next_selector.as_selector_list() {
+            let mut body = mem::take(prev_body);
+            body.extend(next_body);
+            *prev_body = self.minify_declarations(body);
+            *prev_is_group_end = next_is_group_end;
+            return None;
+        }
+
+        let prev_list = prev_selector.as_selector_list().clone();
+        let next_list = next_selector.as_selector_list().clone();
+
+        // A browser discards an entire rule if it doesn't recognize any part of
+        // its selector, so only selectors every browser supports are combined
+        if !is_universally_supported_selector(&prev_list)
+            || !is_universally_supported_selector(&next_list)
+            || !have_same_declarations(prev_body, &next_body)
+        {
+            return Some(CssStmt::RuleSet {
+                selector: next_selector,
+                body: next_body,
+                is_group_end: next_is_group_end,
+                tabs: next_tabs,
+            });
+        }
+
+        let mut components = prev_list.components;
+        for complex in next_list.components {
+            if !components.contains(&complex) {
+                components.push(complex);
+            }
+        }
+
+        *prev_selector = ExtendedSelector::new(SelectorList {
+            components,
+            span: prev_list.span,
+        });
+        *prev_is_group_end = next_is_group_end;
+
+        None
+    }
+}
+
+fn property_key(style: &Style) -> String {
+    if style.declared_as_custom_property {
+        style.property.to_string()
+    } else {
+        style.property.to_string().to_ascii_lowercase()
+    }
+}
+
+fn is_important(value: &Value) -> bool {
+    match value {
+        Value::List(elems, ..) => matches!(
+            elems.last(),
+            Some(Value::String(s, QuoteKind::None)) if s == "!important"
+        ),
+        _ => false,
+    }
+}
+
+/// Whether every browser which understands `property` also understands
+/// `value`, in which case no earlier declaration of `property` can be acting
+/// as a fallback for it
+///
+/// Only an explicit list of long-established values is considered supported.
+/// Anything else, such as newer units or keywords, may be preceded by a
+/// deliberate fallback.
+fn is_universally_supported(property: &str, value: &Value) -> bool {
+    fn is_keyword(value: &Value, keywords: &[&str]) -> bool {
+        matches!(value, Value::String(s, QuoteKind::None) if keywords.contains(&s.as_str()))
+    }
+
+    fn is_basic_length(value: &Value) -> bool {
+        match value {
+            Value::Dimension(SassNumber { num, unit, .. }) => {
+                num.is_finite() && matches!(unit, Unit::None | Unit::Px | Unit::Em | Unit::Percent)
+            }
+            Value::List(elems, ListSeparator::Space, Brackets::None) => {
+                !elems.is_empty() && elems.iter().all(is_basic_length)
+            }
+            _ => false,
+        }
+    }
+
+    match property {
+        "color"
+        | "background-color"
+        | "border-color"
+        | "border-top-color"
+        | "border-right-color"
+        | "border-bottom-color"
+        | "border-left-color"
+        | "outline-color" => {
+            matches!(value, Value::Color(color) if color.is_legacy() && !color.has_missing_channels())
+        }
+        "margin" | "margin-top" | "margin-right" | "margin-bottom" | "margin-left" | "padding"
+        | "padding-top" | "padding-right" | "padding-bottom" | "padding-left" | "width"
+        | "height" | "min-width" | "min-height" | "max-width" | "max-height" | "top" | "right"
+        | "bottom" | "left" | "font-size" | "text-indent" | "border-width" => {
+            is_basic_length(value)
+        }
+        "display" => is_keyword(
+            value,
+            &[
+                "none",
+                "block",
+                "inline",
+                "inline-block",
+                "list-item",
+                "table",
+                "table-row",
+                "table-cell",
+            ],
+        ),
+        "position" => is_keyword(value, &["static", "relative", "absolute", "fixed"]),
+        "float" => is_keyword(value, &["left", "right", "none"]),
+        "clear" => is_keyword(value, &["left", "right", "both", "none"]),
+        "text-align" => is_keyword(value, &["left", "right", "center", "justify"]),
+        "visibility" => is_keyword(value, &["visible", "hidden"]),
+        "overflow" => is_keyword(value, &["visible", "hidden", "scroll", "auto"]),
+        "font-style" => is_keyword(value, &["normal", "italic"]),
+        "font-weight" => is_keyword(value, &["normal", "bold"]),
+        "white-space" => is_keyword(value, &["normal", "pre", "nowrap"]),
+        _ => false,
+    }
+}
+
+fn serialize(value: &Value, span: Span) -> Option<String> {
+    value.to_css_string(span, false).ok()
+}
+
+/// Whether `earlier` has no effect given that `later` is declared after it in
+/// the same rule
+fn is_overridden_by(earlier: &Style, later: &Style) -> bool {
+    if is_important(&earlier.value.node) && !is_important(&later.value.node) {
+        return false;
+    }
+
+    if earlier.declared_as_custom_property {
+        return true;
+    }
+
+    let earlier_text = serialize(&earlier.value.node, earlier.value.span);
+
+    if earlier_text.is_some() && earlier_text == serialize(&later.value.node, later.value.span) {
+        return true;
+    }
+
+    !later.declared_as_custom_property
+        && is_universally_supported(&property_key(later), &later.value.node)
+}
+
+fn have_same_declarations(body1: &[CssStmt], body2: &[CssStmt]) -> bool {
+    fn declaration_text(stmt: &CssStmt) -> Option<String> {
+        match stmt {
+            CssStmt::Style(style) => Some(format!(
+                "{}:{}",
+                style.property,
+                serialize(&style.value.node, style.value.span)?
+            )),
+            _ => None,
+        }
+    }
+
+    body1.len() == body2.len()
+        && body1.iter().zip(body2).all(|(stmt1, stmt2)| {
+            let text = declaration_text(stmt1);
+            text.is_some() && text == declaration_text(stmt2)
+        })
+}
+
+/// Whether every browser understands each selector in `list`
+///
+/// Like [`is_universally_supported`], only type, class, id and attribute
+/// selectors and an explicit list of long-established pseudo-classes and
+/// pseudo-elements are considered supported.
+fn is_universally_supported_selector(list: &SelectorList) -> bool {
+    list.components.iter().all(|complex| {
+        complex.components.iter().all(|component| match component {
+            ComplexSelectorComponent::Compound(compound) => compound
+                .components
+                .iter()
+                .all(is_universally_supported_simple_selector),
+            ComplexSelectorComponent::Combinator(..) => true,
+        })
+    })
+}
+
+fn is_universally_supported_simple_selector(simple: &SimpleSelector) -> bool {
+    match simple {
+        SimpleSelector::Universal(..)
+        | SimpleSelector::Type(..)
+        | SimpleSelector::Id(..)
+        | SimpleSelector::Class(..) => true,
+        // case-sensitivity modifiers such as `[a="b" i]` are relatively new
+        SimpleSelector::Attribute(attribute) => attribute.modifier().is_none(),
+        SimpleSelector::Pseudo(pseudo) => is_universally_supported_pseudo(pseudo),
+        SimpleSelector::Placeholder(..) | SimpleSelector::Parent(..) => false,
+    }
+}
+
+fn single_simple_selector(list: &SelectorList) -> Option<&SimpleSelector> {
+    match list.components.as_slice() {
+        [complex] => match complex.components.as_slice() {
+            [ComplexSelectorComponent::Compound(compound)] => {
+                match compound.components.as_slice() {
+                    [simple] => Some(simple),
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_universally_supported_pseudo(pseudo: &Pseudo) -> bool {
+    let name = pseudo.name.to_ascii_lowercase();
+
+    if !pseudo.is_class {
+        return pseudo.argument.is_none()
+            && pseudo.selector.is_none()
+            && matches!(
+                name.as_str(),
+                "before" | "after" | "first-line" | "first-letter"
+            );
+    }
+
+    match name.as_str() {
+        "link" | "visited" | "hover" | "active" | "focus" | "target" | "root" | "empty"
+        | "checked" | "enabled" | "disabled" | "first-child" | "last-child" | "only-child"
+        | "first-of-type" | "last-of-type" | "only-of-type" => {
+            pseudo.argument.is_none() && pseudo.selector.is_none()
+        }
+        // `:nth-child(2n of .a)` is a newer form
+        "lang" | "nth-child" | "nth-last-child" | "nth-of-type" | "nth-last-of-type" => {
+            pseudo.argument.is_some() && pseudo.selector.is_none()
+        }
+        // `:not()` has only long been supported with a single simple selector
+        "not" => match pseudo.selector.as_deref().and_then(single_simple_selector) {
+            Some(SimpleSelector::Pseudo(inner)) => {
+                inner.is_class
+                    && !inner.name.eq_ignore_ascii_case("not")
+                    && is_universally_supported_pseudo(inner)
+            }
+            Some(simple) => is_universally_supported_simple_selector(simple),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn minify_style(mut style: Style) -> Style {
+    if style.declared_as_custom_property {
+        return style;
+    }
+
+    let property = style.property.to_string().to_ascii_lowercase();
+    let span = style.value.span;
+
+    let value = mem::replace(&mut style.value.node, Value::Null);
+
+    style.value.node = match (property.as_str(), value) {
+        ("font-weight", Value::String(s, QuoteKind::None)) if s == "normal" => {
+            Value::Dimension(SassNumber::new_unitless(400.0))
+        }
+        ("font-weight", Value::String(s, QuoteKind::None)) if s == "bold" => {
+            Value::Dimension(SassNumber::new_unitless(700.0))
+        }
+        // In the `flex` shorthand a unitless zero is the flex shrink factor
+        // rather than the flex basis
+        (property, value) => minify_value(value, span, unvendor(property) != "flex"),
+    };
+
+    style
+}
+
+fn minify_value(value: Value, span: Span, allow_unitless_zero: bool) -> Value {
+    match value {
+        Value::Dimension(number)
+            if allow_unitless_zero
+                && number.num.is_zero()
+                && number.as_slash.is_none()
+                && is_length(&number.unit) =>
+        {
+            Value::Dimension(SassNumber::new_unitless(0.0))
+        }
+        Value::Color(mut color)
+            if color.is_legacy() && !color.has_missing_channels() && color.alpha().0 == 1.0 =>
+        {
+            if let Ok(shortest) = Value::Color(Arc::clone(&color)).to_css_string(span, true) {
+                Arc::make_mut(&mut color).format = ColorFormat::Literal(shortest);
+            }
+
+            Value::Color(color)
+        }
+        Value::List(elems, separator, brackets) => Value::List(
+            elems
+                .into_iter()
+                .map(|elem| minify_value(elem, span, allow_unitless_zero))
+                .collect(),
+            separator,
+            brackets,
+        ),
+        value => value,
+    }
+}
+
+fn is_length(unit: &Unit) -> bool {
+    matches!(
+        unit,
+        Unit::Px
+            | Unit::Mm
+            | Unit::In
+            | Unit::Cm
+            | Unit::Q
+            | Unit::Pt
+            | Unit::Pc
+            | Unit::Em
+            | Unit::Rem
+            | Unit::Lh
+            | Unit::Ex
+            | Unit::Ch
+            | Unit::Cap
+            | Unit::Ic
+            | Unit::Rlh
+            | Unit::Vw
+            | Unit::Vh
+            | Unit::Vmin
+            | Unit::Vmax
+            | Unit::Vi
+            | Unit::Vb
+    )
+}
@@ -1,9 +1,20 @@
 pub(crate) use bin_op::{cmp, div};
+pub use browsers::BrowserTargets;
+pub use css_modules::CssModule;
 pub(crate) use env::Environment;
 pub use visitor::Visitor;
 
 mod bin_op;
+mod browsers;
+mod css_modules;
 mod css_tree;
 mod env;
+mod media_grouping;
+mod minify;
+mod nesting;
+mod prefixer;
+mod purge;
+mod rtl;
 mod scope;
+mod urls;
 mod visitor;
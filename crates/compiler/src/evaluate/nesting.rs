@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::CssStmt,
+    selector::{
+        ComplexSelector, ComplexSelectorComponent, ExtendedSelector, Pseudo, SelectorList,
+        SimpleSelector,
+    },
+};
+
+/// Rewrites style rules that were evaluated for native CSS nesting.
+///
+/// During evaluation, style rules nested within another style rule are placed
+/// inside their parent, but keep their fully resolved selector so that `@extend`
+/// works as usual. Once all extensions have been applied, each group of nested
+/// rules is either emitted using the selectors as written, or flattened if
+/// `@extend` made the nested selectors diverge from their resolved form.
+pub(super) struct NativeNesting<'a> {
+    /// The selectors as written for each nested style rule, keyed by
+    /// `ExtendedSelector::id`
+    relative_selectors: &'a HashMap<usize, SelectorList>,
+}
+
+impl<'a> NativeNesting<'a> {
+    pub fn new(relative_selectors: &'a HashMap<usize, SelectorList>) -> Self {
+        Self { relative_selectors }
+    }
+
+    pub fn apply(&self, stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+        let mut output = Vec::with_capacity(stmts.len());
+
+        for stmt in stmts {
+            match stmt {
+                CssStmt::RuleSet {
+                    selector,
+                    body,
+                    is_group_end,
+                    ..
+                } => {
+                    if self.can_nest(&selector, &body) {
+                        output.push(CssStmt::RuleSet {
+                            selector,
+                            body: self.nest_children(body),
+                            is_group_end,
+                            tabs: 0,
+                        });
+                    } else {
+                        let start = output.len();
+                        self.flatten(selector, body, &mut output);
+                        if is_group_end && output.len() > start {
+                            output.last_mut().unwrap().set_group_end();
+                        }
+                    }
+                }
+                CssStmt::Media(mut media, is_group_end) => {
+                    media.body = self.apply(media.body);
+                    output.push(CssStmt::Media(media, is_group_end));
+                }
+                CssStmt::Supports(mut supports, is_group_end) => {
+                    supports.body = self.apply(supports.body);
+                    output.push(CssStmt::Supports(supports, is_group_end));
+                }
+                CssStmt::UnknownAtRule(mut at_rule, is_group_end) => {
+                    at_rule.body = self.apply(at_rule.body);
+                    output.push(CssStmt::UnknownAtRule(at_rule, is_group_end));
+                }
+                stmt @ (CssStmt::Style(..)
+                | CssStmt::Comment(..)
+                | CssStmt::KeyframesRuleSet(..)
+                | CssStmt::Import(..)) => output.push(stmt),
+            }
+        }
+
+        output
+    }
+
+    fn relative_selector(&self, stmt: &CssStmt) -> Option<&'a SelectorList> {
+        match stmt {
+            CssStmt::RuleSet { selector, .. } => self.relative_selectors.get(&selector.id()),
+            _ => None,
+        }
+    }
+
+    /// Whether every nested rule within `body` produces the same selector when
+    /// written relative to `selector` as it has after extension.
+    fn can_nest(&self, selector: &ExtendedSelector, body: &[CssStmt]) -> bool {
+        let parent = selector.as_selector_list();
+
+        let mut has_nested_children = false;
+
+        for child in body {
+            let relative = match self.relative_selector(child) {
+                Some(relative) => relative,
+                None => continue,
+            };
+
+            let (child_selector, child_body) = match child {
+                CssStmt::RuleSet { selector, body, .. } => (selector, body),
+                _ => unreachable!(),
+            };
+
+            has_nested_children = true;
+
+            let resolved = match relative
+                .clone()
+                .resolve_parent_selectors(Some(parent.clone()), true)
+            {
+                Ok(resolved) => resolved,
+                Err(..) => return false,
+            };
+
+            if resolved != *child_selector.as_selector_list()
+                || !self.can_nest(child_selector, child_body)
+            {
+                return false;
+            }
+        }
+
+        !has_nested_children || is_valid_parent(&parent)
+    }
+
+    /// Replaces the selectors of nested rules in `body` with the selectors as
+    /// written, moving them after the parent's declarations to match the order
+    /// in which flattened rules are emitted.
+    fn nest_children(&self, body: Vec<CssStmt>) -> Vec<CssStmt> {
+        let (nested, mut children): (Vec<CssStmt>, Vec<CssStmt>) = body
+            .into_iter()
+            .partition(|child| self.relative_selector(child).is_some());
+
+        for child in nested {
+            let relative = self.relative_selector(&child).unwrap().clone();
+            if let CssStmt::RuleSet { body, .. } = child {
+                children.push(CssStmt::RuleSet {
+                    selector: ExtendedSelector::new(relative),
+                    body: self.nest_children(body),
+                    is_group_end: false,
+                    tabs: 0,
+                });
+            }
+        }
+
+        children
+    }
+
+    /// Emits `selector` and all rules nested within it as sibling rules, as
+    /// though native nesting were disabled.
+    fn flatten(&self, selector: ExtendedSelector, body: Vec<CssStmt>, output: &mut Vec<CssStmt>) {
+        let (nested, children): (Vec<CssStmt>, Vec<CssStmt>) = body
+            .into_iter()
+            .partition(|child| self.relative_selector(child).is_some());
+
+        output.push(CssStmt::RuleSet {
+            selector,
+            body: children,
+            is_group_end: false,
+            tabs: 0,
+        });
+
+        for child in nested {
+            if let CssStmt::RuleSet { selector, body, .. } = child {
+                self.flatten(selector, body, output);
+            }
+        }
+    }
+}
+
+/// Whether `&` can refer to `parent` in native CSS without changing its meaning.
+///
+/// In native nesting, `&` behaves like `:is()`, which can't match
+/// pseudo-elements and takes the specificity of its most specific argument.
+fn is_valid_parent(parent: &SelectorList) -> bool {
+    let mut specificity = None;
+
+    parent.components.iter().all(|complex| {
+        if complex.min_specificity() != complex.max_specificity()
+            || *specificity.get_or_insert(complex.max_specificity()) != complex.max_specificity()
+        {
+            return false;
+        }
+
+        !contains_pseudo_element(complex)
+    })
+}
+
+fn contains_pseudo_element(complex: &ComplexSelector) -> bool {
+    complex.components.iter().any(|component| match component {
+        ComplexSelectorComponent::Compound(compound) => compound.components.iter().any(|simple| {
+            matches!(
+                simple,
+                SimpleSelector::Pseudo(Pseudo {
+                    is_class: false,
+                    ..
+                })
+            )
+        }),
+        ComplexSelectorComponent::Combinator(..) => false,
+    })
+}
@@ -0,0 +1,285 @@
+use std::collections::HashSet;
+
+use codemap::Spanned;
+
+use crate::{
+    ast::{CssStmt, Style},
+    common::QuoteKind,
+    interner::InternedString,
+    selector::{
+        ComplexSelector, ComplexSelectorComponent, ExtendedSelector, SelectorList, SimpleSelector,
+    },
+    value::Value,
+};
+
+use super::browsers::{BrowserTargets, Feature, Prefix, AT_RULES, PROPERTIES, PSEUDOS, VALUES};
+
+/// Adds the vendor prefixes required by the targeted browsers.
+///
+/// Prefixed declarations are inserted before their standard counterpart,
+/// unless the same rule already declares the prefixed property. Selectors
+/// containing prefixed pseudo-classes and pseudo-elements are emitted as
+/// separate rules, since a browser discards an entire rule if it doesn't
+/// recognize any part of its selector.
+pub(super) struct Prefixer<'a> {
+    targets: &'a BrowserTargets,
+}
+
+impl<'a> Prefixer<'a> {
+    pub fn new(targets: &'a BrowserTargets) -> Self {
+        Self { targets }
+    }
+
+    pub fn prefix(&self, stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+        self.prefix_stmts(stmts, None)
+    }
+
+    /// Whether `feature` should be added, given that the enclosing rule may
+    /// only be understood by browsers using the prefix `only`
+    fn is_required(&self, feature: &Feature, only: Option<Prefix>) -> bool {
+        only.map_or(true, |only| only == feature.prefix) && self.targets.requires(feature)
+    }
+
+    fn prefix_stmts(&self, stmts: Vec<CssStmt>, only: Option<Prefix>) -> Vec<CssStmt> {
+        let declared: HashSet<String> = stmts
+            .iter()
+            .filter_map(|stmt| match stmt {
+                CssStmt::Style(style) => Some(style.property.to_string().to_ascii_lowercase()),
+                _ => None,
+            })
+            .collect();
+
+        let mut output = Vec::with_capacity(stmts.len());
+
+        for stmt in stmts {
+            match stmt {
+                CssStmt::RuleSet {
+                    selector,
+                    body,
+                    is_group_end,
+                    tabs,
+                } => {
+                    for feature in PSEUDOS {
+                        if !self.is_required(feature, only) {
+                            continue;
+                        }
+
+                        if let Some(prefixed) =
+                            prefix_selector(&selector.as_selector_list(), feature)
+                        {
+                            output.push(CssStmt::RuleSet {
+                                selector: ExtendedSelector::new(prefixed),
+                                body: self.prefix_stmts(body.clone(), Some(feature.prefix)),
+                                is_group_end: false,
+                                tabs,
+                            });
+                        }
+                    }
+
+                    output.push(CssStmt::RuleSet {
+                        selector,
+                        body: self.prefix_stmts(body, only),
+                        is_group_end,
+                        tabs,
+                    });
+                }
+                CssStmt::Style(style) => {
+                    self.prefix_declaration(&style, &declared, only, &mut output);
+                    output.push(CssStmt::Style(style));
+                }
+                CssStmt::Media(mut media, is_group_end) => {
+                    media.body = self.prefix_stmts(media.body, only);
+                    output.push(CssStmt::Media(media, is_group_end));
+                }
+                CssStmt::Supports(mut supports, is_group_end) => {
+                    supports.body = self.prefix_stmts(supports.body, only);
+                    output.push(CssStmt::Supports(supports, is_group_end));
+                }
+                CssStmt::UnknownAtRule(mut at_rule, is_group_end) => {
+                    let name = at_rule.name.to_ascii_lowercase();
+
+                    for feature in AT_RULES {
+                        if feature.name == name && self.is_required(feature, only) {
+                            let mut prefixed = at_rule.clone();
+                            prefixed.name = feature.prefixed.to_owned();
+                            prefixed.body = self.prefix_stmts(prefixed.body, Some(feature.prefix));
+                            output.push(CssStmt::UnknownAtRule(prefixed, false));
+                        }
+                    }
+
+                    at_rule.body = self.prefix_stmts(at_rule.body, only);
+                    output.push(CssStmt::UnknownAtRule(at_rule, is_group_end));
+                }
+                CssStmt::KeyframesRuleSet(mut keyframes) => {
+                    keyframes.body = self.prefix_stmts(keyframes.body, only);
+                    output.push(CssStmt::KeyframesRuleSet(keyframes));
+                }
+                stmt @ (CssStmt::Comment(..) | CssStmt::Import(..)) => output.push(stmt),
+            }
+        }
+
+        output
+    }
+
+    /// Emits the prefixed forms of `style` that precede it, skipping those
+    /// already in `declared`
+    fn prefix_declaration(
+        &self,
+        style: &Style,
+        declared: &HashSet<String>,
+        only: Option<Prefix>,
+        output: &mut Vec<CssStmt>,
+    ) {
+        if style.declared_as_custom_property {
+            return;
+        }
+
+        let property = style.property.to_string().to_ascii_lowercase();
+
+        for feature in PROPERTIES {
+            if feature.name != property
+                || declared.contains(feature.prefixed)
+                || !self.is_required(feature, only)
+            {
+                continue;
+            }
+
+            // Properties such as `transition` may name other properties,
+            // which should be prefixed in the same way
+            let value = self
+                .prefix_property_names(&style.value.node, feature.prefix)
+                .unwrap_or_else(|| style.value.node.clone());
+
+            output.push(CssStmt::Style(Style {
+                property: InternedString::get_or_intern(feature.prefixed),
+                value: Box::new(Spanned {
+                    node: value,
+                    span: style.value.span,
+                }),
+                declared_as_custom_property: false,
+            }));
+        }
+
+        for (properties, feature) in VALUES {
+            if properties.map_or(false, |properties| !properties.contains(&property.as_str()))
+                || !self.is_required(feature, only)
+            {
+                continue;
+            }
+
+            if let Some(value) = map_strings(&style.value.node, &|s| prefix_value(s, feature)) {
+                output.push(CssStmt::Style(Style {
+                    property: style.property,
+                    value: Box::new(Spanned {
+                        node: value,
+                        span: style.value.span,
+                    }),
+                    declared_as_custom_property: false,
+                }));
+            }
+        }
+    }
+
+    fn prefix_property_names(&self, value: &Value, prefix: Prefix) -> Option<Value> {
+        map_strings(value, &|s| {
+            PROPERTIES
+                .iter()
+                .find(|feature| {
+                    feature.prefix == prefix
+                        && feature.name.eq_ignore_ascii_case(s)
+                        && self.targets.requires(feature)
+                })
+                .map(|feature| feature.prefixed.to_owned())
+        })
+    }
+}
+
+/// Replaces the unquoted strings within `value` using `f`, returning `None` if
+/// nothing was replaced
+fn map_strings(value: &Value, f: &dyn Fn(&str) -> Option<String>) -> Option<Value> {
+    match value {
+        Value::String(s, QuoteKind::None) => {
+            f(s).map(|replaced| Value::String(replaced, QuoteKind::None))
+        }
+        Value::List(elems, separator, brackets) => {
+            let mut changed = false;
+
+            let elems = elems
+                .iter()
+                .map(|elem| match map_strings(elem, f) {
+                    Some(elem) => {
+                        changed = true;
+                        elem
+                    }
+                    None => elem.clone(),
+                })
+                .collect();
+
+            if changed {
+                Some(Value::List(elems, *separator, *brackets))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn prefix_value(value: &str, feature: &Feature) -> Option<String> {
+    if feature.name.ends_with('(') {
+        let len = feature.name.len();
+
+        if value.len() >= len && value[..len].eq_ignore_ascii_case(feature.name) {
+            return Some(format!("{}{}", feature.prefixed, &value[len..]));
+        }
+
+        return None;
+    }
+
+    if value.eq_ignore_ascii_case(feature.name) {
+        Some(feature.prefixed.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Returns the complex selectors in `list` which contain the pseudo selector
+/// `feature`, with that pseudo selector replaced by its prefixed form
+fn prefix_selector(list: &SelectorList, feature: &Feature) -> Option<SelectorList> {
+    let components: Vec<ComplexSelector> = list
+        .components
+        .iter()
+        .filter_map(|complex| {
+            let mut complex = complex.clone();
+            let mut changed = false;
+
+            for component in &mut complex.components {
+                if let ComplexSelectorComponent::Compound(compound) = component {
+                    for simple in &mut compound.components {
+                        if let SimpleSelector::Pseudo(pseudo) = simple {
+                            if pseudo.name.eq_ignore_ascii_case(feature.name) {
+                                pseudo.name = feature.prefixed.to_owned();
+                                changed = true;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if changed {
+                Some(complex)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if components.is_empty() {
+        return None;
+    }
+
+    Some(SelectorList {
+        components,
+        span: list.span,
+    })
+}
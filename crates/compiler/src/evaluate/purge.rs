@@ -0,0 +1,362 @@
+use std::{borrow::Cow, collections::HashSet};
+
+use crate::{
+    ast::CssStmt,
+    common::{unvendor, QuoteKind},
+    error::SassResult,
+    options::extract_content_tokens,
+    selector::{
+        ComplexSelector, ComplexSelectorComponent, ExtendedSelector, Namespace, SelectorList,
+        SimpleSelector,
+    },
+    value::Value,
+    Fs, UsedSelectors,
+};
+
+/// Removes CSS which can't match any element in the documents described by a
+/// [`UsedSelectors`].
+pub(super) struct Purger<'a> {
+    used: &'a UsedSelectors,
+    /// The words found in `used.content` and in the files at `used.content_paths`
+    content_tokens: HashSet<String>,
+}
+
+impl<'a> Purger<'a> {
+    pub fn new(used: &'a UsedSelectors, fs: &dyn Fs) -> SassResult<Self> {
+        let mut content_tokens = used.content_tokens.clone();
+
+        for path in &used.content_paths {
+            let content = fs.read(path)?;
+            extract_content_tokens(&String::from_utf8_lossy(&content), &mut content_tokens);
+        }
+
+        Ok(Self {
+            used,
+            content_tokens,
+        })
+    }
+
+    pub fn purge(&self, stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+        let stmts = self.purge_rules(stmts);
+
+        let mut animations = HashSet::new();
+
+        if collect_animation_names(&stmts, &mut animations) {
+            self.purge_keyframes(stmts, &animations)
+        } else {
+            stmts
+        }
+    }
+
+    fn is_safelisted(&self, name: &str) -> bool {
+        self.used
+            .safelist
+            .iter()
+            .any(|pattern| matches_pattern(pattern, name))
+    }
+
+    fn is_used(&self, name: &str, names: &HashSet<String>) -> bool {
+        // Selectors of a kind that wasn't described are assumed to match
+        (names.is_empty() && !self.used.has_content)
+            || names.contains(name)
+            || self.content_tokens.contains(name)
+            || self.is_safelisted(name)
+    }
+
+    fn purge_rules(&self, stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+        let mut output: Vec<CssStmt> = Vec::with_capacity(stmts.len());
+
+        for stmt in stmts {
+            let is_group_end = stmt.is_group_end();
+
+            match self.purge_stmt(stmt) {
+                Some(stmt) => output.push(stmt),
+                None => {
+                    if is_group_end {
+                        if let Some(prev) = output.last_mut() {
+                            prev.set_group_end();
+                        }
+                    }
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Purges `stmt` and its children, returning `None` if it should be
+    /// removed entirely
+    fn purge_stmt(&self, stmt: CssStmt) -> Option<CssStmt> {
+        Some(match stmt {
+            CssStmt::RuleSet {
+                selector,
+                body,
+                is_group_end,
+                tabs,
+            } => {
+                let (components, is_unchanged, span) = {
+                    let list = selector.as_selector_list();
+
+                    let components: Vec<ComplexSelector> = list
+                        .components
+                        .iter()
+                        .filter(|complex| self.can_match(complex))
+                        .cloned()
+                        .collect();
+
+                    let is_unchanged = components.len() == list.components.len();
+
+                    (components, is_unchanged, list.span)
+                };
+
+                if components.is_empty() {
+                    return None;
+                }
+
+                let selector = if is_unchanged {
+                    selector
+                } else {
+                    ExtendedSelector::new(SelectorList { components, span })
+                };
+
+                CssStmt::RuleSet {
+                    selector,
+                    body: self.purge_rules(body),
+                    is_group_end,
+                    tabs,
+                }
+            }
+            CssStmt::Media(mut media, is_group_end) => {
+                media.body = self.purge_rules(media.body);
+
+                if media.body.iter().all(CssStmt::is_invisible) {
+                    return None;
+                }
+
+                CssStmt::Media(media, is_group_end)
+            }
+            CssStmt::Supports(mut supports, is_group_end) => {
+                supports.body = self.purge_rules(supports.body);
+
+                if supports.body.iter().all(CssStmt::is_invisible) {
+                    return None;
+                }
+
+                CssStmt::Supports(supports, is_group_end)
+            }
+            CssStmt::UnknownAtRule(mut at_rule, is_group_end) => {
+                // The children of rules such as `@font-face` aren't style rules
+                if at_rule
+                    .body
+                    .iter()
+                    .any(|child| matches!(child, CssStmt::RuleSet { .. }))
+                {
+                    at_rule.body = self.purge_rules(at_rule.body);
+                }
+
+                CssStmt::UnknownAtRule(at_rule, is_group_end)
+            }
+            stmt @ (CssStmt::Style(..)
+            | CssStmt::Comment(..)
+            | CssStmt::KeyframesRuleSet(..)
+            | CssStmt::Import(..)) => stmt,
+        })
+    }
+
+    /// Whether `complex` could match an element in a document using only the
+    /// described names
+    fn can_match(&self, complex: &ComplexSelector) -> bool {
+        complex.components.iter().all(|component| match component {
+            ComplexSelectorComponent::Compound(compound) => compound
+                .components
+                .iter()
+                .all(|simple| self.can_match_simple(simple)),
+            ComplexSelectorComponent::Combinator(..) => true,
+        })
+    }
+
+    fn can_match_simple(&self, simple: &SimpleSelector) -> bool {
+        match simple {
+            SimpleSelector::Class(name) => self.is_used(&unescape(name), &self.used.classes),
+            SimpleSelector::Id(name) => self.is_used(&unescape(name), &self.used.ids),
+            SimpleSelector::Type(name) => {
+                // Elements in other namespaces, such as SVG, aren't checked
+                !matches!(name.namespace, Namespace::None | Namespace::Empty)
+                    || self.is_used(&name.ident.to_ascii_lowercase(), &self.used.elements)
+            }
+            SimpleSelector::Pseudo(pseudo) => match (&pseudo.selector, pseudo.normalized_name()) {
+                // The arguments of selector pseudo-classes such as `:not()` and
+                // `:has()` may refer to elements other than the one matched
+                (Some(list), "is" | "matches" | "any" | "where") => list
+                    .components
+                    .iter()
+                    .any(|complex| self.can_match(complex)),
+                _ => true,
+            },
+            SimpleSelector::Universal(..)
+            | SimpleSelector::Attribute(..)
+            | SimpleSelector::Parent(..)
+            | SimpleSelector::Placeholder(..) => true,
+        }
+    }
+
+    fn purge_keyframes(&self, stmts: Vec<CssStmt>, animations: &HashSet<String>) -> Vec<CssStmt> {
+        let mut output: Vec<CssStmt> = Vec::with_capacity(stmts.len());
+
+        for stmt in stmts {
+            let stmt = match stmt {
+                CssStmt::UnknownAtRule(at_rule, is_group_end)
+                    if unvendor(&at_rule.name).eq_ignore_ascii_case("keyframes") =>
+                {
+                    let name = at_rule
+                        .params
+                        .trim()
+                        .trim_matches(|c| c == '"' || c == '\'');
+
+                    if !animations.contains(name) && !self.is_safelisted(name) {
+                        if is_group_end {
+                            if let Some(prev) = output.last_mut() {
+                                prev.set_group_end();
+                            }
+                        }
+
+                        continue;
+                    }
+
+                    CssStmt::UnknownAtRule(at_rule, is_group_end)
+                }
+                CssStmt::Media(mut media, is_group_end) => {
+                    media.body = self.purge_keyframes(media.body, animations);
+                    CssStmt::Media(media, is_group_end)
+                }
+                CssStmt::Supports(mut supports, is_group_end) => {
+                    supports.body = self.purge_keyframes(supports.body, animations);
+                    CssStmt::Supports(supports, is_group_end)
+                }
+                stmt => stmt,
+            };
+
+            output.push(stmt);
+        }
+
+        output
+    }
+}
+
+/// Adds the names of the animations used in `stmts` to `animations`,
+/// returning `false` if they can't all be determined, as when an animation
+/// name is set using `var()`
+fn collect_animation_names(stmts: &[CssStmt], animations: &mut HashSet<String>) -> bool {
+    fn collect_idents(value: &Value, animations: &mut HashSet<String>) -> bool {
+        match value {
+            Value::String(s, QuoteKind::None) if s.contains('(') => {
+                !s.to_ascii_lowercase().starts_with("var(")
+            }
+            Value::String(s, ..) => {
+                animations.insert(s.clone());
+                true
+            }
+            Value::List(elems, ..) => elems.iter().all(|elem| collect_idents(elem, animations)),
+            _ => true,
+        }
+    }
+
+    stmts.iter().all(|stmt| match stmt {
+        CssStmt::Style(style) => {
+            let property = style.property.to_string().to_ascii_lowercase();
+
+            if style.declared_as_custom_property
+                || !matches!(unvendor(&property), "animation" | "animation-name")
+            {
+                return true;
+            }
+
+            collect_idents(&style.value.node, animations)
+        }
+        CssStmt::RuleSet { body, .. } => collect_animation_names(body, animations),
+        CssStmt::Media(media, ..) => collect_animation_names(&media.body, animations),
+        CssStmt::Supports(supports, ..) => collect_animation_names(&supports.body, animations),
+        CssStmt::UnknownAtRule(at_rule, ..) => collect_animation_names(&at_rule.body, animations),
+        CssStmt::Comment(..) | CssStmt::KeyframesRuleSet(..) | CssStmt::Import(..) => true,
+    })
+}
+
+/// Whether `name` matches `pattern`, in which `*` matches any sequence of
+/// characters
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+
+    // `split` always yields at least one part
+    let first = parts.next().unwrap();
+
+    let mut rest = match name.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts: Vec<&str> = parts.collect();
+
+    let last = match parts.split_last() {
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(idx) => rest = &rest[idx + part.len()..],
+                    None => return false,
+                }
+            }
+
+            last
+        }
+        None => return rest.is_empty(),
+    };
+
+    rest.ends_with(last)
+}
+
+/// Resolves the escapes in an identifier, such as the `\:` in
+/// `.hover\:underline`
+pub(super) fn unescape(ident: &str) -> Cow<'_, str> {
+    if !ident.contains('\\') {
+        return Cow::Borrowed(ident);
+    }
+
+    let mut unescaped = String::with_capacity(ident.len());
+    let mut chars = ident.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        let mut code_point = String::new();
+        while code_point.len() < 6 {
+            match chars.peek() {
+                Some(c) if c.is_ascii_hexdigit() => {
+                    code_point.push(*c);
+                    chars.next();
+                }
+                _ => break,
+            }
+        }
+
+        if code_point.is_empty() {
+            unescaped.extend(chars.next());
+            continue;
+        }
+
+        // A single whitespace character terminates a hex escape
+        if chars.peek().map_or(false, |c| c.is_ascii_whitespace()) {
+            chars.next();
+        }
+
+        unescaped.push(
+            u32::from_str_radix(&code_point, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        );
+    }
+
+    Cow::Owned(unescaped)
+}
@@ -0,0 +1,399 @@
+use crate::{
+    ast::{CssStmt, Style},
+    common::{unvendor, ListSeparator, QuoteKind},
+    interner::InternedString,
+    value::{SassNumber, Value},
+};
+
+/// Mirrors a stylesheet for right-to-left languages.
+///
+/// Physical directions in property names and values are swapped, so that
+/// `margin-left: 1px` becomes `margin-right: 1px`, four-value shorthands and
+/// `border-radius` swap their left and right components, and horizontal
+/// translations are negated.
+///
+/// A `/*rtl:ignore*/` comment leaves the following declaration or rule
+/// unchanged, and everything between `/*rtl:begin:ignore*/` and
+/// `/*rtl:end:ignore*/` is left unchanged. These comments are removed from the
+/// mirrored stylesheet.
+pub(crate) fn mirror(stmts: Vec<CssStmt>) -> Vec<CssStmt> {
+    mirror_stmts(stmts, false)
+}
+
+enum Directive {
+    Ignore,
+    BeginIgnore,
+    EndIgnore,
+}
+
+/// Parses an `rtl:` control comment, returning `None` for any other comment
+fn directive(comment: &str) -> Option<Option<Directive>> {
+    let text = comment.strip_prefix("/*")?.strip_suffix("*/")?;
+    let text = text.trim_start_matches('!').trim().to_ascii_lowercase();
+    let name = text.strip_prefix("rtl:")?;
+
+    Some(match name.trim() {
+        "ignore" => Some(Directive::Ignore),
+        "begin:ignore" => Some(Directive::BeginIgnore),
+        "end:ignore" => Some(Directive::EndIgnore),
+        // Other directives are removed, but otherwise have no effect
+        _ => None,
+    })
+}
+
+fn mirror_stmts(stmts: Vec<CssStmt>, ignore: bool) -> Vec<CssStmt> {
+    let mut output: Vec<CssStmt> = Vec::with_capacity(stmts.len());
+    let mut ignore_next = false;
+    let mut in_ignored_block = false;
+
+    for stmt in stmts {
+        if let CssStmt::Comment(comment, ..) = &stmt {
+            if let Some(directive) = directive(comment) {
+                match directive {
+                    Some(Directive::Ignore) => ignore_next = true,
+                    Some(Directive::BeginIgnore) => in_ignored_block = true,
+                    Some(Directive::EndIgnore) => in_ignored_block = false,
+                    None => {}
+                }
+
+                if stmt.is_group_end() {
+                    if let Some(prev) = output.last_mut() {
+                        prev.set_group_end();
+                    }
+                }
+
+                continue;
+            }
+
+            output.push(stmt);
+            continue;
+        }
+
+        let ignore_stmt = ignore || in_ignored_block || ignore_next;
+        ignore_next = false;
+
+        output.push(mirror_stmt(stmt, ignore_stmt));
+    }
+
+    output
+}
+
+/// Mirrors `stmt` and its children, or only removes control comments from its
+/// children if `ignore` is true
+fn mirror_stmt(stmt: CssStmt, ignore: bool) -> CssStmt {
+    match stmt {
+        CssStmt::RuleSet {
+            selector,
+            body,
+            is_group_end,
+            tabs,
+        } => CssStmt::RuleSet {
+            selector,
+            body: mirror_stmts(body, ignore),
+            is_group_end,
+            tabs,
+        },
+        CssStmt::Style(style) if !ignore => CssStmt::Style(mirror_declaration(style)),
+        CssStmt::Media(mut media, is_group_end) => {
+            media.body = mirror_stmts(media.body, ignore);
+            CssStmt::Media(media, is_group_end)
+        }
+        CssStmt::Supports(mut supports, is_group_end) => {
+            supports.body = mirror_stmts(supports.body, ignore);
+            CssStmt::Supports(supports, is_group_end)
+        }
+        CssStmt::UnknownAtRule(mut at_rule, is_group_end) => {
+            at_rule.body = mirror_stmts(at_rule.body, ignore);
+            CssStmt::UnknownAtRule(at_rule, is_group_end)
+        }
+        CssStmt::KeyframesRuleSet(mut keyframes) => {
+            keyframes.body = mirror_stmts(keyframes.body, ignore);
+            CssStmt::KeyframesRuleSet(keyframes)
+        }
+        stmt @ (CssStmt::Style(..) | CssStmt::Comment(..) | CssStmt::Import(..)) => stmt,
+    }
+}
+
+fn mirror_declaration(mut style: Style) -> Style {
+    if style.declared_as_custom_property {
+        return style;
+    }
+
+    let property = style.property.to_string().to_ascii_lowercase();
+
+    if let Some(mirrored) = swap_sides(&property) {
+        style.property = InternedString::get_or_intern(mirrored);
+    }
+
+    let value = &mut style.value.node;
+
+    match unvendor(&property) {
+        "margin" | "padding" | "border-width" | "border-color" | "border-style" | "inset"
+        | "scroll-margin" | "scroll-padding" => mirror_box_shorthand(value),
+        "border-radius" => mirror_border_radius(value),
+        "transform" => map_idents(value, &mirror_transform_function),
+        "cursor" => map_idents(value, &mirror_cursor),
+        "direction" => map_idents(value, &|ident| match ident.to_ascii_lowercase().as_str() {
+            "ltr" => Some("rtl".to_owned()),
+            "rtl" => Some("ltr".to_owned()),
+            _ => None,
+        }),
+        // These refer to other properties by name
+        "transition" | "transition-property" | "will-change" => map_idents(value, &|ident| {
+            if ident.bytes().all(|b| b.is_ascii_alphabetic() || b == b'-') {
+                swap_sides(&ident.to_ascii_lowercase())
+            } else {
+                None
+            }
+        }),
+        // Other values, such as animation and grid area names, may contain
+        // `left` or `right` without referring to a direction, so only the
+        // keywords themselves are swapped, and only for properties which
+        // accept them
+        "float"
+        | "clear"
+        | "text-align"
+        | "text-align-last"
+        | "justify-content"
+        | "justify-items"
+        | "justify-self"
+        | "background"
+        | "background-position"
+        | "background-position-x"
+        | "mask"
+        | "mask-position"
+        | "object-position"
+        | "transform-origin"
+        | "perspective-origin" => {
+            map_idents(value, &|ident| match ident.to_ascii_lowercase().as_str() {
+                "left" => Some("right".to_owned()),
+                "right" => Some("left".to_owned()),
+                _ => None,
+            })
+        }
+        _ => {}
+    }
+
+    style
+}
+
+/// Swaps `left` and `right` within a hyphen-separated identifier such as
+/// `border-top-left-radius`, returning `None` if it contains neither
+fn swap_sides(ident: &str) -> Option<String> {
+    let mut changed = false;
+
+    let swapped: Vec<&str> = ident
+        .split('-')
+        .map(|segment| match segment {
+            "left" => {
+                changed = true;
+                "right"
+            }
+            "right" => {
+                changed = true;
+                "left"
+            }
+            segment => segment,
+        })
+        .collect();
+
+    if changed {
+        Some(swapped.join("-"))
+    } else {
+        None
+    }
+}
+
+/// Replaces each unquoted string within `value` for which `f` returns a value
+fn map_idents(value: &mut Value, f: &dyn Fn(&str) -> Option<String>) {
+    match value {
+        Value::String(s, QuoteKind::None) => {
+            if let Some(replaced) = f(s) {
+                *s = replaced;
+            }
+        }
+        Value::List(elems, ..) => {
+            for elem in elems {
+                map_idents(elem, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Splits a trailing `!important` from the components of a space-separated
+/// shorthand
+fn shorthand_components(value: &mut Value) -> Option<&mut [Value]> {
+    match value {
+        Value::List(elems, ListSeparator::Space, ..) => {
+            let len = match elems.last() {
+                Some(Value::String(s, QuoteKind::None)) if s == "!important" => elems.len() - 1,
+                _ => elems.len(),
+            };
+
+            Some(&mut elems[..len])
+        }
+        _ => None,
+    }
+}
+
+/// Swaps the right and left components of a shorthand such as `margin`, which
+/// are listed as top, right, bottom, left
+fn mirror_box_shorthand(value: &mut Value) {
+    if let Some(components) = shorthand_components(value) {
+        if components.len() == 4 {
+            components.swap(1, 3);
+        }
+    }
+}
+
+/// Swaps the horizontal corners of `border-radius`, whose components are
+/// listed as top-left, top-right, bottom-right, bottom-left
+fn mirror_border_radius(value: &mut Value) {
+    let elems = match value {
+        Value::List(radii, ListSeparator::Slash, ..) => {
+            for radius in radii {
+                mirror_border_radius(radius);
+            }
+
+            return;
+        }
+        Value::List(elems, ListSeparator::Space, ..) => elems,
+        _ => return,
+    };
+
+    let important = match elems.last() {
+        Some(Value::String(s, QuoteKind::None)) if s == "!important" => elems.pop(),
+        _ => None,
+    };
+
+    // In `1px 2px / 3px 4px`, the horizontal and vertical radii are separated
+    // by a number such as `2px/3px` that was written as a division
+    let slash_idx = elems.iter().position(|elem| {
+        matches!(
+            elem,
+            Value::Dimension(SassNumber {
+                as_slash: Some(..),
+                ..
+            })
+        )
+    });
+
+    match slash_idx {
+        Some(idx) => {
+            let (before, after) = match &elems[idx] {
+                Value::Dimension(SassNumber {
+                    as_slash: Some(as_slash),
+                    ..
+                }) => (as_slash.0.clone(), as_slash.1.clone()),
+                _ => unreachable!(),
+            };
+
+            let mut horizontal = elems[..idx].to_vec();
+            horizontal.push(Value::Dimension(before));
+
+            let mut vertical = vec![Value::Dimension(after)];
+            vertical.extend_from_slice(&elems[idx + 1..]);
+
+            mirror_corners(&mut horizontal);
+            mirror_corners(&mut vertical);
+
+            *elems = horizontal;
+            elems.push(Value::String("/".to_owned(), QuoteKind::None));
+            elems.extend(vertical);
+        }
+        None => mirror_corners(elems),
+    }
+
+    elems.extend(important);
+}
+
+fn mirror_corners(radii: &mut Vec<Value>) {
+    match radii.len() {
+        2 => radii.swap(0, 1),
+        // `a b c` is short for `a b c b`, which mirrors to `b a b c`
+        3 => {
+            radii.swap(0, 1);
+            radii.insert(2, radii[0].clone());
+        }
+        4 => {
+            radii.swap(0, 1);
+            radii.swap(2, 3);
+        }
+        _ => {}
+    }
+}
+
+/// Negates the horizontal component of a translation such as `translateX(10px)`
+fn mirror_transform_function(function: &str) -> Option<String> {
+    let open = function.find('(')?;
+    let name = function[..open].to_ascii_lowercase();
+
+    if !matches!(name.as_str(), "translate" | "translatex" | "translate3d") {
+        return None;
+    }
+
+    let args = function[open + 1..].strip_suffix(')')?;
+
+    // The first argument ends at the first top-level comma
+    let mut depth = 0;
+    let end = args
+        .char_indices()
+        .find(|&(_, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => return true,
+                _ => {}
+            }
+            false
+        })
+        .map_or(args.len(), |(idx, _)| idx);
+
+    let (x, rest) = args.split_at(end);
+
+    Some(format!(
+        "{}({}{})",
+        &function[..open],
+        negate(x.trim()),
+        rest
+    ))
+}
+
+fn negate(length: &str) -> String {
+    if let Some(positive) = length.strip_prefix('-') {
+        if !positive.starts_with('-') {
+            return positive.to_owned();
+        }
+    }
+
+    let is_number = length
+        .bytes()
+        .next()
+        .map_or(false, |b| b.is_ascii_digit() || b == b'.' || b == b'+');
+
+    if length.bytes().all(|b| b == b'0' || b == b'.') {
+        length.to_owned()
+    } else if is_number {
+        format!("-{}", length.trim_start_matches('+'))
+    } else {
+        format!("calc(-1 * {})", length)
+    }
+}
+
+fn mirror_cursor(cursor: &str) -> Option<String> {
+    Some(
+        match cursor.to_ascii_lowercase().as_str() {
+            "e-resize" => "w-resize",
+            "w-resize" => "e-resize",
+            "ne-resize" => "nw-resize",
+            "nw-resize" => "ne-resize",
+            "se-resize" => "sw-resize",
+            "sw-resize" => "se-resize",
+            "nesw-resize" => "nwse-resize",
+            "nwse-resize" => "nesw-resize",
+            _ => return None,
+        }
+        .to_owned(),
+    )
+}
@@ -0,0 +1,309 @@
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use codemap::{CodeMap, Span};
+
+use crate::{
+    ast::CssStmt,
+    common::QuoteKind,
+    error::SassResult,
+    utils::{fnv1a, FNV_OFFSET_BASIS},
+    value::Value,
+    Fs, UrlRewrite,
+};
+
+/// Rewrites relative `url()`s, which are written relative to the stylesheet
+/// containing them, so that they still refer to the same file from the
+/// compiled CSS.
+///
+/// The stylesheet containing each `url()` is found using the span of the
+/// declaration's value. A URL passed to a mixin declared in another file is
+/// therefore resolved from the file containing the mixin's declaration.
+pub(super) struct UrlRewriter<'a> {
+    fs: &'a dyn Fs,
+    map: &'a CodeMap,
+    /// The directory that rewritten URLs are relative to
+    base_dir: PathBuf,
+    /// A public path to prefix rewritten URLs with
+    public_path: Option<&'a str>,
+    cache_bust: bool,
+    /// The directory of each stylesheet, keyed by file name
+    source_dirs: HashMap<String, PathBuf>,
+}
+
+impl<'a> UrlRewriter<'a> {
+    /// `entry_path` is the path of the stylesheet being compiled
+    pub fn new(
+        fs: &'a dyn Fs,
+        map: &'a CodeMap,
+        rewrite: UrlRewrite<'a>,
+        cache_bust: bool,
+        entry_path: &Path,
+    ) -> Self {
+        let (base_dir, public_path) = match rewrite {
+            UrlRewrite::RelativeTo(dir) => (resolve(fs, dir), None),
+            UrlRewrite::PublicPath(public_path) => (
+                parent_dir(&resolve(fs, entry_path)),
+                Some(public_path.trim_end_matches('/')),
+            ),
+        };
+
+        Self {
+            fs,
+            map,
+            base_dir,
+            public_path,
+            cache_bust,
+            source_dirs: HashMap::new(),
+        }
+    }
+
+    pub fn rewrite(&mut self, mut stmts: Vec<CssStmt>) -> SassResult<Vec<CssStmt>> {
+        self.rewrite_stmts(&mut stmts)?;
+        Ok(stmts)
+    }
+
+    fn rewrite_stmts(&mut self, stmts: &mut [CssStmt]) -> SassResult<()> {
+        for stmt in stmts {
+            match stmt {
+                CssStmt::Style(style) => {
+                    let span = style.value.span;
+                    self.rewrite_value(&mut style.value.node, span)?;
+                }
+                CssStmt::RuleSet { body, .. } => self.rewrite_stmts(body)?,
+                CssStmt::Media(media, ..) => self.rewrite_stmts(&mut media.body)?,
+                CssStmt::Supports(supports, ..) => self.rewrite_stmts(&mut supports.body)?,
+                CssStmt::UnknownAtRule(at_rule, ..) => self.rewrite_stmts(&mut at_rule.body)?,
+                CssStmt::KeyframesRuleSet(keyframes) => {
+                    self.rewrite_stmts(&mut keyframes.body)?;
+                }
+                CssStmt::Comment(..) | CssStmt::Import(..) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rewrite_value(&mut self, value: &mut Value, span: Span) -> SassResult<()> {
+        match value {
+            // Both `url(a.png)` and `url("a.png")` are emitted as unquoted
+            // strings, as are functions such as `image-set()` containing them
+            Value::String(s, QuoteKind::None) => {
+                if let Some(rewritten) = self.rewrite_string(s, span)? {
+                    *s = rewritten;
+                }
+            }
+            Value::List(elems, ..) => {
+                for elem in elems {
+                    self.rewrite_value(elem, span)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites each `url()` within `s`, returning `None` if none were changed
+    fn rewrite_string(&mut self, s: &str, span: Span) -> SassResult<Option<String>> {
+        let lower = s.to_ascii_lowercase();
+
+        let mut output = String::with_capacity(s.len());
+        let mut last = 0;
+        let mut changed = false;
+
+        for (start, _) in lower.match_indices("url(") {
+            // Skip functions such as `my-url()`, and `url(`s within an
+            // argument that has already been handled
+            if start < last
+                || s[..start]
+                    .chars()
+                    .next_back()
+                    .map_or(false, |c| c.is_alphanumeric() || c == '-' || c == '_')
+            {
+                continue;
+            }
+
+            let (url_start, url_end) = match url_argument(s, start + "url(".len()) {
+                Some(range) => range,
+                None => continue,
+            };
+
+            if let Some(rewritten) = self.rewrite_url(&s[url_start..url_end], span)? {
+                output.push_str(&s[last..url_start]);
+                output.push_str(&rewritten);
+                last = url_end;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return Ok(None);
+        }
+
+        output.push_str(&s[last..]);
+
+        Ok(Some(output))
+    }
+
+    fn rewrite_url(&mut self, url: &str, span: Span) -> SassResult<Option<String>> {
+        if !is_relative_url(url) {
+            return Ok(None);
+        }
+
+        let (path, suffix) = url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+        let (query, fragment) = suffix.split_at(suffix.find('#').unwrap_or(suffix.len()));
+
+        let asset = normalize(&self.source_dir(span).join(path));
+
+        // Either path may only be relative if the file system couldn't
+        // canonicalize it, in which case they can't be compared
+        if asset.is_absolute() != self.base_dir.is_absolute() {
+            return Err((
+                format!(
+                    "Unable to rewrite url({}), as \"{}\" and \"{}\" couldn't both be resolved to absolute paths.",
+                    url,
+                    asset.to_string_lossy(),
+                    self.base_dir.to_string_lossy()
+                ),
+                span,
+            )
+                .into());
+        }
+
+        let mut rewritten = relative_path(&asset, &self.base_dir);
+
+        if let Some(public_path) = self.public_path {
+            rewritten = format!("{}/{}", public_path, rewritten);
+        }
+
+        rewritten.push_str(query);
+
+        if self.cache_bust {
+            // Assets which can't be read are still rewritten, but without a hash
+            if let Ok(contents) = self.fs.read(&asset) {
+                rewritten.push(if query.is_empty() { '?' } else { '&' });
+                rewritten.push_str("v=");
+                rewritten.push_str(&format!(
+                    "{:08x}",
+                    fnv1a(FNV_OFFSET_BASIS, &contents) as u32
+                ));
+            }
+        }
+
+        rewritten.push_str(fragment);
+
+        Ok(Some(rewritten))
+    }
+
+    /// The directory of the stylesheet containing `span`
+    fn source_dir(&mut self, span: Span) -> &Path {
+        let name = self.map.look_up_span(span).file.name().to_owned();
+        let fs = self.fs;
+
+        self.source_dirs
+            .entry(name)
+            .or_insert_with_key(|name| parent_dir(&resolve(fs, Path::new(name))))
+    }
+}
+
+/// Finds the URL within the `url()` whose argument begins at `start`,
+/// returning its start and end, excluding any quotes
+fn url_argument(s: &str, start: usize) -> Option<(usize, usize)> {
+    let rest = &s[start..];
+    let leading_whitespace = rest.len() - rest.trim_start().len();
+    let start = start + leading_whitespace;
+    let rest = &s[start..];
+
+    match rest.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = rest[1..].find(quote)? + 1;
+            Some((start + 1, start + end))
+        }
+        _ => {
+            let end = rest.find(')')?;
+            Some((start, start + rest[..end].trim_end().len()))
+        }
+    }
+}
+
+/// Whether `url` is relative to the stylesheet it was written in, rather than
+/// being absolute, a fragment, or using a scheme such as `data:`
+fn is_relative_url(url: &str) -> bool {
+    if url.is_empty() || url.starts_with(['/', '#', '~']) {
+        return false;
+    }
+
+    // A scheme is terminated by a colon, which may not appear in the first
+    // segment of a relative path
+    let first_segment = url.find(['/', '?', '#']).map_or(url, |idx| &url[..idx]);
+
+    !first_segment.contains(':')
+}
+
+/// Canonicalizes `path` if possible, so that it can be compared to the
+/// canonical paths of imported stylesheets
+///
+/// Paths which don't exist yet, such as an output directory, are resolved by
+/// canonicalizing their nearest existing ancestor.
+fn resolve(fs: &dyn Fs, path: &Path) -> PathBuf {
+    // An empty path refers to the current directory
+    let path = if path.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        path
+    };
+
+    if let Ok(canonical) = fs.canonicalize(path) {
+        return normalize(&canonical);
+    }
+
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => resolve(fs, parent).join(name),
+        _ => normalize(path),
+    }
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    path.parent().map_or_else(PathBuf::new, Path::to_path_buf)
+}
+
+/// Resolves `.` and `..` in `path` without accessing the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Returns the path of `path` relative to the directory `base`, separated by
+/// `/` regardless of platform
+fn relative_path(path: &Path, base: &Path) -> String {
+    let path: Vec<Component> = path.components().collect();
+    let base: Vec<Component> = base.components().collect();
+
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+
+    let mut segments: Vec<String> = vec!["..".to_owned(); base.len() - common];
+
+    segments.extend(
+        path[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+    );
+
+    segments.join("/")
+}
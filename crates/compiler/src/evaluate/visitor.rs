@@ -1,6 +1,6 @@
 use std::{
     cell::{Cell, RefCell},
-    collections::{BTreeMap, BTreeSet, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     ffi::OsStr,
     fmt,
     iter::FromIterator,
@@ -15,12 +15,13 @@ use indexmap::IndexSet;
 use crate::{
     ast::*,
     builtin::{
+        deprecated_global_function,
         meta::if_arguments,
         modules::{
             declare_module_color, declare_module_list, declare_module_map, declare_module_math,
             declare_module_meta, declare_module_selector, declare_module_string, Module,
         },
-        GLOBAL_FUNCTIONS,
+        Builtin, GLOBAL_FUNCTIONS,
     },
     common::{unvendor, BinaryOp, Identifier, ListSeparator, QuoteKind, UnaryOp},
     error::{SassError, SassResult},
@@ -39,15 +40,27 @@ use crate::{
         ArgList, CalculationArg, CalculationName, Number, SassCalculation, SassFunction, SassMap,
         SassNumber, UserDefinedFunction, Value,
     },
-    ContextFlags, InputSyntax, Options,
+    ContextFlags, Deprecation, InputSyntax, Options, OutputStyle,
 };
 
 use super::{
     bin_op::{add, cmp, div, mul, rem, single_eq, sub},
+    css_modules::CssModules,
     css_tree::{CssTree, CssTreeIdx},
     env::Environment,
+    media_grouping::group_media_queries,
+    minify::Minifier,
+    nesting::NativeNesting,
+    prefixer::Prefixer,
+    purge::Purger,
+    rtl::mirror,
+    urls::UrlRewriter,
 };
 
+/// The number of times each kind of deprecation warning is emitted before any
+/// further warnings of that kind are omitted, unless running in verbose mode
+const MAX_DEPRECATION_REPETITIONS: usize = 5;
+
 trait UserDefinedCallable {
     fn name(&self) -> Identifier;
     fn arguments(&self) -> &ArgumentDeclaration;
@@ -83,6 +96,16 @@ impl UserDefinedCallable for AstMixin {
     }
 }
 
+impl UserDefinedCallable for Arc<AstMixin> {
+    fn name(&self) -> Identifier {
+        self.name
+    }
+
+    fn arguments(&self) -> &ArgumentDeclaration {
+        &self.args
+    }
+}
+
 impl UserDefinedCallable for Arc<CallableContentBlock> {
     fn name(&self) -> Identifier {
         Identifier::from("@content")
@@ -93,6 +116,22 @@ impl UserDefinedCallable for Arc<CallableContentBlock> {
     }
 }
 
+/// A builtin function whose arguments are declared using Sass syntax
+struct BuiltinWithSignature {
+    name: Identifier,
+    arguments: Arc<ArgumentDeclaration>,
+}
+
+impl UserDefinedCallable for BuiltinWithSignature {
+    fn name(&self) -> Identifier {
+        self.name
+    }
+
+    fn arguments(&self) -> &ArgumentDeclaration {
+        &self.arguments
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct CallableContentBlock {
     content: AstContentBlock,
@@ -106,11 +145,29 @@ pub struct Visitor<'a> {
     pub(crate) flags: ContextFlags,
     pub(crate) env: Environment,
     pub(crate) style_rule_ignoring_at_root: Option<ExtendedSelector>,
+    /// Whether the current style rule was defined in a plain CSS stylesheet
+    style_rule_is_plain_css: bool,
+    /// The selectors as written of style rules emitted using native CSS
+    /// nesting, keyed by [`ExtendedSelector::id`]
+    nested_style_rules: HashMap<usize, SelectorList>,
+    /// Style rules which have had siblings emitted after them, and so can no
+    /// longer contain natively nested rules without changing the order of the
+    /// output
+    escaped_style_rules: HashSet<CssTreeIdx>,
+    /// The parent of each style rule nested within another, both keyed by
+    /// [`ExtendedSelector::id`]. Only tracked for [`OutputStyle::Nested`] output
+    style_rule_parents: HashMap<usize, usize>,
     // avoid emitting duplicate warnings for the same span
     pub(crate) warnings_emitted: HashSet<Span>,
+    /// The number of times each deprecation warning has been emitted, used to
+    /// avoid flooding the user with repetitive warnings
+    deprecation_counts: BTreeMap<Deprecation, usize>,
     pub(crate) media_queries: Option<Vec<MediaQuery>>,
     pub(crate) media_query_sources: Option<IndexSet<MediaQuery>>,
     pub(crate) extender: ExtensionStore,
+    /// The user-defined modules used or forwarded by the module currently being
+    /// evaluated
+    upstream_modules: Vec<Arc<RefCell<Module>>>,
 
     /// The complete file path of the current file being visited. Imports are
     /// resolved relative to this path
@@ -131,6 +188,13 @@ pub struct Visitor<'a> {
     /// has been seen in the past. In the majority of cases, files are imported
     /// at most once.
     files_seen: BTreeSet<PathBuf>,
+    /// Files which were loaded from a load path, rather than relative to the
+    /// entry point. Warnings from these files are silenced by
+    /// [`Options::quiet_deps`]
+    dependencies: BTreeSet<PathBuf>,
+    /// Parsed argument declarations of builtins created with
+    /// [`crate::Builtin::with_signature`], keyed by the id of the builtin
+    builtin_signatures: BTreeMap<usize, Arc<ArgumentDeclaration>>,
 }
 
 impl<'a> Visitor<'a> {
@@ -150,12 +214,18 @@ impl<'a> Visitor<'a> {
         Self {
             declaration_name: None,
             style_rule_ignoring_at_root: None,
+            style_rule_is_plain_css: false,
+            nested_style_rules: HashMap::new(),
+            escaped_style_rules: HashSet::new(),
+            style_rule_parents: HashMap::new(),
             flags,
             warnings_emitted: HashSet::new(),
+            deprecation_counts: BTreeMap::new(),
             media_queries: None,
             media_query_sources: None,
             env: Environment::new(),
             extender,
+            upstream_modules: Vec::new(),
             css_tree: CssTree::new(),
             parent: None,
             current_import_path,
@@ -169,6 +239,8 @@ impl<'a> Visitor<'a> {
             map,
             import_cache: BTreeMap::new(),
             files_seen: BTreeSet::new(),
+            dependencies: BTreeSet::new(),
+            builtin_signatures: BTreeMap::new(),
         }
     }
 
@@ -178,6 +250,10 @@ impl<'a> Visitor<'a> {
         self.is_plain_css = style_sheet.is_plain_css;
         mem::swap(&mut self.current_import_path, &mut style_sheet.url);
 
+        for warning in style_sheet.parse_time_warnings {
+            self.emit_deprecation(warning.deprecation, &warning.message, warning.span)?;
+        }
+
         for stmt in style_sheet.body {
             let result = self.visit_stmt(stmt)?;
             debug_assert!(result.is_none());
@@ -191,20 +267,321 @@ impl<'a> Visitor<'a> {
         Ok(())
     }
 
-    pub(crate) fn finish(mut self) -> Vec<CssStmt> {
-        let mut finished_tree = self.css_tree.finish();
+    pub(crate) fn finish(mut self) -> SassResult<Vec<CssStmt>> {
+        let finished_tree = self.finish_tree()?;
+        self.transform_output(finished_tree, self.options.rtl)
+    }
+
+    /// Like [`Self::finish`], but produces both the stylesheet and its mirror
+    /// for right-to-left languages, regardless of [`crate::Options::rtl`]
+    pub(crate) fn finish_with_rtl(mut self) -> SassResult<(Vec<CssStmt>, Vec<CssStmt>)> {
+        let finished_tree = self.finish_tree()?;
+
+        Ok((
+            self.transform_output(finished_tree.clone(), false)?,
+            self.transform_output(finished_tree, true)?,
+        ))
+    }
+
+    /// Like [`Self::finish`], but rewrites local class and `@keyframes` names
+    /// as CSS Modules do, also producing a map from the names as written to
+    /// those generated
+    ///
+    /// `path` is used to make the generated names unique to the stylesheet.
+    pub(crate) fn finish_as_css_module(
+        mut self,
+        path: &str,
+    ) -> SassResult<(Vec<CssStmt>, BTreeMap<String, String>)> {
+        let finished_tree = self.finish_tree()?;
+
+        let mut css_modules = CssModules::new(path);
+        let finished_tree = css_modules.rewrite(finished_tree)?;
+
+        Ok((
+            self.transform_output(finished_tree, self.options.rtl)?,
+            css_modules.finish(),
+        ))
+    }
+
+    fn finish_tree(&mut self) -> SassResult<Vec<CssStmt>> {
+        Self::extend_modules(&self.extender, &self.upstream_modules)?;
+
+        let omitted = self
+            .deprecation_counts
+            .values()
+            .map(|&count| count.saturating_sub(MAX_DEPRECATION_REPETITIONS))
+            .sum::<usize>();
+
+        if omitted > 0 {
+            eprintln!(
+                "Warning: {} repetitive deprecation warnings omitted.\nRun in verbose mode to see all warnings.",
+                omitted
+            );
+        }
+
+        let mut finished_tree = mem::replace(&mut self.css_tree, CssTree::new()).finish();
+
+        if self.options.native_nesting {
+            finished_tree = NativeNesting::new(&self.nested_style_rules).apply(finished_tree);
+        }
+
+        Ok(finished_tree)
+    }
+
+    /// Applies the output transforms enabled in the options to `finished_tree`
+    fn transform_output(
+        &self,
+        mut finished_tree: Vec<CssStmt>,
+        rtl: bool,
+    ) -> SassResult<Vec<CssStmt>> {
+        if let Some(rewrite) = self.options.url_rewrite {
+            finished_tree = UrlRewriter::new(
+                self.options.fs,
+                &*self.map,
+                rewrite,
+                self.options.cache_bust_urls,
+                &self.current_import_path,
+            )
+            .rewrite(finished_tree)?;
+        }
+
+        // Mirroring must happen before comments are removed by the minifier,
+        // as they may contain control directives
+        if rtl {
+            finished_tree = mirror(finished_tree);
+        }
+
+        if let Some(used) = self.options.used_selectors {
+            finished_tree = Purger::new(used, self.options.fs)?.purge(finished_tree);
+        }
+
+        if let Some(grouping) = self.options.media_query_grouping {
+            finished_tree = group_media_queries(finished_tree, grouping);
+        }
+
+        if let Some(targets) = self.options.browser_targets {
+            finished_tree = Prefixer::new(targets).prefix(finished_tree);
+        }
+
+        if self.options.minify {
+            finished_tree = Minifier.minify(finished_tree);
+        }
+
+        if !self.style_rule_parents.is_empty() {
+            Self::indent_nested_style_rules(&self.style_rule_parents, &mut finished_tree);
+        }
+
         if self.import_nodes.is_empty() {
-            finished_tree
+            Ok(finished_tree)
         } else {
-            self.import_nodes.append(&mut finished_tree);
-            self.import_nodes
+            let mut stmts = self.import_nodes.clone();
+            stmts.append(&mut finished_tree);
+            Ok(stmts)
+        }
+    }
+
+    /// Indents style rules which were nested within a parent that has its own
+    /// declarations, as in Ruby Sass's nested output
+    ///
+    /// `parents` maps the id of each nested style rule to that of its parent.
+    fn indent_nested_style_rules(parents: &HashMap<usize, usize>, stmts: &mut [CssStmt]) {
+        fn children(stmt: &mut CssStmt) -> Option<&mut Vec<CssStmt>> {
+            match stmt {
+                CssStmt::RuleSet { body, .. } => Some(body),
+                CssStmt::Media(media, ..) => Some(&mut media.body),
+                CssStmt::Supports(supports, ..) => Some(&mut supports.body),
+                CssStmt::UnknownAtRule(at_rule, ..) => Some(&mut at_rule.body),
+                CssStmt::Style(..)
+                | CssStmt::Comment(..)
+                | CssStmt::KeyframesRuleSet(..)
+                | CssStmt::Import(..) => None,
+            }
+        }
+
+        fn find_rules_with_declarations(stmts: &mut [CssStmt], found: &mut HashSet<usize>) {
+            for stmt in stmts {
+                if let CssStmt::RuleSet { selector, body, .. } = stmt {
+                    if body
+                        .iter()
+                        .any(|child| matches!(child, CssStmt::Style(..)) && !child.is_invisible())
+                    {
+                        found.insert(selector.id());
+                    }
+                }
+
+                if let Some(children) = children(stmt) {
+                    find_rules_with_declarations(children, found);
+                }
+            }
+        }
+
+        fn tabs_for(id: usize, parents: &HashMap<usize, usize>, found: &HashSet<usize>) -> usize {
+            match parents.get(&id) {
+                Some(&parent) => {
+                    tabs_for(parent, parents, found) + usize::from(found.contains(&parent))
+                }
+                None => 0,
+            }
+        }
+
+        fn assign_tabs(
+            stmts: &mut [CssStmt],
+            parents: &HashMap<usize, usize>,
+            found: &HashSet<usize>,
+        ) {
+            for stmt in stmts {
+                if let CssStmt::RuleSet { selector, tabs, .. } = stmt {
+                    *tabs = tabs_for(selector.id(), parents, found);
+                }
+
+                if let Some(children) = children(stmt) {
+                    assign_tabs(children, parents, found);
+                }
+            }
+        }
+
+        let mut rules_with_declarations = HashSet::new();
+        find_rules_with_declarations(stmts, &mut rules_with_declarations);
+        assign_tabs(stmts, parents, &rules_with_declarations);
+    }
+
+    /// Applies the extensions defined in each module to the CSS of the modules it
+    /// transitively uses, and errors if a mandatory extension was never satisfied.
+    ///
+    /// `root` is the extension store of the module at the root of the graph, and
+    /// `upstream` are the modules it uses.
+    fn extend_modules(root: &ExtensionStore, upstream: &[Arc<RefCell<Module>>]) -> SassResult<()> {
+        type ModuleRef = Arc<RefCell<Module>>;
+
+        fn visit_module(
+            module: &Arc<RefCell<Module>>,
+            seen: &mut HashSet<*const RefCell<Module>>,
+            sorted: &mut VecDeque<Arc<RefCell<Module>>>,
+        ) {
+            if !seen.insert(Arc::as_ptr(module)) {
+                return;
+            }
+
+            if let Module::Environment { upstream, .. } = &*(**module).borrow() {
+                for upstream in upstream {
+                    visit_module(upstream, seen, sorted);
+                }
+            }
+
+            sorted.push_front(Arc::clone(module));
+        }
+
+        // All modules transitively used by `root`, ordered such that each module
+        // comes before the modules it uses
+        let mut sorted = VecDeque::new();
+        let mut seen = HashSet::new();
+        for module in upstream {
+            visit_module(module, &mut seen, &mut sorted);
+        }
+
+        // The modules directly downstream of each module, where `None` is `root`.
+        // Because `sorted` is in topological order, these are complete by the time
+        // each module is extended
+        let mut downstream: HashMap<*const RefCell<Module>, Vec<Option<ModuleRef>>> =
+            HashMap::new();
+
+        let root_selectors = root.simple_selectors();
+        let mut unsatisfied =
+            root.extensions_where_target(|target| !root_selectors.contains(target));
+
+        if !root.is_empty() {
+            for module in upstream {
+                downstream
+                    .entry(Arc::as_ptr(module))
+                    .or_default()
+                    .push(None);
+            }
+        }
+
+        for module in sorted {
+            let downstream_modules = downstream.remove(&Arc::as_ptr(&module)).unwrap_or_default();
+            let downstream_modules = downstream_modules
+                .iter()
+                .map(|module| module.as_ref().map(|module| (**module).borrow()))
+                .collect::<Vec<_>>();
+            let downstream_stores = downstream_modules
+                .iter()
+                .map(|module| match module.as_deref() {
+                    Some(Module::Environment {
+                        extension_store, ..
+                    }) => extension_store,
+                    Some(..) => unreachable!("only user-defined modules are upstream"),
+                    None => root,
+                })
+                .collect::<Vec<_>>();
+
+            let mut module_ref = (*module).borrow_mut();
+            let (extension_store, upstream) = match &mut *module_ref {
+                Module::Environment {
+                    extension_store,
+                    upstream,
+                    ..
+                } => (extension_store, upstream),
+                _ => unreachable!("only user-defined modules are upstream"),
+            };
+
+            // Take a snapshot of the simple selectors so that an extension isn't
+            // considered satisfied by a selector added by a sibling extension
+            let original_selectors = extension_store.simple_selectors();
+
+            unsatisfied.extend(
+                extension_store
+                    .extensions_where_target(|target| !original_selectors.contains(target)),
+            );
+
+            if !downstream_stores.is_empty() {
+                extension_store.add_extensions(&downstream_stores)?;
+            }
+
+            if extension_store.is_empty() {
+                continue;
+            }
+
+            for upstream in upstream.iter() {
+                downstream
+                    .entry(Arc::as_ptr(upstream))
+                    .or_default()
+                    .push(Some(Arc::clone(&module)));
+            }
+
+            unsatisfied.retain(|(target, ..)| !original_selectors.contains(target));
+        }
+
+        match unsatisfied.first() {
+            Some((target, span)) => Err((
+                format!(
+                    "The target selector was not found.\nUse \"@extend {} !optional\" to avoid this error.",
+                    target
+                ),
+                *span,
+            )
+                .into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Extends the modules used by `module`, as though it were the root of the
+    /// module graph.
+    pub(crate) fn extend_module_css(module: &Arc<RefCell<Module>>) -> SassResult<()> {
+        match &*(**module).borrow() {
+            Module::Environment {
+                extension_store,
+                upstream,
+                ..
+            } => Self::extend_modules(extension_store, upstream),
+            _ => Ok(()),
         }
     }
 
     fn visit_return_rule(&mut self, ret: AstReturn) -> SassResult<Option<Value>> {
         let val = self.visit_expr(ret.val)?;
 
-        Ok(Some(self.without_slash(val)))
+        Ok(Some(self.without_slash(val, ret.span)?))
     }
 
     // todo: we really don't have to return Option<Value> from all of these children
@@ -269,6 +646,7 @@ impl<'a> Visitor<'a> {
                 forward_rule.url.as_path(),
                 Some(Arc::clone(&new_configuration)),
                 false,
+                false,
                 forward_rule.span,
                 |visitor, module, _| {
                     visitor.env.forward_module(module, forward_rule.clone());
@@ -318,6 +696,7 @@ impl<'a> Visitor<'a> {
                 url.as_path(),
                 None,
                 false,
+                false,
                 forward_rule.span,
                 move |visitor, module, _| {
                     visitor.env.forward_module(module, forward_rule.clone());
@@ -359,7 +738,7 @@ impl<'a> Visitor<'a> {
 
             // todo: superfluous clone?
             let value = self.visit_expr(variable.expr.node.clone())?;
-            let value = self.without_slash(value);
+            let value = self.without_slash(value, variable.expr.span)?;
 
             new_values.insert(
                 variable.name.node,
@@ -514,6 +893,7 @@ impl<'a> Visitor<'a> {
                         selector,
                         body: Vec::new(),
                         is_group_end: false,
+                        tabs: 0,
                     };
 
                     visitor.with_parent(
@@ -543,8 +923,8 @@ impl<'a> Visitor<'a> {
         &mut self,
         stylesheet: StyleSheet,
         configuration: Option<Arc<RefCell<Configuration>>>,
-        // todo: different errors based on this
-        _names_in_errors: bool,
+        names_in_errors: bool,
+        nest_css: bool,
     ) -> SassResult<Arc<RefCell<Module>>> {
         let url = stylesheet.url.clone();
 
@@ -552,29 +932,21 @@ impl<'a> Visitor<'a> {
         if let Some(already_loaded) = self.modules.get(&stylesheet.url) {
             let current_configuration =
                 configuration.unwrap_or_else(|| Arc::clone(&self.configuration));
+            let current_configuration = (*current_configuration).borrow();
 
-            if !current_configuration.borrow().is_implicit() {
-                //   if (!_moduleConfigurations[url]!.sameOriginal(currentConfiguration) &&
-                //       currentConfiguration is ExplicitConfiguration) {
-                //     var message = namesInErrors
-                //         ? "${p.prettyUri(url)} was already loaded, so it can't be "
-                //             "configured using \"with\"."
-                //         : "This module was already loaded, so it can't be configured using "
-                //             "\"with\".";
-
-                //     var existingSpan = _moduleNodes[url]?.span;
-                //     var configurationSpan = configuration == null
-                //         ? currentConfiguration.nodeWithSpan.span
-                //         : null;
-                //     var secondarySpans = {
-                //       if (existingSpan != null) existingSpan: "original load",
-                //       if (configurationSpan != null) configurationSpan: "configuration"
-                //     };
-
-                //     throw secondarySpans.isEmpty
-                //         ? _exception(message)
-                //         : _multiSpanException(message, "new load", secondarySpans);
-                //   }
+            // todo: allow configurations that share an original configuration
+            if names_in_errors
+                && !current_configuration.is_implicit()
+                && !current_configuration.is_empty()
+            {
+                return Err((
+                    format!(
+                        "{} was already loaded, so it can't be configured using \"with\".",
+                        url.to_string_lossy()
+                    ),
+                    current_configuration.span.unwrap(),
+                )
+                    .into());
             }
 
             return Ok(Arc::clone(already_loaded));
@@ -582,11 +954,14 @@ impl<'a> Visitor<'a> {
 
         let env = Environment::new();
         let mut extension_store = ExtensionStore::new(self.empty_span);
+        let mut upstream_modules = Vec::new();
 
         self.with_environment::<SassResult<()>, _>(env.new_closure(), |visitor| {
             let old_parent = visitor.parent;
             mem::swap(&mut visitor.extender, &mut extension_store);
+            mem::swap(&mut visitor.upstream_modules, &mut upstream_modules);
             let old_style_rule = visitor.style_rule_ignoring_at_root.take();
+            let old_style_rule_is_plain_css = mem::take(&mut visitor.style_rule_is_plain_css);
             let old_media_queries = visitor.media_queries.take();
             let old_declaration_name = visitor.declaration_name.take();
             let old_in_unknown_at_rule = visitor.flags.in_unknown_at_rule();
@@ -597,7 +972,15 @@ impl<'a> Visitor<'a> {
             } else {
                 None
             };
-            visitor.parent = None;
+
+            // `meta.load-css()` emits the module's CSS at the include site, so the
+            // current style rule and media queries are kept in scope
+            if nest_css {
+                visitor.style_rule_ignoring_at_root = old_style_rule.clone();
+                visitor.media_queries = old_media_queries.clone();
+            } else {
+                visitor.parent = None;
+            }
             visitor.flags.set(ContextFlags::IN_UNKNOWN_AT_RULE, false);
             visitor
                 .flags
@@ -613,7 +996,9 @@ impl<'a> Visitor<'a> {
             // visitor.end_of_imports = old_end_of_imports;
             // visitor.out_of_order_imports = old_out_of_order_imports;
             mem::swap(&mut visitor.extender, &mut extension_store);
+            mem::swap(&mut visitor.upstream_modules, &mut upstream_modules);
             visitor.style_rule_ignoring_at_root = old_style_rule;
+            visitor.style_rule_is_plain_css = old_style_rule_is_plain_css;
             visitor.media_queries = old_media_queries;
             visitor.declaration_name = old_declaration_name;
             visitor
@@ -633,7 +1018,7 @@ impl<'a> Visitor<'a> {
             Ok(())
         })?;
 
-        let module = env.to_module(extension_store);
+        let module = env.to_module(extension_store, upstream_modules);
 
         self.modules.insert(url, Arc::clone(&module));
 
@@ -645,6 +1030,7 @@ impl<'a> Visitor<'a> {
         url: &Path,
         configuration: Option<Arc<RefCell<Configuration>>>,
         names_in_errors: bool,
+        nest_css: bool,
         span: Span,
         callback: impl Fn(&mut Self, Arc<RefCell<Module>>, StyleSheet) -> SassResult<()>,
     ) -> SassResult<()> {
@@ -703,10 +1089,21 @@ impl<'a> Visitor<'a> {
 
         self.active_modules.insert(canonical_url.clone());
 
-        let module = self.execute(stylesheet.clone(), configuration, names_in_errors)?;
+        let module = self.execute(stylesheet.clone(), configuration, names_in_errors, nest_css)?;
 
         self.active_modules.remove(&canonical_url);
 
+        // `meta.load-css()` emits the module's CSS independently, so it isn't
+        // extended by the loading module
+        if !nest_css
+            && !self
+                .upstream_modules
+                .iter()
+                .any(|upstream| Arc::ptr_eq(upstream, &module))
+        {
+            self.upstream_modules.push(Arc::clone(&module));
+        }
+
         callback(self, module, stylesheet)?;
 
         Ok(())
@@ -720,7 +1117,7 @@ impl<'a> Visitor<'a> {
 
             for var in use_rule.configuration {
                 let value = self.visit_expr(var.expr.node)?;
-                let value = self.without_slash(value);
+                let value = self.without_slash(value, var.expr.span)?;
                 values.insert(
                     var.name.node,
                     ConfiguredValue::explicit(value, var.name.span.merge(var.expr.span)),
@@ -741,6 +1138,7 @@ impl<'a> Visitor<'a> {
             &use_rule.url,
             Some(Arc::clone(&configuration)),
             false,
+            false,
             span,
             |visitor, module, _| {
                 visitor.env.add_module(namespace, module, span)?;
@@ -797,8 +1195,14 @@ impl<'a> Visitor<'a> {
     ///
     /// <https://sass-lang.com/documentation/at-rules/import#finding-the-file>
     /// <https://sass-lang.com/documentation/at-rules/import#load-paths>
-    #[allow(clippy::cognitive_complexity, clippy::redundant_clone)]
     pub fn find_import(&self, path: &Path) -> Option<PathBuf> {
+        self.resolve_import(path).map(|(path, _)| path)
+    }
+
+    /// Locate an imported file, additionally returning whether it was found
+    /// using a load path rather than relative to the current file
+    #[allow(clippy::cognitive_complexity, clippy::redundant_clone)]
+    fn resolve_import(&self, path: &Path) -> Option<(PathBuf, bool)> {
         let path_buf = if path.is_absolute() {
             path.into()
         } else {
@@ -809,7 +1213,7 @@ impl<'a> Visitor<'a> {
         };
 
         macro_rules! try_path {
-            ($path:expr) => {
+            ($path:expr, $from_load_path:expr) => {
                 let path = $path;
                 let dirname = path.parent().unwrap_or_else(|| Path::new(""));
                 let basename = path.file_name().unwrap_or_else(|| OsStr::new(".."));
@@ -817,11 +1221,11 @@ impl<'a> Visitor<'a> {
                 let partial = dirname.join(format!("_{}", basename.to_str().unwrap()));
 
                 if self.options.fs.is_file(&path) {
-                    return Some(path.to_path_buf());
+                    return Some((path.to_path_buf(), $from_load_path));
                 }
 
                 if self.options.fs.is_file(&partial) {
-                    return Some(partial);
+                    return Some((partial, $from_load_path));
                 }
             };
         }
@@ -831,37 +1235,40 @@ impl<'a> Visitor<'a> {
             || path_buf.extension() == Some(OsStr::new("css"))
         {
             let extension = path_buf.extension().unwrap();
-            try_path!(path_buf.with_extension(format!(".import{}", extension.to_str().unwrap())));
-            try_path!(path_buf);
+            try_path!(
+                path_buf.with_extension(format!(".import{}", extension.to_str().unwrap())),
+                false
+            );
+            try_path!(path_buf, false);
             // todo: consider load paths
             return None;
         }
 
         macro_rules! try_path_with_extensions {
-            ($path:expr) => {
+            ($path:expr, $from_load_path:expr) => {
                 let path = $path;
-                try_path!(path.with_extension("import.sass"));
-                try_path!(path.with_extension("import.scss"));
-                try_path!(path.with_extension("import.css"));
-                try_path!(path.with_extension("sass"));
-                try_path!(path.with_extension("scss"));
-                try_path!(path.with_extension("css"));
+                try_path!(path.with_extension("import.sass"), $from_load_path);
+                try_path!(path.with_extension("import.scss"), $from_load_path);
+                try_path!(path.with_extension("import.css"), $from_load_path);
+                try_path!(path.with_extension("sass"), $from_load_path);
+                try_path!(path.with_extension("scss"), $from_load_path);
+                try_path!(path.with_extension("css"), $from_load_path);
             };
         }
 
-        try_path_with_extensions!(path_buf.clone());
+        try_path_with_extensions!(path_buf.clone(), false);
 
         if self.options.fs.is_dir(&path_buf) {
-            try_path_with_extensions!(path_buf.join("index"));
+            try_path_with_extensions!(path_buf.join("index"), false);
         }
 
         for load_path in &self.options.load_paths {
             let path_buf = load_path.join(path);
 
-            try_path_with_extensions!(&path_buf);
+            try_path_with_extensions!(&path_buf, true);
 
             if self.options.fs.is_dir(&path_buf) {
-                try_path_with_extensions!(path_buf.join("index"));
+                try_path_with_extensions!(path_buf.join("index"), true);
             }
         }
 
@@ -893,8 +1300,15 @@ impl<'a> Visitor<'a> {
         _for_import: bool,
         span: Span,
     ) -> SassResult<StyleSheet> {
-        if let Some(name) = self.find_import(url.as_ref()) {
+        if let Some((name, from_load_path)) = self.resolve_import(url.as_ref()) {
             let name = self.options.fs.canonicalize(&name).unwrap_or(name);
+
+            // Files loaded through a load path are dependencies, as is anything
+            // they load in turn
+            if from_load_path || self.dependencies.contains(&self.current_import_path) {
+                self.dependencies.insert(name.clone());
+            }
+
             if let Some(style_sheet) = self.import_cache.get(&name) {
                 return Ok(style_sheet.clone());
             }
@@ -1167,6 +1581,15 @@ impl<'a> Visitor<'a> {
             return Ok(None);
         }
 
+        if self.options.native_nesting {
+            let mut current_parent_idx = self.parent;
+
+            while let Some(parent_idx) = current_parent_idx {
+                self.escaped_style_rules.insert(parent_idx);
+                current_parent_idx = self.css_tree.child_to_parent.get(&parent_idx).copied();
+            }
+        }
+
         let inner_copy = if !included.is_empty() {
             let inner_copy = self
                 .css_tree
@@ -1333,7 +1756,7 @@ impl<'a> Visitor<'a> {
                 },
                 &self.media_queries,
                 extend_rule.span,
-            );
+            )?;
         }
 
         Ok(None)
@@ -1440,6 +1863,7 @@ impl<'a> Visitor<'a> {
                                 selector,
                                 body: Vec::new(),
                                 is_group_end: false,
+                                tabs: 0,
                             };
 
                             visitor.with_parent(
@@ -1554,6 +1978,7 @@ impl<'a> Visitor<'a> {
                         selector,
                         body: Vec::new(),
                         is_group_end: false,
+                        tabs: 0,
                     };
 
                     visitor.with_parent(
@@ -1583,8 +2008,14 @@ impl<'a> Visitor<'a> {
         Ok(None)
     }
 
+    /// Whether `span` is located in a file loaded from a load path
+    fn is_in_dependency(&self, span: Span) -> bool {
+        let file = self.map.find_file(span.low());
+        self.dependencies.contains(Path::new(file.name()))
+    }
+
     pub(crate) fn emit_warning(&mut self, message: &str, span: Span) {
-        if self.options.quiet {
+        if self.options.quiet || (self.options.quiet_deps && self.is_in_dependency(span)) {
             return;
         }
         let loc = self.map.look_up_span(span);
@@ -1597,6 +2028,56 @@ impl<'a> Visitor<'a> {
         );
     }
 
+    pub(crate) fn emit_deprecation(
+        &mut self,
+        deprecation: Deprecation,
+        message: &str,
+        span: Span,
+    ) -> SassResult<()> {
+        if self.options.quiet_deps && self.is_in_dependency(span) {
+            return Ok(());
+        }
+
+        if self.options.fatal_deprecations.contains(&deprecation) {
+            return Err((
+                format!(
+                    "{}\n\nThis is only an error because you've set the {} deprecation to be fatal.\nRemove this setting if you need to keep using this feature.",
+                    message, deprecation
+                ),
+                span,
+            )
+                .into());
+        }
+
+        if self.options.quiet
+            || self.options.silenced_deprecations.contains(&deprecation)
+            || !self.warnings_emitted.insert(span)
+        {
+            return Ok(());
+        }
+
+        if !self.options.verbose {
+            let count = self.deprecation_counts.entry(deprecation).or_insert(0);
+            *count += 1;
+
+            if *count > MAX_DEPRECATION_REPETITIONS {
+                return Ok(());
+            }
+        }
+
+        let loc = self.map.look_up_span(span);
+        eprintln!(
+            "Deprecation Warning [{}]: {}\n    ./{}:{}:{}",
+            deprecation,
+            message,
+            loc.file.name(),
+            loc.begin.line + 1,
+            loc.begin.column + 1
+        );
+
+        Ok(())
+    }
+
     fn visit_warn_rule(&mut self, warn_rule: AstWarn) -> SassResult<()> {
         if self.warnings_emitted.insert(warn_rule.span) {
             let value = self.visit_expr(warn_rule.value)?;
@@ -1648,6 +2129,10 @@ impl<'a> Visitor<'a> {
 
         if let Some(through) = through {
             while parent != CssTree::ROOT && through(self.css_tree.get(parent).as_ref().unwrap()) {
+                if self.options.native_nesting {
+                    self.escaped_style_rules.insert(parent);
+                }
+
                 let grandparent = self.css_tree.child_to_parent.get(&parent).copied();
                 debug_assert!(
                     grandparent.is_some(),
@@ -1740,42 +2225,61 @@ impl<'a> Visitor<'a> {
             .env
             .get_mixin(include_stmt.name, include_stmt.namespace)?;
 
-        match mixin {
-            Mixin::Builtin(mixin) => {
-                if include_stmt.content.is_some() {
-                    return Err(("Mixin doesn't accept a content block.", include_stmt.span).into());
-                }
+        let AstInclude {
+            args,
+            content,
+            name,
+            span,
+            ..
+        } = include_stmt;
 
-                let args = self.eval_args(include_stmt.args, include_stmt.name.span)?;
-                mixin(args, self)?;
+        if content.is_some() && !mixin.accepts_content() {
+            return Err(("Mixin doesn't accept a content block.", span).into());
+        }
 
-                Ok(None)
-            }
-            Mixin::UserDefined(mixin, env) => {
-                if include_stmt.content.is_some() && !mixin.has_content {
-                    return Err(("Mixin doesn't accept a content block.", include_stmt.span).into());
-                }
+        let callable_content = content.map(|c| {
+            Arc::new(CallableContentBlock {
+                content: c,
+                env: self.env.new_closure(),
+            })
+        });
+
+        self.run_mixin(
+            mixin,
+            MaybeEvaledArguments::Invocation(args),
+            callable_content,
+            name.span,
+        )?;
 
-                let AstInclude { args, content, .. } = include_stmt;
+        Ok(None)
+    }
 
+    /// Include `mixin` with the given arguments and content block, as done by
+    /// both `@include` and `meta.apply()`
+    pub(crate) fn run_mixin(
+        &mut self,
+        mixin: Mixin,
+        args: MaybeEvaledArguments,
+        content: Option<Arc<CallableContentBlock>>,
+        span: Span,
+    ) -> SassResult<()> {
+        match mixin {
+            Mixin::Builtin { mixin, .. } => {
+                let args = self.eval_maybe_args(args, span)?;
+                self.with_content(content, |visitor| mixin(args, visitor))
+            }
+            Mixin::UserDefined(mixin, env) => {
                 let old_in_mixin = self.flags.in_mixin();
                 self.flags.set(ContextFlags::IN_MIXIN, true);
 
-                let callable_content = content.map(|c| {
-                    Arc::new(CallableContentBlock {
-                        content: c,
-                        env: self.env.new_closure(),
-                    })
-                });
-
                 self.run_user_defined_callable::<_, (), _>(
-                    MaybeEvaledArguments::Invocation(args),
+                    args,
                     mixin,
                     &env,
-                    include_stmt.name.span,
+                    span,
                     |mixin, visitor| {
-                        visitor.with_content(callable_content, |visitor| {
-                            for stmt in mixin.body {
+                        visitor.with_content(content, |visitor| {
+                            for stmt in mixin.body.clone() {
                                 let result = visitor.visit_stmt(stmt)?;
                                 debug_assert!(result.is_none());
                             }
@@ -1786,7 +2290,7 @@ impl<'a> Visitor<'a> {
 
                 self.flags.set(ContextFlags::IN_MIXIN, old_in_mixin);
 
-                Ok(None)
+                Ok(())
             }
         }
     }
@@ -1794,7 +2298,7 @@ impl<'a> Visitor<'a> {
     fn visit_mixin_decl(&mut self, mixin: AstMixin) {
         self.env.insert_mixin(
             mixin.name,
-            Mixin::UserDefined(mixin, self.env.new_closure()),
+            Mixin::UserDefined(Arc::new(mixin), self.env.new_closure()),
         );
     }
 
@@ -1808,7 +2312,7 @@ impl<'a> Visitor<'a> {
 
         'outer: for val in list {
             if each_stmt.variables.len() == 1 {
-                let val = self.without_slash(val);
+                let val = self.without_slash(val, each_stmt.list_span)?;
                 self.env
                     .scopes_mut()
                     .insert_var_last(each_stmt.variables[0], val);
@@ -1818,7 +2322,7 @@ impl<'a> Visitor<'a> {
                         .into_iter()
                         .chain(std::iter::once(Value::Null).cycle()),
                 ) {
-                    let val = self.without_slash(val);
+                    let val = self.without_slash(val, each_stmt.list_span)?;
                     self.env.scopes_mut().insert_var_last(var, val);
                 }
             }
@@ -2015,8 +2519,17 @@ impl<'a> Visitor<'a> {
             }
         }
 
+        if decl.is_global && !self.env.global_var_exists(decl.name) {
+            let message = if self.env.at_root() {
+                "As of Dart Sass 2.0.0, !global assignments won't be able to declare new variables.\n\nSince this assignment is at the root of the stylesheet, the !global flag is\nunnecessary and can safely be removed.".to_owned()
+            } else {
+                format!("As of Dart Sass 2.0.0, !global assignments won't be able to declare new variables.\n\nRecommendation: add `${}: null` at the stylesheet root.", decl.name)
+            };
+            self.emit_deprecation(Deprecation::NewGlobal, &message, decl.span)?;
+        }
+
         let value = self.visit_expr(decl.value)?;
-        let value = self.without_slash(value);
+        let value = self.without_slash(value, decl.span)?;
 
         self.env.insert_var(
             name,
@@ -2092,20 +2605,32 @@ impl<'a> Visitor<'a> {
         self.serialize(result, quote, span)
     }
 
-    #[allow(clippy::unused_self)]
-    fn without_slash(&mut self, v: Value) -> Value {
-        match v {
-            Value::Dimension(SassNumber { .. }) if v.as_slash().is_some() => {
-                // todo: emit warning. we don't currently because it can be quite loud
-                // self.emit_warning(
-                //     Cow::Borrowed("Using / for division is deprecated and will be removed at some point in the future"),
-                //     self.empty_span,
-                // );
+    fn without_slash(&mut self, v: Value, span: Span) -> SassResult<Value> {
+        if let Value::Dimension(
+            number @ SassNumber {
+                as_slash: Some(..), ..
+            },
+        ) = &v
+        {
+            fn recommendation(number: &SassNumber, span: Span) -> SassResult<String> {
+                match &number.as_slash {
+                    Some(slash) => Ok(format!(
+                        "math.div({}, {})",
+                        recommendation(&slash.0, span)?,
+                        recommendation(&slash.1, span)?
+                    )),
+                    None => Value::Dimension(number.clone()).inspect(span),
+                }
             }
-            _ => {}
+
+            let message = format!(
+                "Using / for division is deprecated and will be removed in Dart Sass 2.0.0.\n\nRecommendation: {}\n\nMore info and automated migrator: https://sass-lang.com/d/slash-div",
+                recommendation(number, span)?
+            );
+            self.emit_deprecation(Deprecation::SlashDiv, &message, span)?;
         }
 
-        v.without_slash()
+        Ok(v.without_slash())
     }
 
     fn eval_maybe_args(
@@ -2128,14 +2653,14 @@ impl<'a> Visitor<'a> {
 
         for expr in arguments.positional {
             let val = self.visit_expr(expr)?;
-            positional.push(self.without_slash(val));
+            positional.push(self.without_slash(val, span)?);
         }
 
         let mut named = BTreeMap::new();
 
         for (key, expr) in arguments.named {
             let val = self.visit_expr(expr)?;
-            named.insert(key, self.without_slash(val));
+            named.insert(key, self.without_slash(val, span)?);
         }
 
         if arguments.rest.is_none() {
@@ -2157,27 +2682,27 @@ impl<'a> Visitor<'a> {
             Value::List(elems, list_separator, _) => {
                 let mut list = elems
                     .into_iter()
-                    .map(|e| self.without_slash(e))
-                    .collect::<Vec<_>>();
+                    .map(|e| self.without_slash(e, span))
+                    .collect::<SassResult<Vec<_>>>()?;
                 positional.append(&mut list);
                 separator = list_separator;
             }
             Value::ArgList(arglist) => {
                 // todo: superfluous clone
                 for (&key, value) in arglist.keywords() {
-                    named.insert(key, self.without_slash(value.clone()));
+                    named.insert(key, self.without_slash(value.clone(), span)?);
                 }
 
                 let mut list = arglist
                     .elems
                     .into_iter()
-                    .map(|e| self.without_slash(e))
-                    .collect::<Vec<_>>();
+                    .map(|e| self.without_slash(e, span))
+                    .collect::<SassResult<Vec<_>>>()?;
                 positional.append(&mut list);
                 separator = arglist.separator;
             }
             _ => {
-                positional.push(self.without_slash(rest));
+                positional.push(self.without_slash(rest, span)?);
             }
         }
 
@@ -2222,7 +2747,7 @@ impl<'a> Visitor<'a> {
         for (key, val) in rest {
             match key.node {
                 Value::String(text, ..) => {
-                    let val = self.without_slash(val);
+                    let val = self.without_slash(val, key.span)?;
                     named.insert(Identifier::from(text), val);
                 }
                 _ => {
@@ -2293,7 +2818,7 @@ impl<'a> Visitor<'a> {
                         || {
                             // todo: superfluous clone
                             let v = visitor.visit_expr(argument.default.clone().unwrap())?;
-                            Ok(visitor.without_slash(v))
+                            visitor.without_slash(v, span)
                         },
                         SassResult::Ok,
                     )?;
@@ -2368,6 +2893,35 @@ impl<'a> Visitor<'a> {
         })
     }
 
+    fn parse_builtin_signature(
+        &mut self,
+        func: &Builtin,
+        name: Identifier,
+    ) -> SassResult<Arc<ArgumentDeclaration>> {
+        if let Some(declaration) = self.builtin_signatures.get(&func.1) {
+            return Ok(Arc::clone(declaration));
+        }
+
+        let signature = func.2.as_deref().unwrap_or("()");
+        let file = self
+            .map
+            .add_file(format!("{}()", name), signature.to_owned());
+        let declaration = ScssParser::new(
+            Lexer::new_from_file(&file),
+            self.map,
+            self.options,
+            file.span.subspan(0, 0),
+            Path::new(file.name()),
+        )
+        .parse_argument_declaration()?;
+
+        let declaration = Arc::new(declaration);
+        self.builtin_signatures
+            .insert(func.1, Arc::clone(&declaration));
+
+        Ok(declaration)
+    }
+
     pub(crate) fn run_function_callable(
         &mut self,
         func: SassFunction,
@@ -2388,10 +2942,50 @@ impl<'a> Visitor<'a> {
         span: Span,
     ) -> SassResult<Value> {
         match func {
+            SassFunction::Builtin(func, name) if func.2.is_some() => {
+                let declaration = self.parse_builtin_signature(&func, name)?;
+                let callable = BuiltinWithSignature {
+                    name,
+                    arguments: declaration,
+                };
+
+                let val = self.run_user_defined_callable(
+                    arguments,
+                    callable,
+                    &Environment::new(),
+                    span,
+                    |callable, visitor| {
+                        let declaration = callable.arguments();
+                        let mut positional = Vec::with_capacity(declaration.args.len() + 1);
+
+                        for name in declaration
+                            .args
+                            .iter()
+                            .map(|arg| arg.name)
+                            .chain(declaration.rest)
+                        {
+                            positional
+                                .push(visitor.env.get_var(Spanned { node: name, span }, None)?);
+                        }
+
+                        let evaluated = ArgumentResult {
+                            positional,
+                            named: BTreeMap::new(),
+                            separator: ListSeparator::Undecided,
+                            span,
+                            touched: BTreeSet::new(),
+                        };
+
+                        (func.0)(evaluated, visitor)
+                    },
+                )?;
+
+                self.without_slash(val, span)
+            }
             SassFunction::Builtin(func, _name) => {
                 let evaluated = self.eval_maybe_args(arguments, span)?;
-                let val = func.0(evaluated, self)?;
-                Ok(self.without_slash(val))
+                let val = (func.0)(evaluated, self)?;
+                self.without_slash(val, span)
             }
             SassFunction::UserDefined(UserDefinedFunction { function, env, .. }) => self
                 .run_user_defined_callable(arguments, function, &env, span, |function, visitor| {
@@ -2485,6 +3079,16 @@ impl<'a> Visitor<'a> {
                 if let Some(f) = self.options.custom_fns.get(name.as_str()) {
                     SassFunction::Builtin(f.clone(), name)
                 } else if let Some(f) = GLOBAL_FUNCTIONS.get(name.as_str()) {
+                    if func_call.namespace.is_none() {
+                        if let Some(replacement) = deprecated_global_function(name.as_str()) {
+                            self.emit_deprecation(
+                                Deprecation::GlobalBuiltin,
+                                &format!("Global built-in functions are deprecated and will be removed in Dart Sass 3.0.0.\nUse {} instead.\n\nMore info and automated migrator: https://sass-lang.com/d/import", replacement),
+                                func_call.span,
+                            )?;
+                        }
+                    }
+
                     SassFunction::Builtin(f.clone(), name)
                 } else {
                     if func_call.namespace.is_some() {
@@ -2667,6 +3271,29 @@ impl<'a> Visitor<'a> {
         args: Vec<AstExpr>,
         span: Span,
     ) -> SassResult<Value> {
+        // math functions may be shadowed by a user-defined function or one made
+        // available through `@use ... as *`
+        if CalculationName::from_math_function(name.as_str()).is_some() {
+            if let Some(func) = self.env.get_fn(Identifier::from(name.as_str()), None)? {
+                let old_in_function = self.flags.in_function();
+                self.flags.set(ContextFlags::IN_FUNCTION, true);
+                let value = self.run_function_callable(
+                    func,
+                    ArgumentInvocation {
+                        positional: args,
+                        named: BTreeMap::new(),
+                        rest: None,
+                        keyword_rest: None,
+                        span,
+                    },
+                    span,
+                )?;
+                self.flags.set(ContextFlags::IN_FUNCTION, old_in_function);
+
+                return Ok(value);
+            }
+        }
+
         let mut args = args
             .into_iter()
             .map(|arg| self.visit_calculation_value(arg, name.in_min_or_max(), span))
@@ -2699,6 +3326,34 @@ impl<'a> Visitor<'a> {
                 };
                 SassCalculation::clamp(min, value, max, self.options, span)
             }
+            CalculationName::Round => SassCalculation::round(args, self.options, span),
+            CalculationName::Mod | CalculationName::Rem => {
+                SassCalculation::modulo(name, args, self.options, span)
+            }
+            CalculationName::Sin | CalculationName::Cos | CalculationName::Tan => {
+                SassCalculation::trig(name, args.remove(0), self.options, span)
+            }
+            CalculationName::Asin
+            | CalculationName::Acos
+            | CalculationName::Atan
+            | CalculationName::Sqrt
+            | CalculationName::Exp => {
+                SassCalculation::unitless_function(name, args.remove(0), self.options, span)
+            }
+            CalculationName::Atan2 | CalculationName::Pow => {
+                SassCalculation::verify_length(&args, 2, span)?;
+                let rhs = args.pop().unwrap();
+                let lhs = args.pop().unwrap();
+                if name == CalculationName::Atan2 {
+                    SassCalculation::atan2(lhs, rhs, self.options, span)
+                } else {
+                    SassCalculation::pow(lhs, rhs, self.options, span)
+                }
+            }
+            CalculationName::Log => SassCalculation::log(args, self.options, span),
+            CalculationName::Hypot => SassCalculation::hypot(args, self.options, span),
+            CalculationName::Abs => Ok(SassCalculation::abs(args.remove(0))),
+            CalculationName::Sign => Ok(SassCalculation::sign(args.remove(0))),
         }
     }
 
@@ -2743,7 +3398,7 @@ impl<'a> Visitor<'a> {
             self.visit_expr(if_false)?
         };
 
-        Ok(self.without_slash(value))
+        self.without_slash(value, if_expr.0.span)
     }
 
     fn visit_string(&mut self, mut text: Interpolation, quote: QuoteKind) -> SassResult<Value> {
@@ -2881,13 +3536,19 @@ impl<'a> Visitor<'a> {
                         span,
                     );
                 } else if left_is_number && right_is_number {
-                    // todo: emit warning here. it prints too frequently, so we do not currently
-                    // self.emit_warning(
-                    //     Cow::Borrowed(format!(
-                    //         "Using / for division outside of calc() is deprecated"
-                    //     )),
-                    //     span,
-                    // );
+                    let source = self
+                        .map
+                        .look_up_span(span)
+                        .file
+                        .source_slice(span)
+                        .to_owned();
+                    let message = format!(
+                        "Using / for division outside of calc() is deprecated and will be removed in Dart Sass 2.0.0.\n\nRecommendation: math.div({}, {}) or calc({})\n\nMore info and automated migrator: https://sass-lang.com/d/slash-div",
+                        left.inspect(span)?,
+                        right.inspect(span)?,
+                        source
+                    );
+                    self.emit_deprecation(Deprecation::SlashDiv, &message, span)?;
                 }
 
                 div(left, right, self.options, span)?
@@ -2953,30 +3614,62 @@ impl<'a> Visitor<'a> {
             return Ok(None);
         }
 
-        let mut parsed_selector = self.parse_selector_from_string(
-            &selector_text,
-            !self.is_plain_css,
-            !self.is_plain_css,
-            ruleset.selector_span,
-        )?;
+        let mut parsed_selector = if self.is_plain_css {
+            let sel_toks = Lexer::new_from_string(&selector_text, ruleset.selector_span);
+            SelectorParser::new(sel_toks, true, false, ruleset.selector_span)
+                .plain_css(true)
+                .parse()?
+        } else {
+            self.parse_selector_from_string(&selector_text, true, true, ruleset.selector_span)?
+        };
 
-        parsed_selector = parsed_selector.resolve_parent_selectors(
-            self.style_rule_ignoring_at_root
+        // Style rules nested within a plain CSS style rule use native CSS nesting,
+        // so they're emitted within their parent rather than being flattened
+        let nest = !(self.style_rule_exists() && self.style_rule_is_plain_css);
+
+        // With native nesting enabled, style rules are emitted within their parent
+        // using the selector as written. The resolved selector is still used for
+        // `@extend`, and if the two diverge the rules are flattened after the fact
+        let native_nesting_selector =
+            if !self.is_plain_css && self.can_nest_natively(&parsed_selector) {
+                Some(parsed_selector.clone())
+            } else {
+                None
+            };
+        let native = native_nesting_selector.is_some();
+
+        if nest {
+            let parent = self
+                .style_rule_ignoring_at_root
                 .as_ref()
                 // todo: this clone should be superfluous(?)
-                .map(|x| x.as_selector_list().clone()),
-            !self.flags.at_root_excluding_style_rule(),
-        )?;
+                .map(|x| x.as_selector_list().clone());
+            let implicit_parent = !self.flags.at_root_excluding_style_rule();
+
+            parsed_selector = if self.is_plain_css {
+                parsed_selector.nest_within_preserving_parents(parent, implicit_parent)
+            } else {
+                parsed_selector.resolve_parent_selectors(parent, implicit_parent)?
+            };
+        }
 
-        // todo: _mediaQueries
         let selector = self
             .extender
-            .add_selector(parsed_selector, &self.media_queries);
+            .add_selector(parsed_selector, &self.media_queries)?;
+
+        if let Some(native_nesting_selector) = native_nesting_selector {
+            self.nested_style_rules
+                .insert(selector.id(), native_nesting_selector);
+        } else if nest && self.options.style == OutputStyle::Nested && self.style_rule_exists() {
+            let parent = self.style_rule_ignoring_at_root.as_ref().unwrap();
+            self.style_rule_parents.insert(selector.id(), parent.id());
+        }
 
         let rule = CssStmt::RuleSet {
             selector: selector.clone(),
             body: Vec::new(),
             is_group_end: false,
+            tabs: 0,
         };
 
         let old_at_root_excluding_style_rule = self.flags.at_root_excluding_style_rule();
@@ -2986,6 +3679,8 @@ impl<'a> Visitor<'a> {
 
         let old_style_rule_ignoring_at_root = self.style_rule_ignoring_at_root.take();
         self.style_rule_ignoring_at_root = Some(selector);
+        let old_style_rule_is_plain_css =
+            mem::replace(&mut self.style_rule_is_plain_css, self.is_plain_css);
 
         self.with_parent(
             rule,
@@ -2998,10 +3693,11 @@ impl<'a> Visitor<'a> {
 
                 Ok(())
             },
-            CssStmt::is_style_rule,
+            |stmt| nest && !native && stmt.is_style_rule(),
         )?;
 
         self.style_rule_ignoring_at_root = old_style_rule_ignoring_at_root;
+        self.style_rule_is_plain_css = old_style_rule_is_plain_css;
         self.flags.set(
             ContextFlags::AT_ROOT_EXCLUDING_STYLE_RULE,
             old_at_root_excluding_style_rule,
@@ -3028,6 +3724,33 @@ impl<'a> Visitor<'a> {
         Some(())
     }
 
+    /// Whether a style rule with `selector` can be emitted within the current
+    /// parent using native CSS nesting
+    fn can_nest_natively(&self, selector: &SelectorList) -> bool {
+        if !self.options.native_nesting || !self.style_rule_exists() {
+            return false;
+        }
+
+        let parent = match self.parent {
+            Some(parent) => parent,
+            None => return false,
+        };
+
+        if self.escaped_style_rules.contains(&parent)
+            || !matches!(*self.css_tree.get(parent), Some(CssStmt::RuleSet { .. }))
+        {
+            return false;
+        }
+
+        !selector.contains_parent_suffix()
+            && selector.components.iter().all(|complex| {
+                !complex
+                    .components
+                    .last()
+                    .map_or(false, ComplexSelectorComponent::is_combinator)
+            })
+    }
+
     fn style_rule_exists(&self) -> bool {
         !self.flags.at_root_excluding_style_rule() && self.style_rule_ignoring_at_root.is_some()
     }
@@ -66,7 +66,7 @@ grass input.scss
     unknown_lints,
 )]
 
-use std::path::Path;
+use std::{io::Write, path::Path};
 
 use parse::{CssParser, SassParser, StylesheetParser};
 use sass_ast::StyleSheet;
@@ -74,26 +74,33 @@ use serializer::Serializer;
 #[cfg(feature = "wasm-exports")]
 use wasm_bindgen::prelude::*;
 
-use codemap::CodeMap;
+use codemap::{CodeMap, Span};
 
+pub use crate::deprecation::Deprecation;
 pub use crate::error::{
     PublicSassErrorKind as ErrorKind, SassError as Error, SassResult as Result,
 };
 pub use crate::fs::{Fs, NullFs, StdFs};
-pub use crate::options::{InputSyntax, Options, OutputStyle};
-pub use crate::{builtin::Builtin, evaluate::Visitor};
+pub use crate::options::{
+    IndentType, InputSyntax, LineFeed, MediaQueryGrouping, Options, OutputStyle, UrlRewrite,
+    UsedSelectors,
+};
+use crate::{ast::CssStmt, lexer::Lexer, parse::ScssParser};
+pub use crate::{
+    builtin::Builtin,
+    evaluate::{BrowserTargets, CssModule, Visitor},
+};
 pub(crate) use crate::{context_flags::ContextFlags, lexer::Token};
-use crate::{lexer::Lexer, parse::ScssParser};
 
 pub mod sass_value {
     pub use crate::{
         ast::ArgumentResult,
-        color::Color,
+        color::{Color, ColorSpace, GamutMapMethod, HueInterpolationMethod},
         common::{BinaryOp, Brackets, ListSeparator, QuoteKind},
         unit::{ComplexUnit, Unit},
         value::{
             ArgList, CalculationArg, CalculationName, Number, SassCalculation, SassFunction,
-            SassMap, SassNumber, Value,
+            SassMap, SassMixin, SassNumber, Value,
         },
     };
 }
@@ -109,6 +116,7 @@ mod builtin;
 mod color;
 mod common;
 mod context_flags;
+mod deprecation;
 mod error;
 mod evaluate;
 mod fs;
@@ -163,11 +171,23 @@ pub fn parse_stylesheet<P: AsRef<Path>>(
     Ok(stylesheet)
 }
 
-fn from_string_with_file_name<P: AsRef<Path>>(
+/// Parses and evaluates `input`, producing the top-level CSS statements to be
+/// serialized along with the codemap their spans refer to
+fn evaluate<P: AsRef<Path>>(
     input: String,
     file_name: P,
     options: &Options,
-) -> Result<String> {
+) -> Result<(Vec<CssStmt>, CodeMap, Span)> {
+    evaluate_with(input, file_name, options, |visitor| visitor.finish())
+}
+
+/// Like [`evaluate`], but uses `finish` to produce the output of the visitor
+fn evaluate_with<P: AsRef<Path>, T, F: FnOnce(Visitor) -> std::result::Result<T, Box<Error>>>(
+    input: String,
+    file_name: P,
+    options: &Options,
+    finish: F,
+) -> Result<(T, CodeMap, Span)> {
     let mut map = CodeMap::new();
     let path = file_name.as_ref();
     let file = map.add_file(path.to_string_lossy().into_owned(), input);
@@ -200,10 +220,25 @@ fn from_string_with_file_name<P: AsRef<Path>>(
         Ok(_) => {}
         Err(e) => return Err(raw_to_parse_error(&map, *e, options.unicode_error_messages)),
     }
-    let stmts = visitor.finish();
+    let stmts = match finish(visitor) {
+        Ok(v) => v,
+        Err(e) => return Err(raw_to_parse_error(&map, *e, options.unicode_error_messages)),
+    };
 
-    let mut serializer = Serializer::new(options, &map, false, empty_span);
+    Ok((stmts, map, empty_span))
+}
 
+/// Serializes each visible group in `stmts`, calling `after_group` once each
+/// has been visited
+///
+/// Returns whether the last group requires a trailing semicolon
+fn serialize_groups<F: FnMut(&mut Serializer) -> Result<()>>(
+    serializer: &mut Serializer,
+    stmts: Vec<CssStmt>,
+    map: &CodeMap,
+    options: &Options,
+    mut after_group: F,
+) -> Result<bool> {
     let mut prev_was_group_end = false;
     let mut prev_requires_semicolon = false;
     for stmt in stmts {
@@ -216,15 +251,74 @@ fn from_string_with_file_name<P: AsRef<Path>>(
 
         serializer
             .visit_group(stmt, prev_was_group_end, prev_requires_semicolon)
-            .map_err(|e| raw_to_parse_error(&map, *e, options.unicode_error_messages))?;
+            .map_err(|e| raw_to_parse_error(map, *e, options.unicode_error_messages))?;
+
+        after_group(serializer)?;
 
         prev_was_group_end = is_group_end;
         prev_requires_semicolon = requires_semicolon;
     }
 
+    Ok(prev_requires_semicolon)
+}
+
+fn serialize(
+    stmts: Vec<CssStmt>,
+    map: &CodeMap,
+    options: &Options,
+    empty_span: Span,
+) -> Result<String> {
+    let mut serializer = Serializer::new(options, map, false, empty_span);
+
+    let prev_requires_semicolon =
+        serialize_groups(&mut serializer, stmts, map, options, |_| Ok(()))?;
+
     Ok(serializer.finish(prev_requires_semicolon))
 }
 
+fn from_string_with_file_name<P: AsRef<Path>>(
+    input: String,
+    file_name: P,
+    options: &Options,
+) -> Result<String> {
+    let (stmts, map, empty_span) = evaluate(input, file_name, options)?;
+
+    serialize(stmts, &map, options, empty_span)
+}
+
+fn from_string_with_rtl_and_file_name<P: AsRef<Path>>(
+    input: String,
+    file_name: P,
+    options: &Options,
+) -> Result<(String, String)> {
+    let ((ltr, rtl), map, empty_span) = evaluate_with(input, file_name, options, |visitor| {
+        visitor.finish_with_rtl()
+    })?;
+
+    Ok((
+        serialize(ltr, &map, options, empty_span)?,
+        serialize(rtl, &map, options, empty_span)?,
+    ))
+}
+
+fn from_string_as_css_module_with_file_name<P: AsRef<Path>>(
+    input: String,
+    file_name: P,
+    options: &Options,
+) -> Result<CssModule> {
+    let path = file_name.as_ref().to_string_lossy().into_owned();
+
+    let ((stmts, exports), map, empty_span) =
+        evaluate_with(input, file_name, options, |visitor| {
+            visitor.finish_as_css_module(&path)
+        })?;
+
+    Ok(CssModule {
+        css: serialize(stmts, &map, options, empty_span)?,
+        exports,
+    })
+}
+
 /// Compile CSS from a path
 ///
 /// n.b. `grass` does not currently support files or paths that are not valid UTF-8
@@ -241,6 +335,90 @@ pub fn from_path<P: AsRef<Path>>(p: P, options: &Options) -> Result<String> {
     from_string_with_file_name(String::from_utf8(options.fs.read(p.as_ref())?)?, p, options)
 }
 
+/// Compile CSS from a path, producing both the stylesheet and its mirror for
+/// right-to-left languages
+///
+/// The input is only evaluated once. See [`Options::rtl`] for how the
+/// stylesheet is mirrored; that option itself is ignored.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let (css, rtl_css) = grass::from_path_with_rtl("input.scss", &grass::Options::default())?;
+///     Ok(())
+/// }
+/// ```
+#[inline]
+pub fn from_path_with_rtl<P: AsRef<Path>>(p: P, options: &Options) -> Result<(String, String)> {
+    from_string_with_rtl_and_file_name(String::from_utf8(options.fs.read(p.as_ref())?)?, p, options)
+}
+
+/// Compile CSS from a path as a CSS Module
+///
+/// Each class and `@keyframes` name in the stylesheet is rewritten to a name
+/// that is unique to `p`, and `animation` and `animation-name` declarations
+/// are updated to match. Names may be left as written by wrapping them in
+/// `:global(..)`, as in `:global(.active)` or `@keyframes :global(fade)`.
+///
+/// The generated names are deterministic, so compiling the same file at the
+/// same path always produces the same output.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let module = grass::from_path_as_css_module("input.scss", &grass::Options::default())?;
+///     let json = module.exports_json();
+///     Ok(())
+/// }
+/// ```
+#[inline]
+pub fn from_path_as_css_module<P: AsRef<Path>>(p: P, options: &Options) -> Result<CssModule> {
+    from_string_as_css_module_with_file_name(
+        String::from_utf8(options.fs.read(p.as_ref())?)?,
+        p,
+        options,
+    )
+}
+
+/// Compile CSS from a path, writing the output to `writer`
+///
+/// Rather than building the entire stylesheet in memory, each top-level group
+/// is written to `writer` as soon as it has been serialized. Nothing is written
+/// if evaluating the stylesheet fails, but an error while serializing it may
+/// leave `writer` with only part of the output.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let mut css = Vec::new();
+///     grass::compile_to_writer("input.scss", &grass::Options::default(), &mut css)?;
+///     Ok(())
+/// }
+/// ```
+pub fn compile_to_writer<P: AsRef<Path>, W: Write>(
+    p: P,
+    options: &Options,
+    writer: &mut W,
+) -> Result<()> {
+    let input = String::from_utf8(options.fs.read(p.as_ref())?)?;
+    let (stmts, map, empty_span) = evaluate(input, p, options)?;
+
+    let mut serializer = Serializer::new(options, &map, false, empty_span);
+
+    if stmts.iter().any(|stmt| serializer.writes_non_ascii(stmt)) {
+        serializer.write_charset(writer)?;
+    }
+
+    let prev_requires_semicolon =
+        serialize_groups(&mut serializer, stmts, &map, options, |serializer| {
+            Ok(serializer.flush(writer)?)
+        })?;
+
+    serializer.finish_to_writer(prev_requires_semicolon, writer)?;
+
+    Ok(())
+}
+
 /// Compile CSS from a string
 ///
 /// ```
@@ -256,6 +434,84 @@ pub fn from_string<S: Into<String>>(input: S, options: &Options) -> Result<Strin
     from_string_with_file_name(input.into(), "stdin", options)
 }
 
+/// Compile CSS from a string, as though it had been read from the file at `path`
+///
+/// Relative imports are resolved from `path`, and it is used as the file name
+/// in error messages and warnings. If [`Options::input_syntax`] is not set, the
+/// syntax is inferred from the extension of `path`. The file itself does not
+/// need to exist.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let css = grass::from_string_with_path(
+///         "a\n  color: red".to_string(),
+///         "input.sass",
+///         &grass::Options::default(),
+///     )?;
+///     assert_eq!(css, "a {\n  color: red;\n}\n");
+///     Ok(())
+/// }
+/// ```
+#[inline]
+pub fn from_string_with_path<S: Into<String>, P: AsRef<Path>>(
+    input: S,
+    path: P,
+    options: &Options,
+) -> Result<String> {
+    from_string_with_file_name(input.into(), path, options)
+}
+
+/// Compile CSS from a string, producing both the stylesheet and its mirror for
+/// right-to-left languages
+///
+/// See [`from_path_with_rtl`] for more information.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let (css, rtl_css) = grass::from_string_with_rtl(
+///         "a { margin-left: 1px; }".to_string(),
+///         &grass::Options::default(),
+///     )?;
+///     assert_eq!(css, "a {\n  margin-left: 1px;\n}\n");
+///     assert_eq!(rtl_css, "a {\n  margin-right: 1px;\n}\n");
+///     Ok(())
+/// }
+/// ```
+#[inline]
+pub fn from_string_with_rtl<S: Into<String>>(
+    input: S,
+    options: &Options,
+) -> Result<(String, String)> {
+    from_string_with_rtl_and_file_name(input.into(), "stdin", options)
+}
+
+/// Compile CSS from a string as a CSS Module
+///
+/// See [`from_path_as_css_module`] for more information. The generated names
+/// are derived from the file name `stdin`.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let module = grass::from_string_as_css_module(
+///         ".button { color: red; }".to_string(),
+///         &grass::Options::default(),
+///     )?;
+///     assert_eq!(module.css, ".button_lxn4z3 {\n  color: red;\n}\n");
+///     assert_eq!(module.exports["button"], "button_lxn4z3");
+///     Ok(())
+/// }
+/// ```
+#[inline]
+pub fn from_string_as_css_module<S: Into<String>>(
+    input: S,
+    options: &Options,
+) -> Result<CssModule> {
+    from_string_as_css_module_with_file_name(input.into(), "stdin", options)
+}
+
 #[cfg(feature = "wasm-exports")]
 #[wasm_bindgen(js_name = from_string)]
 pub fn from_string_js(input: String) -> std::result::Result<String, String> {
@@ -1,9 +1,9 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
-use crate::{builtin::Builtin, Fs, StdFs};
+use crate::{builtin::Builtin, BrowserTargets, Deprecation, Fs, StdFs};
 
 /// Configuration for Sass compilation
 ///
@@ -13,12 +13,27 @@ use crate::{builtin::Builtin, Fs, StdFs};
 pub struct Options<'a> {
     pub(crate) fs: &'a dyn Fs,
     pub(crate) style: OutputStyle,
+    pub(crate) indent_type: IndentType,
+    pub(crate) indent_width: usize,
+    pub(crate) linefeed: LineFeed,
     pub(crate) load_paths: Vec<PathBuf>,
     pub(crate) allows_charset: bool,
     pub(crate) unicode_error_messages: bool,
     pub(crate) quiet: bool,
+    pub(crate) quiet_deps: bool,
     pub(crate) input_syntax: Option<InputSyntax>,
     pub(crate) custom_fns: HashMap<String, Builtin>,
+    pub(crate) fatal_deprecations: HashSet<Deprecation>,
+    pub(crate) silenced_deprecations: HashSet<Deprecation>,
+    pub(crate) verbose: bool,
+    pub(crate) native_nesting: bool,
+    pub(crate) minify: bool,
+    pub(crate) media_query_grouping: Option<MediaQueryGrouping>,
+    pub(crate) browser_targets: Option<&'a BrowserTargets>,
+    pub(crate) rtl: bool,
+    pub(crate) used_selectors: Option<&'a UsedSelectors>,
+    pub(crate) url_rewrite: Option<UrlRewrite<'a>>,
+    pub(crate) cache_bust_urls: bool,
 }
 
 impl Default for Options<'_> {
@@ -27,12 +42,27 @@ impl Default for Options<'_> {
         Self {
             fs: &StdFs,
             style: OutputStyle::Expanded,
+            indent_type: IndentType::Space,
+            indent_width: 2,
+            linefeed: LineFeed::Lf,
             load_paths: Vec::new(),
             allows_charset: true,
             unicode_error_messages: true,
             quiet: false,
+            quiet_deps: false,
             input_syntax: None,
             custom_fns: HashMap::new(),
+            fatal_deprecations: HashSet::new(),
+            silenced_deprecations: HashSet::new(),
+            verbose: false,
+            native_nesting: false,
+            minify: false,
+            media_query_grouping: None,
+            browser_targets: None,
+            rtl: false,
+            used_selectors: None,
+            url_rewrite: None,
+            cache_bust_urls: false,
         }
     }
 }
@@ -49,11 +79,15 @@ impl<'a> Options<'a> {
         self
     }
 
-    /// `grass` currently offers 2 different output styles
+    /// `grass` currently offers 4 different output styles
     ///
     ///  - [`OutputStyle::Expanded`] writes each selector and declaration on its own line.
     ///  - [`OutputStyle::Compressed`] removes as many extra characters as possible
     ///    and writes the entire stylesheet on a single line.
+    ///  - [`OutputStyle::Nested`] indents style rules to reflect how they were
+    ///    nested in the source, as in Ruby Sass and libsass.
+    ///  - [`OutputStyle::Compact`] writes each style rule on a single line, as in
+    ///    Ruby Sass and libsass.
     ///
     /// By default, output is expanded.
     #[must_use]
@@ -63,6 +97,38 @@ impl<'a> Options<'a> {
         self
     }
 
+    /// This option controls whether the output is indented using spaces or tabs.
+    ///
+    /// By default, output is indented using spaces.
+    #[must_use]
+    #[inline]
+    pub const fn indent_type(mut self, indent_type: IndentType) -> Self {
+        self.indent_type = indent_type;
+        self
+    }
+
+    /// This option controls the number of spaces or tabs used for each level of
+    /// indentation.
+    ///
+    /// By default, each level is indented by 2 characters.
+    #[must_use]
+    #[inline]
+    pub const fn indent_width(mut self, indent_width: usize) -> Self {
+        self.indent_width = indent_width;
+        self
+    }
+
+    /// This option controls the character sequence used to end each line of the
+    /// output.
+    ///
+    /// By default, lines end with `\n`.
+    #[must_use]
+    #[inline]
+    pub const fn linefeed(mut self, linefeed: LineFeed) -> Self {
+        self.linefeed = linefeed;
+        self
+    }
+
     /// This flag tells Sass not to emit any warnings
     /// when compiling. By default, Sass emits warnings
     /// when deprecated features are used or when the
@@ -77,6 +143,83 @@ impl<'a> Options<'a> {
         self
     }
 
+    /// This flag tells Sass not to emit warnings which come from dependencies.
+    ///
+    /// A dependency is any file which was loaded from one of the
+    /// [load paths](Options::load_path), as well as any file it loads in turn.
+    /// Warnings from stylesheets loaded relative to the entry point are still
+    /// emitted. This is useful for silencing warnings from third-party
+    /// libraries which you can't fix yourself.
+    ///
+    /// By default, this value is `false` and warnings from dependencies are
+    /// emitted.
+    #[must_use]
+    #[inline]
+    pub const fn quiet_deps(mut self, quiet_deps: bool) -> Self {
+        self.quiet_deps = quiet_deps;
+        self
+    }
+
+    /// This flag tells Sass to emit every deprecation warning it encounters.
+    ///
+    /// By default, Sass only emits the first 5 warnings for each kind of
+    /// deprecation and then prints a summary of how many were omitted.
+    #[must_use]
+    #[inline]
+    pub const fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Treat uses of the given deprecated feature as errors rather than
+    /// warnings.
+    ///
+    /// This is useful to ensure a stylesheet has been fully migrated away from
+    /// a feature before it is removed from the language.
+    ///
+    /// This method will append a single deprecation to the list.
+    #[must_use]
+    #[inline]
+    pub fn fatal_deprecation(mut self, deprecation: Deprecation) -> Self {
+        self.fatal_deprecations.insert(deprecation);
+        self
+    }
+
+    /// Append multiple fatal deprecations
+    ///
+    /// See [`Options::fatal_deprecation`](Options::fatal_deprecation) for more
+    /// information
+    #[must_use]
+    #[inline]
+    pub fn fatal_deprecations(mut self, deprecations: &[Deprecation]) -> Self {
+        self.fatal_deprecations.extend(deprecations);
+        self
+    }
+
+    /// Don't emit warnings for uses of the given deprecated feature.
+    ///
+    /// If a deprecation is both silenced and fatal, it will be treated as
+    /// fatal.
+    ///
+    /// This method will append a single deprecation to the list.
+    #[must_use]
+    #[inline]
+    pub fn silence_deprecation(mut self, deprecation: Deprecation) -> Self {
+        self.silenced_deprecations.insert(deprecation);
+        self
+    }
+
+    /// Append multiple silenced deprecations
+    ///
+    /// See [`Options::silence_deprecation`](Options::silence_deprecation) for
+    /// more information
+    #[must_use]
+    #[inline]
+    pub fn silence_deprecations(mut self, deprecations: &[Deprecation]) -> Self {
+        self.silenced_deprecations.extend(deprecations);
+        self
+    }
+
     /// All Sass implementations allow users to provide
     /// load paths: paths on the filesystem that Sass
     /// will look in when locating modules. For example,
@@ -113,6 +256,164 @@ impl<'a> Options<'a> {
         self
     }
 
+    /// This flag tells Sass to emit nested style rules using native CSS nesting,
+    /// as in `a { color: red; &:hover { color: blue; } }`, rather than flattening
+    /// them into full selectors.
+    ///
+    /// Rules are only nested where doing so is equivalent to the flattened
+    /// output. Rules whose selectors are changed by `@extend`, that use parent
+    /// selector suffixes such as `&-foo`, or that are separated from their
+    /// parent by `@at-root` or a bubbled at-rule are still flattened.
+    ///
+    /// By default, this value is `false` and all style rules are flattened.
+    #[must_use]
+    #[inline]
+    pub const fn native_nesting(mut self, native_nesting: bool) -> Self {
+        self.native_nesting = native_nesting;
+        self
+    }
+
+    /// This flag tells Sass to optimize the generated CSS to reduce its size.
+    ///
+    /// Adjacent style rules with the same selector or the same declarations are
+    /// merged, declarations which are overridden later in the same rule are
+    /// removed, empty at-rules are dropped, and colors, zero lengths and
+    /// `font-weight` keywords are shortened. Comments are removed unless they
+    /// begin with `/*!`.
+    ///
+    /// These optimizations never change which declarations apply to an element.
+    /// Declarations which may be acting as fallbacks for older browsers, such as
+    /// those using vendor prefixes or CSS functions, are preserved.
+    ///
+    /// This is most useful in combination with [`OutputStyle::Compressed`].
+    ///
+    /// By default, this value is `false` and the CSS is not optimized.
+    #[must_use]
+    #[inline]
+    pub const fn minify(mut self, minify: bool) -> Self {
+        self.minify = minify;
+        self
+    }
+
+    /// This option tells Sass to merge `@media` rules with identical queries,
+    /// which are otherwise emitted next to each style rule that contains them.
+    ///
+    /// See [`MediaQueryGrouping`] for the available strategies.
+    ///
+    /// By default, `@media` rules are not grouped.
+    #[must_use]
+    #[inline]
+    pub const fn group_media_queries(mut self, grouping: MediaQueryGrouping) -> Self {
+        self.media_query_grouping = Some(grouping);
+        self
+    }
+
+    /// This option tells Sass to add the vendor prefixes needed by the browsers
+    /// in `targets`, which are parsed from a
+    /// [browserslist](https://github.com/browserslist/browserslist)-style
+    /// query such as `"last 2 versions, safari >= 14"`.
+    ///
+    /// Prefixes are added to properties (such as `-webkit-user-select`), values
+    /// (such as `-webkit-sticky`), pseudo selectors (such as
+    /// `::-moz-placeholder`), and `@keyframes`, based on compatibility data
+    /// bundled with grass. Prefixed declarations which are already present are
+    /// left alone.
+    ///
+    /// Flexbox is prefixed using the final `-webkit-` syntax. The 2009 and
+    /// 2012 syntaxes needed by some older browsers, such as `display: -webkit-box`
+    /// and `display: -ms-flexbox`, aren't generated.
+    ///
+    /// See [`BrowserTargets::parse`] for the supported queries.
+    ///
+    /// By default, no prefixes are added.
+    #[must_use]
+    #[inline]
+    pub const fn browser_targets(mut self, targets: &'a BrowserTargets) -> Self {
+        self.browser_targets = Some(targets);
+        self
+    }
+
+    /// This flag tells Sass to mirror the output for right-to-left languages
+    /// such as Arabic and Hebrew.
+    ///
+    /// Physical directions are swapped in property names (`margin-left` becomes
+    /// `margin-right`) and values (`float: left` becomes `float: right`), the
+    /// left and right components of four-value shorthands and `border-radius`
+    /// are swapped, and horizontal translations are negated.
+    ///
+    /// A `/*rtl:ignore*/` comment prevents the following declaration or rule
+    /// from being mirrored, as does surrounding CSS with `/*rtl:begin:ignore*/`
+    /// and `/*rtl:end:ignore*/`.
+    ///
+    /// To produce both the original and the mirrored stylesheet from a single
+    /// compilation, use [`crate::from_path_with_rtl`].
+    ///
+    /// By default, this value is `false` and the output is not mirrored.
+    #[must_use]
+    #[inline]
+    pub const fn rtl(mut self, rtl: bool) -> Self {
+        self.rtl = rtl;
+        self
+    }
+
+    /// This option tells Sass to remove style rules which can't match any
+    /// element in the documents described by `used`.
+    ///
+    /// Complex selectors are removed from a rule if they refer to a class, ID, or
+    /// element name which isn't used, and the rule is removed entirely if none
+    /// of its selectors remain. `@media` and `@supports` rules which become
+    /// empty as a result are removed, as are `@keyframes` rules whose names
+    /// aren't referred to by any remaining `animation` or `animation-name`
+    /// declaration.
+    ///
+    /// See [`UsedSelectors`] for how to describe the documents.
+    ///
+    /// By default, no CSS is removed.
+    #[must_use]
+    #[inline]
+    pub const fn purge_unused(mut self, used: &'a UsedSelectors) -> Self {
+        self.used_selectors = Some(used);
+        self
+    }
+
+    /// This option tells Sass to rewrite relative `url()`s, which are written
+    /// relative to the stylesheet containing them, so that they still refer to
+    /// the same file from the compiled CSS.
+    ///
+    /// This allows partials in other directories to refer to assets relative
+    /// to themselves. For example, if `_partials/_hero.scss` contains
+    /// `url("../img/bg.png")` and the output is written to `dist/style.css`,
+    /// then [`UrlRewrite::RelativeTo`] with the path `dist` rewrites it to
+    /// `url("../img/bg.png")` relative to `dist`.
+    ///
+    /// URLs which are absolute, fragments such as `#id`, or which have a scheme
+    /// such as `data:` or `https:` are left unchanged.
+    ///
+    /// By default, URLs are emitted as written.
+    #[must_use]
+    #[inline]
+    pub const fn rewrite_urls(mut self, rewrite: UrlRewrite<'a>) -> Self {
+        self.url_rewrite = Some(rewrite);
+        self
+    }
+
+    /// This flag tells Sass to add a hash of the contents of each file referred
+    /// to by a rewritten `url()` to its query string, as in
+    /// `url(img/bg.png?v=3b9c0a4e)`, so that browsers fetch the file again once
+    /// it changes.
+    ///
+    /// Files are read using [`Options::fs`]. A URL is still rewritten if the
+    /// file can't be read, but without a hash. This has no effect unless
+    /// [`Options::rewrite_urls`] is also set.
+    ///
+    /// By default, this value is `false`.
+    #[must_use]
+    #[inline]
+    pub const fn cache_bust_urls(mut self, cache_bust_urls: bool) -> Self {
+        self.cache_bust_urls = cache_bust_urls;
+        self
+    }
+
     /// This flag tells Sass whether to emit a `@charset`
     /// declaration or a UTF-8 byte-order mark.
     ///
@@ -214,4 +515,218 @@ pub enum OutputStyle {
     /// Ideal for release builds, this mode removes as many extra characters as
     /// possible and writes the entire stylesheet on a single line.
     Compressed,
+
+    /// This mode indents style rules to reflect the structure of the source
+    /// stylesheet, and places closing braces at the end of the last line of
+    /// each block.
+    ///
+    /// This matches the default output of Ruby Sass and libsass.
+    Nested,
+
+    /// This mode writes each style rule, including all of its declarations, on
+    /// a single line.
+    ///
+    /// This matches the compact output of Ruby Sass and libsass.
+    Compact,
+}
+
+/// How `@media` rules with identical queries are merged
+///
+/// See [`Options::group_media_queries`] for additional information
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MediaQueryGrouping {
+    /// Only merge a `@media` rule into an earlier one with the same query if
+    /// no declaration between the two could conflict with the declarations
+    /// being moved.
+    ///
+    /// This never changes which declarations apply to an element.
+    Safe,
+
+    /// Merge every `@media` rule with the same query, and move the merged rules
+    /// to the end of the stylesheet.
+    ///
+    /// This may change which declarations apply to an element if a declaration
+    /// outside of a `@media` rule follows one inside it and both have the same
+    /// specificity.
+    Aggressive,
+}
+
+/// How relative `url()`s are rewritten
+///
+/// See [`Options::rewrite_urls`] for additional information
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UrlRewrite<'a> {
+    /// Rewrite URLs to be relative to this directory, which should contain the
+    /// compiled CSS.
+    RelativeTo(&'a Path),
+
+    /// Rewrite URLs to begin with this public path, such as `/static`,
+    /// followed by the location of the file relative to the directory of the
+    /// stylesheet being compiled.
+    PublicPath(&'a str),
+}
+
+/// The character used to indent output
+///
+/// See [`Options::indent_type`] for additional information
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IndentType {
+    Space,
+    Tab,
+}
+
+impl IndentType {
+    pub(crate) fn as_byte(self) -> u8 {
+        match self {
+            Self::Space => b' ',
+            Self::Tab => b'\t',
+        }
+    }
+}
+
+/// The character sequence used to end each line of output
+///
+/// See [`Options::linefeed`] for additional information
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineFeed {
+    /// `\n`
+    Lf,
+
+    /// `\n\r`
+    LfCr,
+
+    /// `\r`
+    Cr,
+
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineFeed {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::LfCr => "\n\r",
+            Self::Cr => "\r",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+/// The class names, IDs, and element names used by a set of documents
+///
+/// See [`Options::purge_unused`] for additional information
+///
+/// ```
+/// # use grass_compiler as grass;
+/// let used = grass::UsedSelectors::new()
+///     .classes(["button", "active"])
+///     .content(r#"<nav id="menu"><a class="link">Home</a></nav>"#)
+///     .safelist(["js-*"]);
+/// ```
+///
+/// If no names of a given kind are provided, and no content is scanned,
+/// selectors of that kind are assumed to always match. For example, providing
+/// only class names leaves rules for IDs and element names untouched.
+#[derive(Debug, Clone, Default)]
+pub struct UsedSelectors {
+    pub(crate) classes: HashSet<String>,
+    pub(crate) ids: HashSet<String>,
+    pub(crate) elements: HashSet<String>,
+    pub(crate) content_tokens: HashSet<String>,
+    pub(crate) content_paths: Vec<PathBuf>,
+    pub(crate) has_content: bool,
+    pub(crate) safelist: Vec<String>,
+}
+
+impl UsedSelectors {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds class names which are used, without the leading `.`
+    #[must_use]
+    pub fn classes<I: IntoIterator<Item = S>, S: Into<String>>(mut self, classes: I) -> Self {
+        self.classes.extend(classes.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds IDs which are used, without the leading `#`
+    #[must_use]
+    pub fn ids<I: IntoIterator<Item = S>, S: Into<String>>(mut self, ids: I) -> Self {
+        self.ids.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds element names which are used, such as `div`
+    #[must_use]
+    pub fn elements<I: IntoIterator<Item = S>, S: Into<String>>(mut self, elements: I) -> Self {
+        self.elements.extend(
+            elements
+                .into_iter()
+                .map(|element| element.into().to_ascii_lowercase()),
+        );
+        self
+    }
+
+    /// Scans HTML or template source for the names it may use
+    ///
+    /// Every word made up of letters, digits, `-`, and `_` is treated as a
+    /// possible class name, ID, and element name, which errs on the side of
+    /// keeping CSS that is unused.
+    #[must_use]
+    pub fn content(mut self, content: &str) -> Self {
+        self.has_content = true;
+        extract_content_tokens(content, &mut self.content_tokens);
+        self
+    }
+
+    /// Adds HTML or template files to scan for the names they may use, as with
+    /// [`UsedSelectors::content`]
+    ///
+    /// The files are read using [`Options::fs`] during compilation.
+    #[must_use]
+    pub fn content_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.has_content = true;
+        self.content_paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Adds names which should always be considered used, whether they're
+    /// class names, IDs, element names, or `@keyframes` names
+    ///
+    /// A `*` in a name matches any sequence of characters, so `js-*` keeps
+    /// every class beginning with `js-`.
+    #[must_use]
+    pub fn safelist<I: IntoIterator<Item = S>, S: Into<String>>(mut self, names: I) -> Self {
+        self.safelist.extend(names.into_iter().map(Into::into));
+        self
+    }
+}
+
+/// Adds every word in `content` which could be a name to `tokens`
+///
+/// Words containing characters such as `:` and `/`, which are common in the
+/// class names of utility frameworks, are added both whole and in parts.
+pub(crate) fn extract_content_tokens(content: &str, tokens: &mut HashSet<String>) {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+
+    for word in content.split(|c: char| !(is_name_char(c) || matches!(c, ':' | '/' | '.' | '@'))) {
+        if word.is_empty() {
+            continue;
+        }
+
+        tokens.insert(word.to_owned());
+
+        for part in word.split(|c: char| !is_name_char(c)) {
+            if !part.is_empty() {
+                tokens.insert(part.to_owned());
+            }
+        }
+    }
 }
@@ -17,6 +17,7 @@ pub(crate) struct CssParser<'a> {
     pub empty_span: Span,
     pub flags: ContextFlags,
     pub options: &'a Options<'a>,
+    pub warnings: Vec<ParseTimeWarning>,
 }
 
 impl<'a> BaseParser<'a> for CssParser<'a> {
@@ -66,6 +67,10 @@ impl<'a> StylesheetParser<'a> for CssParser<'a> {
         &mut self.flags
     }
 
+    fn warnings_mut(&mut self) -> &mut Vec<ParseTimeWarning> {
+        &mut self.warnings
+    }
+
     fn current_indentation(&self) -> usize {
         0
     }
@@ -122,6 +127,7 @@ impl<'a> CssParser<'a> {
             empty_span,
             flags: ContextFlags::empty(),
             options,
+            warnings: Vec::new(),
         }
     }
 
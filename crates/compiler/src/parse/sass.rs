@@ -2,7 +2,7 @@ use std::path::Path;
 
 use codemap::{CodeMap, Span};
 
-use crate::{ast::*, error::SassResult, lexer::Lexer, ContextFlags, Options, Token};
+use crate::{ast::*, error::SassResult, lexer::Lexer, ContextFlags, Deprecation, Options, Token};
 
 use super::{BaseParser, StylesheetParser};
 
@@ -14,6 +14,7 @@ pub(crate) struct SassParser<'a> {
     pub empty_span: Span,
     pub flags: ContextFlags,
     pub options: &'a Options<'a>,
+    pub warnings: Vec<ParseTimeWarning>,
     pub current_indentation: usize,
     pub next_indentation: Option<usize>,
     pub spaces: Option<bool>,
@@ -99,6 +100,10 @@ impl<'a> StylesheetParser<'a> for SassParser<'a> {
         &mut self.flags
     }
 
+    fn warnings_mut(&mut self) -> &mut Vec<ParseTimeWarning> {
+        &mut self.warnings
+    }
+
     fn current_indentation(&self) -> usize {
         self.current_indentation
     }
@@ -156,8 +161,23 @@ impl<'a> StylesheetParser<'a> for SassParser<'a> {
         let start_next_indentation_end = self.next_indentation_end;
 
         self.read_indentation()?;
-        if self.scan_char('@') && self.scan_identifier("else", false)? {
-            return Ok(true);
+        let before_at = self.toks.cursor();
+        if self.scan_char('@') {
+            if self.scan_identifier("else", false)? {
+                return Ok(true);
+            }
+
+            if self.scan_identifier("elseif", true)? {
+                let span = self.toks.span_from(before_at);
+                self.warn_deprecation(
+                    Deprecation::Elseif,
+                    "@elseif is deprecated and will not be supported in future Sass versions.\n\nRecommendation: @else if".to_owned(),
+                    span,
+                );
+                let new_cursor = self.toks.cursor() - 2;
+                self.toks.set_cursor(new_cursor);
+                return Ok(true);
+            }
         }
 
         self.toks.set_cursor(start);
@@ -367,6 +387,7 @@ impl<'a> SassParser<'a> {
             empty_span,
             flags,
             options,
+            warnings: Vec::new(),
             current_indentation: 0,
             next_indentation: None,
             next_indentation_end: None,
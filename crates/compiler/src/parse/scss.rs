@@ -2,7 +2,7 @@ use std::path::Path;
 
 use codemap::{CodeMap, Span};
 
-use crate::{lexer::Lexer, ContextFlags, Options};
+use crate::{ast::ParseTimeWarning, lexer::Lexer, ContextFlags, Options};
 
 use super::{BaseParser, StylesheetParser};
 
@@ -14,6 +14,7 @@ pub(crate) struct ScssParser<'a> {
     pub empty_span: Span,
     pub flags: ContextFlags,
     pub options: &'a Options<'a>,
+    pub warnings: Vec<ParseTimeWarning>,
 }
 
 impl<'a> ScssParser<'a> {
@@ -35,6 +36,7 @@ impl<'a> ScssParser<'a> {
             empty_span,
             flags,
             options,
+            warnings: Vec::new(),
         }
     }
 }
@@ -82,6 +84,10 @@ impl<'a> StylesheetParser<'a> for ScssParser<'a> {
         &mut self.flags
     }
 
+    fn warnings_mut(&mut self) -> &mut Vec<ParseTimeWarning> {
+        &mut self.warnings
+    }
+
     fn empty_span(&self) -> Span {
         self.empty_span
     }
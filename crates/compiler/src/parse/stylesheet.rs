@@ -15,7 +15,7 @@ use crate::{
     error::SassResult,
     lexer::Lexer,
     utils::{is_name, is_name_start, is_plain_css_import, opposite_bracket},
-    ContextFlags, Options, Token,
+    ContextFlags, Deprecation, Options, Token,
 };
 
 use super::{
@@ -38,6 +38,17 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
     fn current_indentation(&self) -> usize;
     fn flags(&self) -> &ContextFlags;
     fn flags_mut(&mut self) -> &mut ContextFlags;
+    fn warnings_mut(&mut self) -> &mut Vec<ParseTimeWarning>;
+
+    /// Record a deprecation warning to be emitted when this stylesheet is
+    /// evaluated
+    fn warn_deprecation(&mut self, deprecation: Deprecation, message: String, span: Span) {
+        self.warnings_mut().push(ParseTimeWarning {
+            deprecation,
+            message,
+            span,
+        });
+    }
 
     #[allow(clippy::type_complexity)]
     const IDENTIFIER_LIKE: Option<fn(&mut Self) -> SassResult<Spanned<AstExpr>>> = None;
@@ -79,13 +90,19 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
 
         self.whitespace()?;
 
+        let before_at = self.toks().cursor();
         if self.scan_char('@') {
             if self.scan_identifier("else", true)? {
                 return Ok(true);
             }
 
             if self.scan_identifier("elseif", true)? {
-                // todo: deprecation warning here
+                let span = self.toks_mut().span_from(before_at);
+                self.warn_deprecation(
+                    Deprecation::Elseif,
+                    "@elseif is deprecated and will not be supported in future Sass versions.\n\nRecommendation: @else if".to_owned(),
+                    span,
+                );
                 let new_cursor = self.toks().cursor() - 2;
                 self.toks_mut().set_cursor(new_cursor);
                 return Ok(true);
@@ -220,6 +237,8 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
             }
         }
 
+        style_sheet.parse_time_warnings = mem::take(self.warnings_mut());
+
         Ok(style_sheet)
     }
 
@@ -423,7 +442,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
         self.expect_identifier("in", false)?;
         self.whitespace()?;
 
-        let list = self.parse_expression(None, None, None)?.node;
+        let list = self.parse_expression(None, None, None)?;
 
         let body = self.with_children(child)?.node;
 
@@ -432,7 +451,8 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
 
         Ok(AstStmt::Each(AstEach {
             variables,
-            list,
+            list: list.node,
+            list_span: list.span,
             body,
         }))
     }
@@ -890,6 +910,11 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
             }))
         } else {
             // todo: try parseImportUrl
+            self.warn_deprecation(
+                Deprecation::Import,
+                "Sass @import rules are deprecated and will be removed in Dart Sass 3.0.0.\n\nMore info and automated migrator: https://sass-lang.com/d/import".to_owned(),
+                span,
+            );
             Ok(AstImport::Sass(AstSassImport { url, span }))
         }
     }
@@ -1782,11 +1807,6 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
     fn parse_declaration_or_style_rule(&mut self) -> SassResult<AstStmt> {
         let start = self.toks().cursor();
 
-        if self.is_plain_css() && self.flags().in_style_rule() && !self.flags().in_unknown_at_rule()
-        {
-            return self.parse_property_or_variable_declaration(true);
-        }
-
         // The indented syntax allows a single backslash to distinguish a style rule
         // from old-style property syntax. We don't support old property syntax, but
         // we do support the backslash because it's easy to do.
@@ -2434,6 +2454,14 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
 
         let post_colon_whitespace = self.raw_text(Self::whitespace);
         if self.looking_at_children()? {
+            if self.is_plain_css() {
+                return Err((
+                    "Nested declarations aren't allowed in plain CSS.",
+                    self.toks().current_span(),
+                )
+                    .into());
+            }
+
             let body = self.with_children(Self::parse_declaration_child)?.node;
             return Ok(DeclarationOrBuffer::Stmt(AstStmt::Style(AstStyle {
                 name: name_buffer,
@@ -2483,6 +2511,14 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
         };
 
         if self.looking_at_children()? {
+            if self.is_plain_css() {
+                return Err((
+                    "Nested declarations aren't allowed in plain CSS.",
+                    self.toks().current_span(),
+                )
+                    .into());
+            }
+
             let body = self.with_children(Self::parse_declaration_child)?.node;
             Ok(DeclarationOrBuffer::Stmt(AstStmt::Style(AstStyle {
                 name: name_buffer,
@@ -1518,9 +1518,12 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
     fn parse_calculation_value(parser: &mut P) -> SassResult<Spanned<AstExpr>> {
         match parser.toks().peek() {
             Some(Token {
-                kind: '+' | '-' | '.' | '0'..='9',
+                kind: '+' | '.' | '0'..='9',
                 ..
             }) => ValueParser::parse_number(parser),
+            Some(Token { kind: '-', .. }) if !parser.looking_at_identifier() => {
+                ValueParser::parse_number(parser)
+            }
             Some(Token { kind: '$', .. }) => ValueParser::parse_variable(parser),
             Some(Token { kind: '(', .. }) => {
                 let start = parser.toks().cursor();
@@ -1559,11 +1562,29 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
                     );
                 }
 
+                let lowercase = ident.to_ascii_lowercase();
+
                 if !parser.toks().next_char_is('(') {
-                    return Err(("Expected \"(\" or \".\".", parser.toks().current_span()).into());
+                    let constant = match lowercase.as_str() {
+                        "pi" => std::f64::consts::PI,
+                        "e" => std::f64::consts::E,
+                        "infinity" => f64::INFINITY,
+                        "-infinity" => f64::NEG_INFINITY,
+                        "nan" => f64::NAN,
+                        _ => {
+                            return Err(
+                                ("Expected \"(\" or \".\".", parser.toks().current_span()).into()
+                            )
+                        }
+                    };
+
+                    return Ok(AstExpr::Number {
+                        n: Number(constant),
+                        unit: Unit::None,
+                    }
+                    .span(ident_span));
                 }
 
-                let lowercase = ident.to_ascii_lowercase();
                 let calculation = ValueParser::try_parse_calculation(parser, &lowercase, start)?;
 
                 if let Some(calc) = calculation {
@@ -1678,6 +1699,17 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
         parser: &mut P,
         max_args: Option<usize>,
         start: usize,
+    ) -> SassResult<Vec<AstExpr>> {
+        ValueParser::parse_calculation_arguments_with_strategy(parser, max_args, false, start)
+    }
+
+    /// `round()` may take a rounding strategy such as `up` as its first argument,
+    /// which is otherwise not a valid calculation value
+    fn parse_calculation_arguments_with_strategy(
+        parser: &mut P,
+        max_args: Option<usize>,
+        allow_rounding_strategy: bool,
+        start: usize,
     ) -> SassResult<Vec<AstExpr>> {
         parser.expect_char('(')?;
         if let Some(interpolation) =
@@ -1688,7 +1720,15 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
         }
 
         parser.whitespace()?;
-        let mut arguments = vec![ValueParser::parse_calculation_sum(parser)?.node];
+        let first = if allow_rounding_strategy {
+            ValueParser::try_parse_rounding_strategy(parser)?
+        } else {
+            None
+        };
+        let mut arguments = vec![match first {
+            Some(strategy) => strategy,
+            None => ValueParser::parse_calculation_sum(parser)?.node,
+        }];
 
         while (max_args.is_none() || arguments.len() < max_args.unwrap()) && parser.scan_char(',') {
             parser.whitespace()?;
@@ -1707,6 +1747,29 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
         Ok(arguments)
     }
 
+    fn try_parse_rounding_strategy(parser: &mut P) -> SassResult<Option<AstExpr>> {
+        if !parser.looking_at_identifier() {
+            return Ok(None);
+        }
+
+        let start = parser.toks().cursor();
+        let ident = parser.parse_identifier(false, false)?;
+        let span = parser.toks_mut().span_from(start);
+        parser.whitespace()?;
+
+        if !matches!(ident.as_str(), "nearest" | "up" | "down" | "to-zero")
+            || !parser.toks().next_char_is(',')
+        {
+            parser.toks_mut().set_cursor(start);
+            return Ok(None);
+        }
+
+        Ok(Some(AstExpr::String(
+            StringExpr(Interpolation::new_plain(ident), QuoteKind::None),
+            span,
+        )))
+    }
+
     fn try_parse_calculation(
         parser: &mut P,
         name: &str,
@@ -1724,12 +1787,25 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
                 }
                 .span(parser.toks_mut().span_from(start))
             }
-            "min" | "max" => {
-                // min() and max() are parsed as calculations if possible, and otherwise
-                // are parsed as normal Sass functions.
+            "min" | "max" | "round" | "abs" => {
+                // These share their names with global Sass functions, so they are parsed
+                // as calculations if possible, and otherwise are parsed as normal Sass
+                // functions.
                 let before_args = parser.toks().cursor();
 
-                let args = match ValueParser::parse_calculation_arguments(parser, None, start) {
+                let (name, max_args) = match name {
+                    "min" => (CalculationName::Min, None),
+                    "max" => (CalculationName::Max, None),
+                    "round" => (CalculationName::Round, Some(3)),
+                    _ => (CalculationName::Abs, Some(1)),
+                };
+
+                let args = match ValueParser::parse_calculation_arguments_with_strategy(
+                    parser,
+                    max_args,
+                    name == CalculationName::Round,
+                    start,
+                ) {
                     Ok(args) => args,
                     Err(..) => {
                         parser.toks_mut().set_cursor(before_args);
@@ -1737,15 +1813,7 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
                     }
                 };
 
-                AstExpr::Calculation {
-                    name: if name == "min" {
-                        CalculationName::Min
-                    } else {
-                        CalculationName::Max
-                    },
-                    args,
-                }
-                .span(parser.toks_mut().span_from(start))
+                AstExpr::Calculation { name, args }.span(parser.toks_mut().span_from(start))
             }
             "clamp" => {
                 let args = ValueParser::parse_calculation_arguments(parser, Some(3), start)?;
@@ -1755,7 +1823,13 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
                 }
                 .span(parser.toks_mut().span_from(start))
             }
-            _ => return Ok(None),
+            _ => match CalculationName::from_math_function(name) {
+                Some((name, max_args)) => {
+                    let args = ValueParser::parse_calculation_arguments(parser, max_args, start)?;
+                    AstExpr::Calculation { name, args }.span(parser.toks_mut().span_from(start))
+                }
+                None => return Ok(None),
+            },
         }))
     }
 
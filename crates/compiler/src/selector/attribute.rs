@@ -102,6 +102,12 @@ fn attribute_operator(parser: &mut SelectorParser) -> SassResult<AttributeOp> {
     Ok(op)
 }
 impl Attribute {
+    /// The case-sensitivity modifier of this selector, such as `i` in
+    /// `[a="b" i]`
+    pub fn modifier(&self) -> Option<char> {
+        self.modifier
+    }
+
     pub fn from_tokens(parser: &mut SelectorParser) -> SassResult<Attribute> {
         let start = parser.toks.cursor();
         parser.whitespace_without_comments();
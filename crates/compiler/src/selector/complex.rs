@@ -268,6 +268,24 @@ impl ComplexSelector {
             }
         })
     }
+
+    /// Whether any parent selector in this selector has a suffix, as in `&-foo`.
+    pub fn contains_parent_suffix(&self) -> bool {
+        self.components.iter().any(|c| {
+            if let ComplexSelectorComponent::Compound(compound) = c {
+                compound.components.iter().any(|simple| match simple {
+                    SimpleSelector::Parent(suffix) => suffix.is_some(),
+                    SimpleSelector::Pseudo(Pseudo {
+                        selector: Some(sel),
+                        ..
+                    }) => sel.contains_parent_suffix(),
+                    _ => false,
+                })
+            } else {
+                false
+            }
+        })
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Copy, Hash)]
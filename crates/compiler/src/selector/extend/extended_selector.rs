@@ -37,6 +37,12 @@ impl ExtendedSelector {
         Self(Rc::new(RefCell::new(selector)))
     }
 
+    /// An identifier for this selector that remains stable as its value is
+    /// extended.
+    pub fn id(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+
     pub fn is_invisible(&self) -> bool {
         (*self.0).borrow().is_invisible()
     }
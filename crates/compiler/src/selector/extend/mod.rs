@@ -7,7 +7,10 @@ use codemap::Span;
 
 use indexmap::IndexMap;
 
-use crate::{ast::CssMediaQuery, error::SassResult};
+use crate::{
+    ast::CssMediaQuery,
+    error::{SassError, SassResult},
+};
 
 use super::{
     ComplexSelector, ComplexSelectorComponent, ComplexSelectorHashSet, CompoundSelector, Pseudo,
@@ -75,8 +78,10 @@ pub(crate) struct ExtensionStore {
     /// A map from CSS selectors to the media query contexts they're defined in.
     ///
     /// This tracks the contexts in which each selector's style rule is defined.
-    /// If a rule is defined at the top level, it doesn't have an entry.
-    media_contexts: HashMap<SelectorList, Vec<CssMediaQuery>>,
+    /// If a rule is defined at the top level, it doesn't have an entry. Entries
+    /// are keyed by `ExtendedSelector::id`, since distinct rules may share a
+    /// selector.
+    media_contexts: HashMap<usize, Vec<CssMediaQuery>>,
 
     /// A map from `SimpleSelector`s to the specificity of their source
     /// selectors.
@@ -101,6 +106,12 @@ pub(crate) struct ExtensionStore {
     /// The mode that controls this extender's behavior.
     mode: ExtendMode,
 
+    /// The first error encountered while extending a selector.
+    ///
+    /// Extension is written in terms of `Option`s, so errors are recorded here
+    /// and surfaced once the public entry point returns.
+    error: Option<Box<SassError>>,
+
     span: Span,
 }
 
@@ -128,6 +139,7 @@ impl ExtensionStore {
             source_specificity: HashMap::new(),
             originals: ComplexSelectorHashSet::new(),
             mode: ExtendMode::Normal,
+            error: None,
             span,
         }
     }
@@ -188,7 +200,10 @@ impl ExtensionStore {
             extender.originals.extend(selector.components.iter());
         }
 
-        Ok(extender.extend_list(selector, Some(&extensions), &None))
+        let selector = extender.extend_list(selector, Some(&extensions), &None);
+        extender.take_error()?;
+
+        Ok(selector)
     }
 
     fn with_mode(mode: ExtendMode, span: Span) -> Self {
@@ -418,7 +433,9 @@ impl ExtensionStore {
                     .clone()
                     .into_iter()
                     .map(|state| {
-                        state.assert_compatible_media_context(media_query_context);
+                        if let Err(e) = state.assert_compatible_media_context(media_query_context) {
+                            self.error.get_or_insert(e);
+                        }
                         state.extender
                     })
                     .collect(),
@@ -450,6 +467,7 @@ impl ExtensionStore {
         //       [.w .y .x.z, .y .w .x.z]
         //     ]
         let mut first = self.mode != ExtendMode::Replace;
+        let mut error = None;
 
         let unified_paths = paths(options).into_iter().map(|path| {
             let complexes: Vec<Vec<ComplexSelectorComponent>> = if first {
@@ -499,7 +517,9 @@ impl ExtensionStore {
             let mut line_break = false;
 
             for state in path {
-                state.assert_compatible_media_context(media_query_context);
+                if let Err(e) = state.assert_compatible_media_context(media_query_context) {
+                    error.get_or_insert(e);
+                }
                 line_break = line_break || state.extender.line_break;
             }
 
@@ -513,6 +533,10 @@ impl ExtensionStore {
 
         let unified_paths: Vec<ComplexSelector> = unified_paths.flatten().flatten().collect();
 
+        if let Some(e) = error {
+            self.error.get_or_insert(e);
+        }
+
         Some(if in_original && self.mode != ExtendMode::Replace {
             let original = unified_paths.first().cloned();
             self.trim(unified_paths, &|complex| Some(complex) == original.as_ref())
@@ -865,7 +889,7 @@ impl ExtensionStore {
         mut selector: SelectorList,
         // span: Span,
         media_query_context: &Option<Vec<CssMediaQuery>>,
-    ) -> ExtendedSelector {
+    ) -> SassResult<ExtendedSelector> {
         if !selector.is_invisible() {
             for complex in selector.components.clone() {
                 self.originals.insert(&complex);
@@ -874,24 +898,154 @@ impl ExtensionStore {
 
         if !self.extensions.is_empty() {
             selector = self.extend_list(selector, None, media_query_context);
-            /*
-              todo: when we have error handling
-                  } on SassException catch (error) {
-              throw SassException(
-                  "From ${error.span.message('')}\n"
-                  "${error.message}",
-                  span);
-            }
-              */
+            self.take_error()?;
         }
-        if let Some(mut media_query_context) = media_query_context.clone() {
+        let extended_selector = ExtendedSelector::new(selector.clone());
+        if let Some(media_query_context) = media_query_context.clone() {
             self.media_contexts
-                .get_mut(&selector)
-                .replace(&mut media_query_context);
+                .insert(extended_selector.id(), media_query_context);
         }
-        let extended_selector = ExtendedSelector::new(selector.clone());
         self.register_selector(selector, &extended_selector);
-        extended_selector
+        Ok(extended_selector)
+    }
+
+    /// Returns the first error encountered while extending, if any.
+    fn take_error(&mut self) -> SassResult<()> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether this extender has no extensions.
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    /// All simple selectors in the style rules handled by this extender.
+    pub fn simple_selectors(&self) -> HashSet<SimpleSelector> {
+        self.selectors.keys().cloned().collect()
+    }
+
+    /// Returns the target and span of every mandatory extension whose target
+    /// matches `callback`, in the order in which they were defined.
+    pub fn extensions_where_target(
+        &self,
+        callback: impl Fn(&SimpleSelector) -> bool,
+    ) -> Vec<(SimpleSelector, Span)> {
+        let mut extensions: Vec<(SimpleSelector, Span)> = self
+            .extensions
+            .iter()
+            .filter(|(target, ..)| callback(target))
+            .flat_map(|(target, sources)| {
+                sources
+                    .values()
+                    .filter(|extension| !extension.is_optional)
+                    .map(move |extension| (target.clone(), extension.span))
+            })
+            .collect();
+
+        extensions.sort_by_key(|(_, span)| span.low());
+
+        extensions
+    }
+
+    /// Extends `self` with all the extensions in `extension_stores`.
+    ///
+    /// This is used to apply the extensions defined in downstream modules to the
+    /// selectors of the modules they use.
+    pub fn add_extensions(&mut self, extension_stores: &[&ExtensionStore]) -> SassResult<()> {
+        // Extensions already in `self` whose extenders are extended by
+        // `extension_stores`, and thus which need to be updated.
+        let mut extensions_to_extend: Option<Vec<Extension>> = None;
+
+        // Selectors that contain simple selectors that are extended by
+        // `extension_stores`, and thus which need to be extended themselves.
+        let mut selectors_to_extend: Option<SelectorHashSet> = None;
+
+        // An extension map with the same structure as `self.extensions` that only
+        // includes extensions from `extension_stores`.
+        let mut new_extensions: Option<
+            HashMap<SimpleSelector, IndexMap<ComplexSelector, Extension>>,
+        > = None;
+
+        for extension_store in extension_stores {
+            if extension_store.is_empty() {
+                continue;
+            }
+
+            for (simple, specificity) in &extension_store.source_specificity {
+                self.source_specificity.insert(simple.clone(), *specificity);
+            }
+
+            for (target, new_sources) in &extension_store.extensions {
+                // Private selectors can't be extended across module boundaries.
+                if let SimpleSelector::Placeholder(name) = target {
+                    if name.starts_with('-') || name.starts_with('_') {
+                        continue;
+                    }
+                }
+
+                // Find existing extensions to extend.
+                let extensions_for_target = self.extensions_by_extender.get(target).cloned();
+                if let Some(extensions_for_target) = &extensions_for_target {
+                    extensions_to_extend
+                        .get_or_insert_with(Vec::new)
+                        .extend(extensions_for_target.iter().cloned());
+                }
+
+                // Find existing selectors to extend.
+                let selectors_for_target = self.selectors.get(target).cloned();
+                if let Some(selectors_for_target) = &selectors_for_target {
+                    let selectors_to_extend =
+                        selectors_to_extend.get_or_insert_with(SelectorHashSet::new);
+                    for selector in selectors_for_target.clone() {
+                        selectors_to_extend.insert(selector);
+                    }
+                }
+
+                let should_extend =
+                    extensions_for_target.is_some() || selectors_for_target.is_some();
+
+                // Add `new_sources` to `self.extensions`.
+                let existing_sources = self.extensions.entry(target.clone()).or_default();
+
+                for (extender, extension) in new_sources {
+                    let extension = match existing_sources.get(extender) {
+                        Some(existing) => {
+                            MergedExtension::merge(existing.clone(), extension.clone())?
+                        }
+                        None => extension.clone(),
+                    };
+                    existing_sources.insert(extender.clone(), extension.clone());
+
+                    if should_extend {
+                        new_extensions
+                            .get_or_insert_with(HashMap::new)
+                            .entry(target.clone())
+                            .or_default()
+                            .insert(extender.clone(), extension);
+                    }
+                }
+            }
+        }
+
+        let new_extensions = match new_extensions {
+            Some(new_extensions) => new_extensions,
+            None => return Ok(()),
+        };
+
+        if let Some(extensions_to_extend) = extensions_to_extend {
+            // We can ignore the return value here because it's only useful for extend
+            // loops, which can't exist across module boundaries.
+            self.extend_existing_extensions(extensions_to_extend, &new_extensions);
+        }
+
+        if let Some(selectors_to_extend) = selectors_to_extend {
+            self.extend_existing_selectors(selectors_to_extend, &new_extensions);
+        }
+
+        self.take_error()
     }
 
     /// Registers the `SimpleSelector`s in `list` to point to `selector` in
@@ -942,7 +1096,7 @@ impl ExtensionStore {
         extend: &ExtendRule,
         media_context: &Option<Vec<CssMediaQuery>>,
         span: Span,
-    ) {
+    ) -> SassResult<()> {
         let selectors = self.selectors.get(target).cloned();
         let existing_extensions = self.extensions_by_extender.get(target).cloned();
 
@@ -970,8 +1124,8 @@ impl ExtensionStore {
                 // If there's already an extend from `extender` to `target`, we don't need
                 // to re-run the extension. We may need to mark the extension as
                 // mandatory, though.
-                let mut new_val = MergedExtension::merge(existing_state.clone(), state).unwrap();
-                sources.get_mut(&complex).replace(&mut new_val);
+                let merged = MergedExtension::merge(existing_state.clone(), state)?;
+                sources.insert(complex.clone(), merged);
                 continue;
             }
 
@@ -1003,7 +1157,7 @@ impl ExtensionStore {
         let new_extensions = if let Some(new) = new_extensions {
             new
         } else {
-            return;
+            return Ok(());
         };
 
         let mut new_extensions_by_target = HashMap::new();
@@ -1020,6 +1174,8 @@ impl ExtensionStore {
         if let Some(selectors) = selectors {
             self.extend_existing_selectors(selectors, &new_extensions_by_target);
         }
+
+        self.take_error()
     }
 
     /// Extend `extensions` using `new_extensions`.
@@ -1086,14 +1242,16 @@ impl ExtensionStore {
                 let with_extender = extension.clone().with_extender(complex.clone());
                 let existing_extension = sources.get(&complex);
                 if let Some(existing_extension) = existing_extension.cloned() {
-                    sources.get_mut(&complex).replace(
-                        &mut MergedExtension::merge(existing_extension.clone(), with_extender)
-                            .unwrap(),
-                    );
+                    match MergedExtension::merge(existing_extension, with_extender) {
+                        Ok(merged) => {
+                            sources.insert(complex.clone(), merged);
+                        }
+                        Err(e) => {
+                            self.error.get_or_insert(e);
+                        }
+                    }
                 } else {
-                    sources
-                        .get_mut(&complex)
-                        .replace(&mut with_extender.clone());
+                    sources.insert(complex.clone(), with_extender.clone());
 
                     for component in complex.components.clone() {
                         if let ComplexSelectorComponent::Compound(component) = component {
@@ -1119,8 +1277,11 @@ impl ExtensionStore {
             // was replaced due to :not() expansion, we must get rid of the old
             // version.
             if !contains_extension {
-                sources.remove(&extension.extender);
+                sources.shift_remove(&extension.extender);
             }
+
+            self.extensions
+                .insert(extension.target.clone().unwrap(), sources);
         }
         additional_extensions
     }
@@ -1136,7 +1297,7 @@ impl ExtensionStore {
             selector.set_inner(self.extend_list(
                 old_value.clone(),
                 Some(new_extensions),
-                &self.media_contexts.get(&old_value).cloned(),
+                &self.media_contexts.get(&selector.id()).cloned(),
             ));
             /*
             todo: error handling
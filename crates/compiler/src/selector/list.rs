@@ -76,6 +76,12 @@ impl SelectorList {
             .any(ComplexSelector::contains_parent_selector)
     }
 
+    pub fn contains_parent_suffix(&self) -> bool {
+        self.components
+            .iter()
+            .any(ComplexSelector::contains_parent_suffix)
+    }
+
     pub const fn new(span: Span) -> Self {
         Self {
             components: Vec::new(),
@@ -146,6 +152,40 @@ impl SelectorList {
         })
     }
 
+    /// Returns a new list nested within `parent` that leaves any
+    /// `SimpleSelector::Parent`s in place.
+    ///
+    /// This is used for plain CSS, in which `&` is part of the native nesting
+    /// syntax rather than a reference to be resolved.
+    pub fn nest_within_preserving_parents(
+        self,
+        parent: Option<Self>,
+        implicit_parent: bool,
+    ) -> Self {
+        let parent = match parent {
+            Some(parent) if implicit_parent => parent,
+            Some(..) | None => return self,
+        };
+
+        Self {
+            components: self
+                .components
+                .iter()
+                .flat_map(|complex| {
+                    parent.components.iter().map(move |parent_complex| {
+                        let mut components = parent_complex.components.clone();
+                        components.extend(complex.components.iter().cloned());
+                        ComplexSelector::new(
+                            components,
+                            complex.line_break || parent_complex.line_break,
+                        )
+                    })
+                })
+                .collect(),
+            span: self.span,
+        }
+    }
+
     /// Returns a new list with all `SimpleSelector::Parent`s replaced with `parent`.
     ///
     /// If `implicit_parent` is true, this treats `ComplexSelector`s that don't
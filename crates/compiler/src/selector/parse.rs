@@ -40,6 +40,10 @@ pub(crate) struct SelectorParser<'a> {
     /// Whether this parser allows placeholder selectors beginning with `%`.
     allows_placeholder: bool,
 
+    /// Whether this parser is parsing a plain CSS selector, in which parent
+    /// selectors can't have suffixes.
+    plain_css: bool,
+
     pub toks: Lexer<'a>,
 
     span: Span,
@@ -61,10 +65,16 @@ impl<'a> SelectorParser<'a> {
             toks,
             allows_parent,
             allows_placeholder,
+            plain_css: false,
             span,
         }
     }
 
+    pub fn plain_css(mut self, plain_css: bool) -> Self {
+        self.plain_css = plain_css;
+        self
+    }
+
     pub fn parse(mut self) -> SassResult<SelectorList> {
         let tmp = self.parse_selector_list()?;
         if self.toks.peek().is_some() {
@@ -319,6 +329,14 @@ impl<'a> SelectorParser<'a> {
     fn parse_parent_selector(&mut self) -> SassResult<SimpleSelector> {
         self.toks.next();
         let suffix = if self.looking_at_identifier_body() {
+            if self.plain_css {
+                return Err((
+                    "Parent selectors can't have suffixes in plain CSS.",
+                    self.span,
+                )
+                    .into());
+            }
+
             let mut buffer = String::new();
             self.parse_identifier_body(&mut buffer, false, false)?;
             Some(buffer)
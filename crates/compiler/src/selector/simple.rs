@@ -82,7 +82,9 @@ impl fmt::Display for SimpleSelector {
             Self::Pseudo(pseudo) => write!(f, "{}", pseudo),
             Self::Type(name) => write!(f, "{}", name),
             Self::Attribute(attr) => write!(f, "{}", attr),
-            Self::Parent(..) => unreachable!("It should not be possible to format `&`."),
+            // Parent selectors are only preserved in plain CSS, where they're part of
+            // the native nesting syntax
+            Self::Parent(suffix) => write!(f, "&{}", suffix.as_deref().unwrap_or("")),
         }
     }
 }
@@ -124,12 +126,12 @@ impl SimpleSelector {
             | Self::Type(..)
             | Self::Id(..)
             | Self::Class(..)
-            | Self::Attribute(..) => false,
+            | Self::Attribute(..)
+            | Self::Parent(..) => false,
             Self::Pseudo(Pseudo { name, selector, .. }) => {
                 name != "not" && selector.as_ref().map_or(false, |sel| sel.is_invisible())
             }
             Self::Placeholder(..) => true,
-            Self::Parent(..) => unreachable!("parent selectors should be resolved at this point"),
         }
     }
 
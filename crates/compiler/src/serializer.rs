@@ -1,22 +1,23 @@
-use std::io::Write;
+use std::io::{self, Write};
 
 use codemap::{CodeMap, Span};
 
 use crate::{
     ast::{CssStmt, MediaQuery, Style, SupportsRule},
-    color::{Color, ColorFormat, NAMED_COLORS},
+    color::{Color, ColorFormat, ColorSpace, NAMED_COLORS},
     common::{BinaryOp, Brackets, ListSeparator, QuoteKind},
     error::SassResult,
     selector::{
         Combinator, ComplexSelector, ComplexSelectorComponent, CompoundSelector, Namespace, Pseudo,
         SelectorList, SimpleSelector,
     },
+    unit::Unit,
     utils::hex_char_for,
     value::{
         fuzzy_equals, ArgList, CalculationArg, CalculationName, SassCalculation, SassFunction,
-        SassMap, SassNumber, Value,
+        SassMap, SassMixin, SassNumber, Value,
     },
-    Options,
+    LineFeed, Options, OutputStyle,
 };
 
 pub(crate) fn serialize_selector_list(
@@ -107,6 +108,30 @@ pub(crate) fn inspect_function_ref(
     Ok(serializer.finish_for_expr())
 }
 
+pub(crate) fn inspect_mixin_ref(
+    mixin: &SassMixin,
+    options: &Options,
+    span: Span,
+) -> SassResult<String> {
+    let code_map = CodeMap::new();
+    let mut serializer = Serializer::new(options, &code_map, true, span);
+
+    serializer.visit_mixin_ref(mixin, span)?;
+
+    Ok(serializer.finish_for_expr())
+}
+
+pub(crate) fn serialize_quoted_string(string: &str) -> String {
+    let options = Options::default();
+    let mut map = CodeMap::new();
+    let span = map.add_file(String::new(), String::new()).span;
+    let mut serializer = Serializer::new(&options, &map, false, span);
+
+    serializer.visit_quoted_string(false, string);
+
+    serializer.finish_for_expr()
+}
+
 pub(crate) fn inspect_number(
     number: &SassNumber,
     options: &Options,
@@ -128,6 +153,8 @@ pub(crate) struct Serializer<'a> {
     // todo: use this field
     _quote: bool,
     buffer: Vec<u8>,
+    /// Whether any of `buffer` has been flushed to a writer
+    has_flushed: bool,
     map: &'a CodeMap,
     span: Span,
 }
@@ -138,9 +165,10 @@ impl<'a> Serializer<'a> {
             inspect,
             _quote: true,
             indentation: 0,
-            indent_width: 2,
+            indent_width: options.indent_width,
             options,
             buffer: Vec::new(),
+            has_flushed: false,
             map,
             span,
         }
@@ -221,7 +249,7 @@ impl<'a> Serializer<'a> {
                 self.buffer.extend_from_slice(name.ident.as_bytes());
             }
             SimpleSelector::Attribute(attr) => write!(&mut self.buffer, "{}", attr).unwrap(),
-            SimpleSelector::Parent(..) => unreachable!("It should not be possible to format `&`."),
+            SimpleSelector::Parent(..) => write!(&mut self.buffer, "{}", simple).unwrap(),
         }
     }
 
@@ -295,8 +323,10 @@ impl<'a> Serializer<'a> {
     }
 
     fn write_newline(&mut self) {
-        if !self.options.is_compressed() {
-            self.buffer.push(b'\n');
+        match self.options.style {
+            OutputStyle::Expanded | OutputStyle::Nested => self.buffer.push(b'\n'),
+            OutputStyle::Compact => self.buffer.push(b' '),
+            OutputStyle::Compressed => {}
         }
     }
 
@@ -306,12 +336,7 @@ impl<'a> Serializer<'a> {
     }
 
     fn write_calculation_name(&mut self, name: CalculationName) {
-        match name {
-            CalculationName::Calc => self.buffer.extend_from_slice(b"calc"),
-            CalculationName::Min => self.buffer.extend_from_slice(b"min"),
-            CalculationName::Max => self.buffer.extend_from_slice(b"max"),
-            CalculationName::Clamp => self.buffer.extend_from_slice(b"clamp"),
-        }
+        self.buffer.extend_from_slice(name.as_str().as_bytes());
     }
 
     fn visit_calculation(&mut self, calculation: &SassCalculation) -> SassResult<()> {
@@ -334,6 +359,9 @@ impl<'a> Serializer<'a> {
 
     fn write_calculation_arg(&mut self, arg: &CalculationArg) -> SassResult<()> {
         match arg {
+            CalculationArg::Number(num) if !num.num.is_finite() => {
+                self.write_calculation_number(num);
+            }
             CalculationArg::Number(num) => self.visit_number(num)?,
             CalculationArg::Calculation(calc) => {
                 self.visit_calculation(calc)?;
@@ -377,6 +405,13 @@ impl<'a> Serializer<'a> {
                     CalculationArg::Operation { op: op2, .. } => {
                         CalculationArg::parenthesize_calculation_rhs(*op, *op2)
                     }
+                    CalculationArg::Number(num) if *op == BinaryOp::Div => {
+                        if num.num.is_finite() {
+                            num.unit.is_complex()
+                        } else {
+                            num.unit != Unit::None
+                        }
+                    }
                     _ => false,
                 };
 
@@ -462,7 +497,74 @@ impl<'a> Serializer<'a> {
             && Self::is_symmetrical_hex(color.blue().0.round() as u32)
     }
 
+    /// Write a color in a non-legacy space, or a legacy color with missing
+    /// channels, using the CSS Color Level 4 syntax
+    fn write_space_color(&mut self, color: &Color) {
+        let space = color.space();
+
+        if space.uses_color_function() {
+            self.buffer.extend_from_slice(b"color(");
+            self.buffer.extend_from_slice(space.name().as_bytes());
+            self.buffer.push(b' ');
+        } else {
+            self.buffer.extend_from_slice(space.name().as_bytes());
+            self.buffer.push(b'(');
+        }
+
+        for (idx, (channel, info)) in color
+            .channels()
+            .iter()
+            .zip(space.channels().iter())
+            .enumerate()
+        {
+            if idx != 0 {
+                self.buffer.push(b' ');
+            }
+
+            let channel = match channel {
+                Some(channel) => channel.0,
+                None => {
+                    self.buffer.extend_from_slice(b"none");
+                    continue;
+                }
+            };
+
+            match (space, info.name) {
+                (ColorSpace::Oklab | ColorSpace::Oklch, "lightness") => {
+                    self.write_float(channel * 100.0);
+                    self.buffer.push(b'%');
+                }
+                (
+                    ColorSpace::Lab | ColorSpace::Lch | ColorSpace::Hsl | ColorSpace::Hwb,
+                    "lightness" | "saturation" | "whiteness" | "blackness",
+                ) => {
+                    self.write_float(channel);
+                    self.buffer.push(b'%');
+                }
+                _ if info.is_hue => {
+                    self.write_float(channel);
+                    self.buffer.extend_from_slice(b"deg");
+                }
+                _ => self.write_float(channel),
+            }
+        }
+
+        if color.is_alpha_missing() {
+            self.buffer.extend_from_slice(b" / none");
+        } else if !fuzzy_equals(color.alpha().0, 1.0) {
+            self.buffer.extend_from_slice(b" / ");
+            self.write_float(color.alpha().0);
+        }
+
+        self.buffer.push(b')');
+    }
+
     pub fn visit_color(&mut self, color: &Color) {
+        if !color.is_legacy() || color.has_missing_channels() {
+            self.write_space_color(color);
+            return;
+        }
+
         let red = color.red().0.round() as u8;
         let green = color.green().0.round() as u8;
         let blue = color.blue().0.round() as u8;
@@ -548,6 +650,13 @@ impl<'a> Serializer<'a> {
             return Ok(());
         }
 
+        if !number.num.is_finite() {
+            self.buffer.extend_from_slice(b"calc(");
+            self.write_calculation_number(number);
+            self.buffer.push(b')');
+            return Ok(());
+        }
+
         if !self.inspect && number.unit.is_complex() {
             return Err((
                 format!(
@@ -565,6 +674,32 @@ impl<'a> Serializer<'a> {
         Ok(())
     }
 
+    /// Non-finite numbers can only be represented in CSS inside a calculation,
+    /// where they are written as a constant multiplied by each of their units
+    fn write_calculation_number(&mut self, number: &SassNumber) {
+        let value = number.num.0;
+        if value.is_nan() {
+            self.buffer.extend_from_slice(b"NaN");
+        } else if value == f64::INFINITY {
+            self.buffer.extend_from_slice(b"infinity");
+        } else if value == f64::NEG_INFINITY {
+            self.buffer.extend_from_slice(b"-infinity");
+        } else {
+            self.write_float(value);
+        }
+
+        let (numer, denom) = number.unit.clone().numer_and_denom();
+
+        for (op, units) in [(b'*', numer), (b'/', denom)] {
+            for unit in units {
+                self.write_optional_space();
+                self.buffer.push(op);
+                self.write_optional_space();
+                write!(&mut self.buffer, "1{}", unit).unwrap();
+            }
+        }
+    }
+
     fn write_float(&mut self, float: f64) {
         if float.is_infinite() && float.is_sign_negative() {
             self.buffer.extend_from_slice(b"-Infinity");
@@ -614,11 +749,11 @@ impl<'a> Serializer<'a> {
             self.buffer.push(b';');
         }
 
-        if !self.buffer.is_empty() {
+        if !self.is_empty() {
             self.write_optional_newline();
         }
 
-        if prev_was_group_end && !self.buffer.is_empty() {
+        if prev_was_group_end && !self.is_empty() {
             self.write_optional_newline();
         }
 
@@ -627,42 +762,191 @@ impl<'a> Serializer<'a> {
         Ok(())
     }
 
+    /// Whether nothing has been serialized, including output which has already
+    /// been flushed
+    fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && !self.has_flushed
+    }
+
+    /// Whether serializing `stmt` writes any non-ASCII text, in which case the
+    /// stylesheet must be preceded by a `@charset` rule or byte-order mark
+    ///
+    /// This allows that prefix to be written before the first group is flushed,
+    /// without having to serialize the stylesheet first.
+    pub fn writes_non_ascii(&self, stmt: &CssStmt) -> bool {
+        if stmt.is_invisible() {
+            return false;
+        }
+
+        let children_write_non_ascii =
+            |children: &[CssStmt]| children.iter().any(|child| self.writes_non_ascii(child));
+
+        match stmt {
+            CssStmt::RuleSet { selector, body, .. } => {
+                selector
+                    .as_selector_list()
+                    .components
+                    .iter()
+                    .filter(|complex| !complex.is_invisible())
+                    .any(|complex| !complex.to_string().is_ascii())
+                    || children_write_non_ascii(body)
+            }
+            CssStmt::Style(style) => {
+                !style.property.resolve_ref().is_ascii()
+                    || value_writes_non_ascii(&style.value.node)
+            }
+            CssStmt::Media(media_rule, ..) => {
+                media_rule.query.iter().any(|query| {
+                    query
+                        .modifier
+                        .iter()
+                        .chain(&query.media_type)
+                        .chain(&query.conditions)
+                        .any(|text| !text.is_ascii())
+                }) || children_write_non_ascii(&media_rule.body)
+            }
+            CssStmt::UnknownAtRule(unknown_at_rule, ..) => {
+                !unknown_at_rule.name.is_ascii()
+                    || !unknown_at_rule.params.is_ascii()
+                    || children_write_non_ascii(&unknown_at_rule.body)
+            }
+            CssStmt::Supports(supports_rule, ..) => {
+                !supports_rule.params.is_ascii() || children_write_non_ascii(&supports_rule.body)
+            }
+            CssStmt::Comment(comment, ..) => {
+                (!self.options.is_compressed() || comment.starts_with("/*!")) && !comment.is_ascii()
+            }
+            CssStmt::KeyframesRuleSet(keyframes_rule_set) => {
+                keyframes_rule_set
+                    .selector
+                    .iter()
+                    .any(|selector| !selector.to_string().is_ascii())
+                    || children_write_non_ascii(&keyframes_rule_set.body)
+            }
+            CssStmt::Import(import, modifiers) => {
+                !import.is_ascii() || modifiers.as_ref().map_or(false, |m| !m.is_ascii())
+            }
+        }
+    }
+
+    /// The text which precedes a stylesheet containing non-ASCII characters
+    fn charset_prefix(&self) -> &'static str {
+        if self.options.is_compressed() {
+            "\u{FEFF}"
+        } else {
+            "@charset \"UTF-8\";\n"
+        }
+    }
+
+    /// Writes `bytes` to `writer`, replacing each `\n` with the configured
+    /// [`LineFeed`]
+    fn write_with_linefeeds<W: Write>(&self, writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+        if self.options.linefeed == LineFeed::Lf {
+            return writer.write_all(bytes);
+        }
+
+        for (idx, line) in bytes.split(|&b| b == b'\n').enumerate() {
+            if idx != 0 {
+                writer.write_all(self.options.linefeed.as_str().as_bytes())?;
+            }
+
+            writer.write_all(line)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the `@charset` rule or byte-order mark which precedes a
+    /// stylesheet containing non-ASCII text, unless this is disabled
+    pub fn write_charset<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        if !self.options.allows_charset {
+            return Ok(());
+        }
+
+        self.write_with_linefeeds(writer, self.charset_prefix().as_bytes())
+    }
+
+    /// Writes everything serialized so far to `writer`, clearing the buffer
+    pub fn flush<W: Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        self.write_with_linefeeds(writer, &self.buffer)?;
+        self.buffer.clear();
+        self.has_flushed = true;
+
+        Ok(())
+    }
+
     fn finish_for_expr(self) -> String {
         // SAFETY: todo
         unsafe { String::from_utf8_unchecked(self.buffer) }
     }
 
-    pub fn finish(mut self, prev_requires_semicolon: bool) -> String {
-        let is_not_ascii = self.buffer.iter().any(|&c| !c.is_ascii());
-
+    fn write_trailer(&mut self, prev_requires_semicolon: bool) {
         if prev_requires_semicolon {
             self.buffer.push(b';');
         }
 
-        if !self.buffer.is_empty() {
+        if !self.is_empty() {
             self.write_optional_newline();
         }
+    }
+
+    pub fn finish(mut self, prev_requires_semicolon: bool) -> String {
+        let is_not_ascii = !self.buffer.is_ascii();
+        let charset_prefix = self.charset_prefix();
+
+        self.write_trailer(prev_requires_semicolon);
 
         // SAFETY: todo
         let mut as_string = unsafe { String::from_utf8_unchecked(self.buffer) };
 
-        if is_not_ascii && self.options.is_compressed() && self.options.allows_charset {
-            as_string.insert(0, '\u{FEFF}');
-        } else if is_not_ascii && self.options.allows_charset {
-            as_string.insert_str(0, "@charset \"UTF-8\";\n");
+        if is_not_ascii && self.options.allows_charset {
+            as_string.insert_str(0, charset_prefix);
+        }
+
+        if self.options.linefeed != LineFeed::Lf {
+            as_string = as_string.replace('\n', self.options.linefeed.as_str());
         }
 
         as_string
     }
 
+    /// Writes the end of the stylesheet to `writer`, once every group has been
+    /// visited and flushed
+    ///
+    /// Unlike [`Self::finish`], this doesn't write a `@charset` rule, which
+    /// must instead be written using [`Self::write_charset`] before the first
+    /// group is flushed.
+    pub fn finish_to_writer<W: Write>(
+        mut self,
+        prev_requires_semicolon: bool,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        self.write_trailer(prev_requires_semicolon);
+        self.flush(writer)
+    }
+
     fn write_indentation(&mut self) {
-        if self.options.is_compressed() {
+        // In compact output, indentation is written by the parent only when a
+        // child begins a new line
+        if matches!(
+            self.options.style,
+            OutputStyle::Compressed | OutputStyle::Compact
+        ) {
             return;
         }
 
+        self.write_indentation_unconditionally();
+    }
+
+    fn write_indentation_unconditionally(&mut self) {
+        let indent = self.options.indent_type.as_byte();
         self.buffer.reserve(self.indentation);
         for _ in 0..self.indentation {
-            self.buffer.push(b' ');
+            self.buffer.push(indent);
         }
     }
 
@@ -934,6 +1218,25 @@ impl<'a> Serializer<'a> {
         Ok(())
     }
 
+    fn visit_mixin_ref(&mut self, mixin: &SassMixin, span: Span) -> SassResult<()> {
+        if !self.inspect {
+            return Err((
+                format!(
+                    "{} isn't a valid CSS value.",
+                    inspect_mixin_ref(mixin, self.options, span)?
+                ),
+                span,
+            )
+                .into());
+        }
+
+        self.buffer.extend_from_slice(b"get-mixin(");
+        self.visit_quoted_string(false, mixin.name.as_str());
+        self.buffer.push(b')');
+
+        Ok(())
+    }
+
     fn visit_arglist(&mut self, arglist: &ArgList, span: Span) -> SassResult<()> {
         self.visit_list(&arglist.elems, ListSeparator::Comma, Brackets::None, span)
     }
@@ -953,6 +1256,7 @@ impl<'a> Serializer<'a> {
             }
             Value::Map(map) => self.visit_map(map, span)?,
             Value::FunctionRef(func) => self.visit_function_ref(func, span)?,
+            Value::MixinRef(mixin) => self.visit_mixin_ref(mixin, span)?,
             Value::String(s, QuoteKind::Quoted) => self.visit_quoted_string(false, s),
             Value::String(s, QuoteKind::None) => self.visit_unquoted_string(s),
             Value::ArgList(arglist) => self.visit_arglist(arglist, span)?,
@@ -1029,57 +1333,81 @@ impl<'a> Serializer<'a> {
         }
     }
 
-    fn write_children(&mut self, mut children: Vec<CssStmt>) -> SassResult<()> {
+    fn write_children(&mut self, children: Vec<CssStmt>) -> SassResult<()> {
         if self.options.is_compressed() {
             self.buffer.push(b'{');
         } else {
-            self.buffer.extend_from_slice(b" {\n");
+            self.buffer.extend_from_slice(b" {");
         }
 
         self.indentation += self.indent_width;
 
-        let last = children.pop();
+        let mut is_first = true;
+        let mut prev_requires_semicolon = false;
 
         for child in children {
-            let needs_semicolon = Self::requires_semicolon(&child);
-            let did_write = self.visit_stmt(child)?;
-
-            if !did_write {
+            if child.is_invisible() {
                 continue;
             }
 
-            if needs_semicolon {
+            if prev_requires_semicolon {
                 self.buffer.push(b';');
             }
 
-            self.write_optional_newline();
-        }
+            self.write_child_separator(&child, is_first);
 
-        if let Some(last) = last {
-            let needs_semicolon = Self::requires_semicolon(&last);
-            let did_write = self.visit_stmt(last)?;
+            prev_requires_semicolon = Self::requires_semicolon(&child);
+            is_first = false;
 
-            if did_write {
-                if needs_semicolon && !self.options.is_compressed() {
-                    self.buffer.push(b';');
-                }
+            self.visit_stmt(child)?;
+        }
 
-                self.write_optional_newline();
-            }
+        if prev_requires_semicolon && !self.options.is_compressed() {
+            self.buffer.push(b';');
         }
 
         self.indentation -= self.indent_width;
 
-        if self.options.is_compressed() {
-            self.buffer.push(b'}');
-        } else {
-            self.write_indentation();
-            self.buffer.extend_from_slice(b"}");
+        match self.options.style {
+            OutputStyle::Expanded => {
+                self.buffer.push(b'\n');
+                self.write_indentation();
+                self.buffer.push(b'}');
+            }
+            OutputStyle::Nested | OutputStyle::Compact => self.buffer.extend_from_slice(b" }"),
+            OutputStyle::Compressed => self.buffer.push(b'}'),
         }
 
         Ok(())
     }
 
+    /// Writes the whitespace preceding `child` within a block
+    fn write_child_separator(&mut self, child: &CssStmt, is_first: bool) {
+        match self.options.style {
+            OutputStyle::Expanded | OutputStyle::Nested => self.buffer.push(b'\n'),
+            // Blocks other than the first are written on their own line, while
+            // declarations are kept on the same line as their parent
+            OutputStyle::Compact => {
+                let is_block = match child {
+                    CssStmt::RuleSet { .. }
+                    | CssStmt::Media(..)
+                    | CssStmt::Supports(..)
+                    | CssStmt::KeyframesRuleSet(..) => true,
+                    CssStmt::UnknownAtRule(at_rule, ..) => at_rule.has_body,
+                    CssStmt::Style(..) | CssStmt::Comment(..) | CssStmt::Import(..) => false,
+                };
+
+                if is_block && !is_first {
+                    self.buffer.push(b'\n');
+                    self.write_indentation_unconditionally();
+                } else {
+                    self.buffer.push(b' ');
+                }
+            }
+            OutputStyle::Compressed => {}
+        }
+    }
+
     fn write_optional_space(&mut self) {
         if !self.options.is_compressed() {
             self.buffer.push(b' ');
@@ -1114,11 +1442,21 @@ impl<'a> Serializer<'a> {
         }
 
         match stmt {
-            CssStmt::RuleSet { selector, body, .. } => {
+            CssStmt::RuleSet {
+                selector,
+                body,
+                tabs,
+                ..
+            } => {
+                let indentation = self.indentation;
+                self.indentation += tabs * self.indent_width;
+
                 self.write_indentation();
                 self.write_selector_list(&selector.as_selector_list());
 
                 self.write_children(body)?;
+
+                self.indentation = indentation;
             }
             CssStmt::Media(media_rule, ..) => {
                 self.write_indentation();
@@ -1181,3 +1519,50 @@ impl<'a> Serializer<'a> {
         Ok(true)
     }
 }
+
+/// Whether serializing `value` writes any non-ASCII text
+///
+/// Maps and function references can't be written as CSS at all, so are
+/// reported as errors during serialization instead.
+fn value_writes_non_ascii(value: &Value) -> bool {
+    match value {
+        Value::String(s, ..) => !s.is_ascii(),
+        Value::Dimension(number) => number_writes_non_ascii(number),
+        Value::List(elems, ..) => elems.iter().any(value_writes_non_ascii),
+        Value::ArgList(arglist) => arglist.elems.iter().any(value_writes_non_ascii),
+        Value::Calculation(calculation) => calculation
+            .args
+            .iter()
+            .any(calculation_arg_writes_non_ascii),
+        Value::True
+        | Value::False
+        | Value::Null
+        | Value::Color(..)
+        | Value::Map(..)
+        | Value::FunctionRef(..)
+        | Value::MixinRef(..) => false,
+    }
+}
+
+fn number_writes_non_ascii(number: &SassNumber) -> bool {
+    if let Some(as_slash) = &number.as_slash {
+        return number_writes_non_ascii(&as_slash.0) || number_writes_non_ascii(&as_slash.1);
+    }
+
+    matches!(number.unit, Unit::Unknown(..) | Unit::Complex { .. })
+        && !number.unit.to_string().is_ascii()
+}
+
+fn calculation_arg_writes_non_ascii(arg: &CalculationArg) -> bool {
+    match arg {
+        CalculationArg::Number(number) => number_writes_non_ascii(number),
+        CalculationArg::Calculation(calculation) => calculation
+            .args
+            .iter()
+            .any(calculation_arg_writes_non_ascii),
+        CalculationArg::String(s) | CalculationArg::Interpolation(s) => !s.is_ascii(),
+        CalculationArg::Operation { lhs, rhs, .. } => {
+            calculation_arg_writes_non_ascii(lhs) || calculation_arg_writes_non_ascii(rhs)
+        }
+    }
+}
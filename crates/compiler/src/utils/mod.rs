@@ -20,6 +20,19 @@ pub(crate) fn is_plain_css_import(url: &str) -> bool {
         || lower.starts_with("//")
 }
 
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Hashes `bytes` using FNV-1a, starting from `seed`
+///
+/// Unlike the hashers in `std`, this is stable across platforms and releases,
+/// so may be used to generate output.
+pub(crate) fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(seed, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
 pub(crate) fn opposite_bracket(b: char) -> char {
     debug_assert!(matches!(b, '(' | '{' | '[' | ')' | '}' | ']'));
     match b {
@@ -8,7 +8,7 @@ use crate::{
     error::SassResult,
     serializer::inspect_number,
     unit::Unit,
-    value::{SassNumber, Value},
+    value::{fuzzy_round, Number, SassNumber, Value},
     Options,
 };
 
@@ -43,20 +43,81 @@ pub enum CalculationName {
     Min,
     Max,
     Clamp,
+    Round,
+    Mod,
+    Rem,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Pow,
+    Sqrt,
+    Hypot,
+    Log,
+    Exp,
+    Abs,
+    Sign,
 }
 
 impl fmt::Display for CalculationName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            CalculationName::Calc => f.write_str("calc"),
-            CalculationName::Min => f.write_str("min"),
-            CalculationName::Max => f.write_str("max"),
-            CalculationName::Clamp => f.write_str("clamp"),
-        }
+        f.write_str(self.as_str())
     }
 }
 
 impl CalculationName {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            CalculationName::Calc => "calc",
+            CalculationName::Min => "min",
+            CalculationName::Max => "max",
+            CalculationName::Clamp => "clamp",
+            CalculationName::Round => "round",
+            CalculationName::Mod => "mod",
+            CalculationName::Rem => "rem",
+            CalculationName::Sin => "sin",
+            CalculationName::Cos => "cos",
+            CalculationName::Tan => "tan",
+            CalculationName::Asin => "asin",
+            CalculationName::Acos => "acos",
+            CalculationName::Atan => "atan",
+            CalculationName::Atan2 => "atan2",
+            CalculationName::Pow => "pow",
+            CalculationName::Sqrt => "sqrt",
+            CalculationName::Hypot => "hypot",
+            CalculationName::Log => "log",
+            CalculationName::Exp => "exp",
+            CalculationName::Abs => "abs",
+            CalculationName::Sign => "sign",
+        }
+    }
+
+    /// The math functions whose name isn't shared with a global Sass function,
+    /// along with the number of arguments they accept at most
+    pub(crate) fn from_math_function(name: &str) -> Option<(Self, Option<usize>)> {
+        Some(match name {
+            "mod" => (CalculationName::Mod, Some(2)),
+            "rem" => (CalculationName::Rem, Some(2)),
+            "sin" => (CalculationName::Sin, Some(1)),
+            "cos" => (CalculationName::Cos, Some(1)),
+            "tan" => (CalculationName::Tan, Some(1)),
+            "asin" => (CalculationName::Asin, Some(1)),
+            "acos" => (CalculationName::Acos, Some(1)),
+            "atan" => (CalculationName::Atan, Some(1)),
+            "atan2" => (CalculationName::Atan2, Some(2)),
+            "pow" => (CalculationName::Pow, Some(2)),
+            "sqrt" => (CalculationName::Sqrt, Some(1)),
+            "hypot" => (CalculationName::Hypot, None),
+            "log" => (CalculationName::Log, Some(2)),
+            "exp" => (CalculationName::Exp, Some(1)),
+            "sign" => (CalculationName::Sign, Some(1)),
+            _ => return None,
+        })
+    }
+
     pub(crate) fn in_min_or_max(self) -> bool {
         self == CalculationName::Min || self == CalculationName::Max
     }
@@ -226,7 +287,446 @@ impl SassCalculation {
         }))
     }
 
-    fn verify_length(args: &[CalculationArg], len: usize, span: Span) -> SassResult<()> {
+    pub fn round(
+        mut args: Vec<CalculationArg>,
+        options: &Options,
+        span: Span,
+    ) -> SassResult<Value> {
+        args = Self::simplify_arguments(args);
+
+        if args.len() == 1 {
+            return Ok(match args.remove(0) {
+                CalculationArg::Number(mut number) => {
+                    number.num = Number(fuzzy_round(number.num.0));
+                    Value::Dimension(number)
+                }
+                arg => Value::Calculation(SassCalculation {
+                    name: CalculationName::Round,
+                    args: vec![arg],
+                }),
+            });
+        }
+
+        let strategy = match &args[0] {
+            CalculationArg::String(text) | CalculationArg::Interpolation(text) => {
+                Some(text.as_str())
+            }
+            _ => None,
+        };
+
+        let (strategy, number, step) = match (strategy, args.len()) {
+            (Some("nearest" | "up" | "down" | "to-zero"), 2) => {
+                if matches!(
+                    args[1],
+                    CalculationArg::String(..) | CalculationArg::Interpolation(..)
+                ) {
+                    return Ok(Value::Calculation(SassCalculation {
+                        name: CalculationName::Round,
+                        args,
+                    }));
+                }
+
+                return Err(("If strategy is not null, step is required.", span).into());
+            }
+            (Some(strategy @ ("nearest" | "up" | "down" | "to-zero")), 3) => {
+                (strategy.to_owned(), &args[1], &args[2])
+            }
+            (_, 2) => ("nearest".to_owned(), &args[0], &args[1]),
+            (Some(..), 3) => {
+                return Ok(Value::Calculation(SassCalculation {
+                    name: CalculationName::Round,
+                    args,
+                }))
+            }
+            (None, 3) => {
+                let strategy = match &args[0] {
+                    CalculationArg::Number(n) => inspect_number(n, options, span)?,
+                    _ => {
+                        return Ok(Value::Calculation(SassCalculation {
+                            name: CalculationName::Round,
+                            args,
+                        }))
+                    }
+                };
+
+                return Err((
+                    format!("{} must be either nearest, up, down or to-zero.", strategy),
+                    span,
+                )
+                    .into());
+            }
+            _ => {
+                Self::verify_length(&args, 3, span)?;
+
+                return Ok(Value::Calculation(SassCalculation {
+                    name: CalculationName::Round,
+                    args,
+                }));
+            }
+        };
+
+        match (number, step) {
+            (CalculationArg::Number(number), CalculationArg::Number(step))
+                if number.has_compatible_units(&step.unit) =>
+            {
+                Ok(Value::Dimension(Self::round_with_step(
+                    &strategy,
+                    number.clone(),
+                    step,
+                )))
+            }
+            (number, step) => {
+                Self::verify_compatible_numbers(&[number.clone(), step.clone()], options, span)?;
+
+                Ok(Value::Calculation(SassCalculation {
+                    name: CalculationName::Round,
+                    args,
+                }))
+            }
+        }
+    }
+
+    fn round_with_step(strategy: &str, mut number: SassNumber, step: &SassNumber) -> SassNumber {
+        let value = number.num.0;
+        let step = step.num.convert(&step.unit, &number.unit).0;
+
+        number.num = Number(
+            if (value.is_infinite() && step.is_infinite())
+                || step == 0.0
+                || value.is_nan()
+                || step.is_nan()
+            {
+                f64::NAN
+            } else if value.is_infinite() {
+                value
+            } else if step.is_infinite() {
+                match strategy {
+                    _ if value == 0.0 => value,
+                    "nearest" | "to-zero" if value > 0.0 => 0.0,
+                    "nearest" | "to-zero" => -0.0,
+                    "up" if value > 0.0 => f64::INFINITY,
+                    "up" => -0.0,
+                    "down" if value < 0.0 => f64::NEG_INFINITY,
+                    _ => 0.0,
+                }
+            } else {
+                let quotient = value / step;
+                let rounded = match strategy {
+                    "up" if step < 0.0 => quotient.floor(),
+                    "up" => quotient.ceil(),
+                    "down" if step < 0.0 => quotient.ceil(),
+                    "down" => quotient.floor(),
+                    "to-zero" if value < 0.0 => quotient.ceil(),
+                    "to-zero" => quotient.floor(),
+                    _ => fuzzy_round(quotient),
+                };
+
+                rounded * step
+            },
+        );
+        number.as_slash = None;
+
+        number
+    }
+
+    pub fn modulo(
+        name: CalculationName,
+        mut args: Vec<CalculationArg>,
+        options: &Options,
+        span: Span,
+    ) -> SassResult<Value> {
+        debug_assert!(matches!(name, CalculationName::Mod | CalculationName::Rem));
+
+        args = Self::simplify_arguments(args);
+        Self::verify_length(&args, 2, span)?;
+        Self::verify_compatible_numbers(&args, options, span)?;
+
+        let (dividend, modulus) = match (&args[0], &args[1]) {
+            (CalculationArg::Number(dividend), CalculationArg::Number(modulus))
+                if dividend.has_compatible_units(&modulus.unit) =>
+            {
+                (dividend, modulus)
+            }
+            _ => return Ok(Value::Calculation(SassCalculation { name, args })),
+        };
+
+        let modulus_value = modulus.num.convert(&modulus.unit, &dividend.unit);
+        let mut result = dividend.clone();
+        result.as_slash = None;
+        result.num = dividend.num % modulus_value;
+
+        if name == CalculationName::Rem
+            && modulus_value.0.is_sign_negative() != dividend.num.0.is_sign_negative()
+        {
+            if modulus_value.0.is_infinite() {
+                result.num = dividend.num;
+            } else if result.num.0 == 0.0 {
+                result.num = -result.num;
+            } else {
+                result.num -= modulus_value;
+            }
+        }
+
+        Ok(Value::Dimension(result))
+    }
+
+    pub fn abs(arg: CalculationArg) -> Value {
+        match Self::simplify(arg) {
+            CalculationArg::Number(mut number) => {
+                number.num = number.num.abs();
+                Value::Dimension(number)
+            }
+            arg => Value::Calculation(SassCalculation {
+                name: CalculationName::Abs,
+                args: vec![arg],
+            }),
+        }
+    }
+
+    pub fn sign(arg: CalculationArg) -> Value {
+        match Self::simplify(arg) {
+            CalculationArg::Number(number) if number.num.is_nan() || number.num.is_zero() => {
+                Value::Dimension(number)
+            }
+            CalculationArg::Number(mut number) if number.unit != Unit::Percent => {
+                number.num = Number(number.num.0.signum());
+                Value::Dimension(number)
+            }
+            arg => Value::Calculation(SassCalculation {
+                name: CalculationName::Sign,
+                args: vec![arg],
+            }),
+        }
+    }
+
+    /// `sin()`, `cos()` and `tan()`, which accept either an angle or a unitless
+    /// number of radians
+    pub fn trig(
+        name: CalculationName,
+        arg: CalculationArg,
+        options: &Options,
+        span: Span,
+    ) -> SassResult<Value> {
+        let number = match Self::simplify(arg) {
+            CalculationArg::Number(number) => number,
+            arg => {
+                return Ok(Value::Calculation(SassCalculation {
+                    name,
+                    args: vec![arg],
+                }))
+            }
+        };
+
+        let radians = match number.unit {
+            Unit::None => number.num.0,
+            Unit::Rad | Unit::Deg | Unit::Grad | Unit::Turn => {
+                number.num.convert(&number.unit, &Unit::Rad).0
+            }
+            _ => {
+                return Err((
+                    format!(
+                        "Expected {} to have an angle unit (deg, grad, rad, turn).",
+                        inspect_number(&number, options, span)?
+                    ),
+                    span,
+                )
+                    .into())
+            }
+        };
+
+        Ok(Value::Dimension(SassNumber::new_unitless(match name {
+            CalculationName::Sin => radians.sin(),
+            CalculationName::Cos => radians.cos(),
+            CalculationName::Tan => radians.tan(),
+            _ => unreachable!("invalid trigonometric function {}", name),
+        })))
+    }
+
+    /// The math functions that only accept unitless numbers: `asin()`, `acos()`,
+    /// `atan()`, `sqrt()` and `exp()`
+    pub fn unitless_function(
+        name: CalculationName,
+        arg: CalculationArg,
+        options: &Options,
+        span: Span,
+    ) -> SassResult<Value> {
+        let number = match Self::simplify(arg) {
+            CalculationArg::Number(number) => number,
+            arg => {
+                return Ok(Value::Calculation(SassCalculation {
+                    name,
+                    args: vec![arg],
+                }))
+            }
+        };
+
+        Self::assert_no_units(&number, options, span)?;
+
+        let value = number.num.0;
+
+        Ok(Value::Dimension(match name {
+            CalculationName::Asin => SassNumber {
+                num: Number(value.asin().to_degrees()),
+                unit: Unit::Deg,
+                as_slash: None,
+            },
+            CalculationName::Acos => SassNumber {
+                num: Number(value.acos().to_degrees()),
+                unit: Unit::Deg,
+                as_slash: None,
+            },
+            CalculationName::Atan => SassNumber {
+                num: Number(value.atan().to_degrees()),
+                unit: Unit::Deg,
+                as_slash: None,
+            },
+            CalculationName::Sqrt => SassNumber::new_unitless(value.sqrt()),
+            CalculationName::Exp => SassNumber::new_unitless(value.exp()),
+            _ => unreachable!("invalid unitless function {}", name),
+        }))
+    }
+
+    pub fn atan2(
+        y: CalculationArg,
+        x: CalculationArg,
+        options: &Options,
+        span: Span,
+    ) -> SassResult<Value> {
+        let args = Self::simplify_arguments(vec![y, x]);
+        Self::verify_compatible_numbers(&args, options, span)?;
+
+        match (&args[0], &args[1]) {
+            (CalculationArg::Number(y), CalculationArg::Number(x))
+                if y.unit != Unit::Percent
+                    && x.unit != Unit::Percent
+                    && y.has_compatible_units(&x.unit) =>
+            {
+                let x = x.num.convert(&x.unit, &y.unit);
+
+                Ok(Value::Dimension(SassNumber {
+                    num: Number(y.num.0.atan2(x.0).to_degrees()),
+                    unit: Unit::Deg,
+                    as_slash: None,
+                }))
+            }
+            _ => Ok(Value::Calculation(SassCalculation {
+                name: CalculationName::Atan2,
+                args,
+            })),
+        }
+    }
+
+    pub fn pow(
+        base: CalculationArg,
+        exponent: CalculationArg,
+        options: &Options,
+        span: Span,
+    ) -> SassResult<Value> {
+        let args = Self::simplify_arguments(vec![base, exponent]);
+        Self::verify_compatible_numbers(&args, options, span)?;
+
+        match (&args[0], &args[1]) {
+            (CalculationArg::Number(base), CalculationArg::Number(exponent)) => {
+                Self::assert_no_units(base, options, span)?;
+                Self::assert_no_units(exponent, options, span)?;
+
+                Ok(Value::Dimension(SassNumber::new_unitless(
+                    base.num.0.powf(exponent.num.0),
+                )))
+            }
+            _ => Ok(Value::Calculation(SassCalculation {
+                name: CalculationName::Pow,
+                args,
+            })),
+        }
+    }
+
+    pub fn log(args: Vec<CalculationArg>, options: &Options, span: Span) -> SassResult<Value> {
+        let args = Self::simplify_arguments(args);
+
+        if args
+            .iter()
+            .any(|arg| !matches!(arg, CalculationArg::Number(..)))
+        {
+            return Ok(Value::Calculation(SassCalculation {
+                name: CalculationName::Log,
+                args,
+            }));
+        }
+
+        let mut numbers = args.iter().map(|arg| match arg {
+            CalculationArg::Number(number) => number,
+            _ => unreachable!(),
+        });
+
+        let number = numbers.next().unwrap();
+        Self::assert_no_units(number, options, span)?;
+
+        let value = match numbers.next() {
+            Some(base) => {
+                Self::assert_no_units(base, options, span)?;
+                number.num.0.ln() / base.num.0.ln()
+            }
+            None => number.num.0.ln(),
+        };
+
+        Ok(Value::Dimension(SassNumber::new_unitless(value)))
+    }
+
+    pub fn hypot(args: Vec<CalculationArg>, options: &Options, span: Span) -> SassResult<Value> {
+        let args = Self::simplify_arguments(args);
+        Self::verify_compatible_numbers(&args, options, span)?;
+
+        let first = match &args[0] {
+            CalculationArg::Number(first) if first.unit != Unit::Percent => first,
+            _ => {
+                return Ok(Value::Calculation(SassCalculation {
+                    name: CalculationName::Hypot,
+                    args,
+                }))
+            }
+        };
+
+        let mut subtotal = 0.0;
+
+        for arg in &args {
+            match arg {
+                CalculationArg::Number(number) if number.has_compatible_units(&first.unit) => {
+                    let value = number.num.convert(&number.unit, &first.unit).0;
+                    subtotal += value * value;
+                }
+                _ => {
+                    return Ok(Value::Calculation(SassCalculation {
+                        name: CalculationName::Hypot,
+                        args,
+                    }))
+                }
+            }
+        }
+
+        Ok(Value::Dimension(SassNumber {
+            num: Number(subtotal.sqrt()),
+            unit: first.unit.clone(),
+            as_slash: None,
+        }))
+    }
+
+    fn assert_no_units(number: &SassNumber, options: &Options, span: Span) -> SassResult<()> {
+        if number.unit == Unit::None {
+            return Ok(());
+        }
+
+        Err((
+            format!(
+                "Expected {} to have no units.",
+                inspect_number(number, options, span)?
+            ),
+            span,
+        )
+            .into())
+    }
+
+    pub(crate) fn verify_length(args: &[CalculationArg], len: usize, span: Span) -> SassResult<()> {
         if args.len() == len {
             return Ok(());
         }
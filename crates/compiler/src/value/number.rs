@@ -378,6 +378,14 @@ fn real_mod(n1: f64, n2: f64) -> f64 {
 }
 
 fn modulo(n1: f64, n2: f64) -> f64 {
+    if n1.is_infinite() {
+        return f64::NAN;
+    }
+
+    if n2.is_infinite() {
+        return if n1.signum() == n2.signum() { n1 } else { n2 };
+    }
+
     if n2 > 0.0 {
         return real_mod(n1, n2);
     }
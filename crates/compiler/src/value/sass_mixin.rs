@@ -0,0 +1,47 @@
+use std::{fmt, sync::Arc};
+
+use crate::{ast::Mixin, common::Identifier};
+
+/// A Sass mixin, returned by `meta.get-mixin()`
+///
+/// The mixin name is stored in addition to the body
+/// for use in the builtin function `inspect()`
+#[derive(Clone)]
+pub struct SassMixin {
+    pub(crate) mixin: Mixin,
+    pub name: Identifier,
+}
+
+impl SassMixin {
+    /// Whether the mixin may be passed a content block
+    pub fn accepts_content(&self) -> bool {
+        self.mixin.accepts_content()
+    }
+}
+
+impl PartialEq for SassMixin {
+    fn eq(&self, other: &Self) -> bool {
+        if self.name != other.name {
+            return false;
+        }
+
+        match (&self.mixin, &other.mixin) {
+            (Mixin::UserDefined(mixin1, ..), Mixin::UserDefined(mixin2, ..)) => {
+                Arc::ptr_eq(mixin1, mixin2)
+            }
+            (Mixin::Builtin { .. }, Mixin::Builtin { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for SassMixin {}
+
+impl fmt::Debug for SassMixin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SassMixin")
+            .field("name", &self.name)
+            .field("mixin", &self.mixin)
+            .finish()
+    }
+}
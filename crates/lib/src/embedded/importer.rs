@@ -0,0 +1,440 @@
+//! Resolution of `@use`, `@forward` and `@import` through importers defined by
+//! the host.
+//!
+//! `grass` looks for stylesheets by probing a file system, so each host importer
+//! is exposed to the compiler as a load path beneath a virtual root directory.
+//! When the compiler probes a file beneath that root, the probe is translated
+//! back into the URL the stylesheet used and forwarded to the host.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::OsStr,
+    io::{self, Error, ErrorKind},
+    path::{Component, Path, PathBuf},
+};
+
+use grass_compiler::{Fs, StdFs};
+
+use super::{
+    proto::{decode, Encoder},
+    SharedConnection,
+};
+
+const VIRTUAL_ROOT: &str = "/.grass-embedded-importer";
+
+/// Marks a load path which is resolved without a containing stylesheet
+const LOAD_PATH_MARKER: &str = "~";
+
+const CANONICALIZE_REQUEST: u32 = 4;
+const IMPORT_REQUEST: u32 = 5;
+const FILE_IMPORT_REQUEST: u32 = 6;
+
+const CANONICALIZE_RESPONSE: u32 = 3;
+const IMPORT_RESPONSE: u32 = 4;
+const FILE_IMPORT_RESPONSE: u32 = 5;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum HostImporter {
+    /// An importer which canonicalizes URLs and returns stylesheet contents
+    Importer(u32),
+    /// An importer which redirects URLs to files on disk
+    FileImporter(u32),
+}
+
+#[derive(Debug, Clone)]
+enum Resolved {
+    Disk(PathBuf),
+    /// An index into [`ImporterState::canonical`]
+    Virtual(usize),
+}
+
+#[derive(Debug)]
+struct CanonicalStylesheet {
+    url: String,
+    contents: String,
+    extension: &'static str,
+}
+
+/// A URL to resolve, identified by the index of the importer to resolve it
+/// with and the stylesheet which contains it, if any
+type ResolutionKey = (usize, Option<usize>, String);
+
+#[derive(Debug, Default)]
+struct ImporterState {
+    canonical: Vec<CanonicalStylesheet>,
+    resolved: HashMap<ResolutionKey, Option<(Resolved, &'static str)>>,
+    loaded_urls: Vec<String>,
+}
+
+/// A probe for a stylesheet beneath [`VIRTUAL_ROOT`]
+struct Probe {
+    importer: usize,
+    containing: Option<usize>,
+    url: String,
+    extension: &'static str,
+    /// Whether this probe is for a partial or import-only file, which the
+    /// host importer resolves on its own
+    is_variant: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct ImporterFs {
+    connection: SharedConnection,
+    compilation_id: u32,
+    importers: Vec<HostImporter>,
+    state: RefCell<ImporterState>,
+}
+
+fn extension_for(path: &Path) -> Option<&'static str> {
+    match path.extension().and_then(OsStr::to_str) {
+        Some("scss") => Some("scss"),
+        Some("sass") => Some("sass"),
+        Some("css") => Some("css"),
+        _ => None,
+    }
+}
+
+fn file_url(path: &Path) -> String {
+    format!("file://{}", path.to_string_lossy())
+}
+
+impl ImporterFs {
+    pub fn new(
+        connection: SharedConnection,
+        compilation_id: u32,
+        importers: Vec<HostImporter>,
+    ) -> Self {
+        Self {
+            connection,
+            compilation_id,
+            importers,
+            state: RefCell::new(ImporterState::default()),
+        }
+    }
+
+    /// The load path which causes the compiler to consult the given importer
+    pub fn load_path(index: usize) -> PathBuf {
+        Path::new(VIRTUAL_ROOT)
+            .join(index.to_string())
+            .join(LOAD_PATH_MARKER)
+    }
+
+    /// The canonical URLs of every stylesheet loaded during compilation
+    pub fn loaded_urls(&self) -> Vec<String> {
+        self.state.borrow().loaded_urls.clone()
+    }
+
+    fn canonical_path(importer: usize, canonical: usize, extension: &str) -> PathBuf {
+        Path::new(VIRTUAL_ROOT)
+            .join(importer.to_string())
+            .join(canonical.to_string())
+            .join(format!("canonical.{}", extension))
+    }
+
+    /// If `path` is the location of a stylesheet returned by a host importer,
+    /// the index of that stylesheet
+    fn parse_canonical_path(path: &Path) -> Option<usize> {
+        let rest = path.strip_prefix(VIRTUAL_ROOT).ok()?;
+        let components = rest
+            .components()
+            .map(|c| c.as_os_str().to_str())
+            .collect::<Option<Vec<_>>>()?;
+
+        match components.as_slice() {
+            [_, canonical, file] if file.starts_with("canonical.") => canonical.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_probe(path: &Path) -> Option<Probe> {
+        let rest = path.strip_prefix(VIRTUAL_ROOT).ok()?;
+        let mut components = rest.components().filter_map(|c| match c {
+            Component::Normal(c) => c.to_str(),
+            _ => None,
+        });
+
+        let importer = components.next()?.parse().ok()?;
+        let containing = match components.next()? {
+            LOAD_PATH_MARKER => None,
+            canonical => Some(canonical.parse().ok()?),
+        };
+
+        let mut segments = components.collect::<Vec<_>>();
+        let basename = segments.pop()?;
+        let extension = extension_for(Path::new(basename))?;
+
+        let mut name = &basename[..basename.len() - extension.len() - 1];
+        let mut is_variant = false;
+
+        if let Some(stripped) = name.strip_suffix(".import") {
+            name = stripped;
+            is_variant = true;
+        }
+
+        if let Some(stripped) = name.strip_prefix('_') {
+            name = stripped;
+            is_variant = true;
+        }
+
+        segments.push(name);
+
+        Some(Probe {
+            importer,
+            containing,
+            url: segments.join("/"),
+            extension,
+            is_variant,
+        })
+    }
+
+    fn request(&self, field: u32, response_field: u32, message: Encoder) -> io::Result<Vec<u8>> {
+        self.connection
+            .lock()
+            .unwrap()
+            .request(self.compilation_id, field, response_field, message)
+    }
+
+    /// Ask the host to resolve `url`, returning where the stylesheet lives
+    /// along with its syntax
+    fn resolve(&self, probe: &Probe) -> io::Result<Option<(Resolved, &'static str)>> {
+        let key = (probe.importer, probe.containing, probe.url.clone());
+
+        if let Some(resolved) = self.state.borrow().resolved.get(&key) {
+            return Ok(resolved.clone());
+        }
+
+        let containing_url = probe.containing.and_then(|idx| {
+            self.state
+                .borrow()
+                .canonical
+                .get(idx)
+                .map(|stylesheet| stylesheet.url.clone())
+        });
+
+        let resolved = match self.importers.get(probe.importer) {
+            Some(&HostImporter::FileImporter(importer_id)) => {
+                self.resolve_file(importer_id, &probe.url, containing_url.as_deref())?
+            }
+            Some(&HostImporter::Importer(importer_id)) => {
+                self.resolve_stylesheet(importer_id, &probe.url, containing_url.as_deref())?
+            }
+            None => None,
+        };
+
+        self.state
+            .borrow_mut()
+            .resolved
+            .insert(key, resolved.clone());
+
+        Ok(resolved)
+    }
+
+    fn resolve_file(
+        &self,
+        importer_id: u32,
+        url: &str,
+        containing_url: Option<&str>,
+    ) -> io::Result<Option<(Resolved, &'static str)>> {
+        let id = self.connection.lock().unwrap().next_request_id();
+
+        let mut request = Encoder::new();
+        request
+            .varint(1, u64::from(id))
+            .varint(3, u64::from(importer_id))
+            .string(4, url)
+            .bool(5, false);
+        if let Some(containing_url) = containing_url {
+            request.string(6, containing_url);
+        }
+
+        let response = self.request(FILE_IMPORT_REQUEST, FILE_IMPORT_RESPONSE, request)?;
+
+        for (field, value) in decode(&response)? {
+            if field == 2 {
+                let url = value.as_string()?;
+                let path = match url.strip_prefix("file://") {
+                    Some(path) => PathBuf::from(path),
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "FileImportResponse.file_url must be a file: URL, was \"{}\"",
+                                url
+                            ),
+                        ))
+                    }
+                };
+
+                return Ok(extension_for(&path)
+                    .filter(|_| StdFs.is_file(&path))
+                    .map(|extension| (Resolved::Disk(path), extension)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn resolve_stylesheet(
+        &self,
+        importer_id: u32,
+        url: &str,
+        containing_url: Option<&str>,
+    ) -> io::Result<Option<(Resolved, &'static str)>> {
+        let id = self.connection.lock().unwrap().next_request_id();
+
+        let mut request = Encoder::new();
+        request
+            .varint(1, u64::from(id))
+            .varint(3, u64::from(importer_id))
+            .string(4, url)
+            .bool(5, false);
+        if let Some(containing_url) = containing_url {
+            request.string(6, containing_url);
+        }
+
+        let response = self.request(CANONICALIZE_REQUEST, CANONICALIZE_RESPONSE, request)?;
+
+        let mut canonical_url = None;
+        for (field, value) in decode(&response)? {
+            if field == 2 {
+                canonical_url = Some(value.as_string()?);
+            }
+        }
+
+        let canonical_url = match canonical_url {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+
+        if let Some((idx, stylesheet)) = self
+            .state
+            .borrow()
+            .canonical
+            .iter()
+            .enumerate()
+            .find(|(_, stylesheet)| stylesheet.url == canonical_url)
+        {
+            return Ok(Some((Resolved::Virtual(idx), stylesheet.extension)));
+        }
+
+        let id = self.connection.lock().unwrap().next_request_id();
+
+        let mut request = Encoder::new();
+        request
+            .varint(1, u64::from(id))
+            .varint(3, u64::from(importer_id))
+            .string(4, &canonical_url);
+
+        let response = self.request(IMPORT_REQUEST, IMPORT_RESPONSE, request)?;
+
+        for (field, value) in decode(&response)? {
+            if field != 2 {
+                continue;
+            }
+
+            let mut contents = String::new();
+            let mut extension = "scss";
+
+            for (field, value) in decode(value.as_bytes())? {
+                match field {
+                    1 => contents = value.as_string()?,
+                    2 => {
+                        extension = match value.as_u64() {
+                            1 => "sass",
+                            2 => "css",
+                            _ => "scss",
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            let mut state = self.state.borrow_mut();
+            state.canonical.push(CanonicalStylesheet {
+                url: canonical_url,
+                contents,
+                extension,
+            });
+
+            return Ok(Some((
+                Resolved::Virtual(state.canonical.len() - 1),
+                extension,
+            )));
+        }
+
+        Ok(None)
+    }
+
+    fn resolve_probe(&self, path: &Path) -> Option<(Resolved, &'static str, usize)> {
+        let probe = Self::parse_probe(path)?;
+
+        if probe.is_variant {
+            return None;
+        }
+
+        match self.resolve(&probe) {
+            Ok(Some((resolved, extension))) if extension == probe.extension => {
+                Some((resolved, extension, probe.importer))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Fs for ImporterFs {
+    fn is_dir(&self, path: &Path) -> bool {
+        !path.starts_with(VIRTUAL_ROOT) && StdFs.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        if !path.starts_with(VIRTUAL_ROOT) {
+            return StdFs.is_file(path);
+        }
+
+        Self::parse_canonical_path(path).is_some() || self.resolve_probe(path).is_some()
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        if !path.starts_with(VIRTUAL_ROOT) {
+            let contents = StdFs.read(path)?;
+            let canonical = StdFs
+                .canonicalize(path)
+                .unwrap_or_else(|_| path.to_path_buf());
+            self.state
+                .borrow_mut()
+                .loaded_urls
+                .push(file_url(&canonical));
+            return Ok(contents);
+        }
+
+        let mut state = self.state.borrow_mut();
+        let stylesheet = Self::parse_canonical_path(path)
+            .and_then(|idx| state.canonical.get(idx))
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "stylesheet not found"))?;
+
+        let contents = stylesheet.contents.clone().into_bytes();
+        let url = stylesheet.url.clone();
+        state.loaded_urls.push(url);
+
+        Ok(contents)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if !path.starts_with(VIRTUAL_ROOT) {
+            return StdFs.canonicalize(path);
+        }
+
+        if Self::parse_canonical_path(path).is_some() {
+            return Ok(path.to_path_buf());
+        }
+
+        match self.resolve_probe(path) {
+            Some((Resolved::Disk(path), ..)) => StdFs.canonicalize(&path),
+            Some((Resolved::Virtual(idx), extension, importer)) => {
+                Ok(Self::canonical_path(importer, idx, extension))
+            }
+            None => Ok(path.to_path_buf()),
+        }
+    }
+}
@@ -0,0 +1,716 @@
+//! An implementation of the host side of the [embedded Sass protocol][protocol],
+//! allowing `grass` to be driven by embedded Sass hosts such as the `sass-embedded`
+//! npm package.
+//!
+//! Compilation requests are processed one at a time, in the order they are
+//! received. Warnings and `@debug` output are written to stderr rather than
+//! being sent to the host as log events.
+//!
+//! [protocol]: https://github.com/sass/sass/blob/main/spec/embedded-protocol.md
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, stdin, stdout, Error, ErrorKind, Read, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use grass_compiler::{
+    codemap::{LineCol, SpanLoc},
+    from_path, from_string_with_path,
+    sass_value::{ArgumentResult, Value},
+    Builtin, Deprecation, ErrorKind as SassErrorKind, InputSyntax, Options, OutputStyle,
+    Result as SassResult, Visitor,
+};
+
+use self::{
+    importer::{HostImporter, ImporterFs},
+    proto::{decode, read_packet, write_packet, Encoder},
+    value::{decode_value, encode_value},
+};
+
+mod importer;
+mod proto;
+mod value;
+
+/// The version of the embedded protocol implemented by this module
+const PROTOCOL_VERSION: &str = "3.0.0";
+
+/// The exit code used when the host violates the protocol
+const EX_PROTOCOL: i32 = 76;
+
+/// The compilation id used for messages not associated with any compilation
+const NO_COMPILATION: u32 = 0xffff_ffff;
+
+// Fields of `InboundMessage`
+const COMPILE_REQUEST: u32 = 2;
+const FUNCTION_CALL_RESPONSE: u32 = 6;
+const VERSION_REQUEST: u32 = 7;
+
+// Fields of `OutboundMessage`
+const PROTOCOL_ERROR: u32 = 1;
+const COMPILE_RESPONSE: u32 = 2;
+const FUNCTION_CALL_REQUEST: u32 = 7;
+const VERSION_RESPONSE: u32 = 8;
+
+pub(crate) type SharedConnection = Arc<Mutex<Connection>>;
+
+/// The channel over which `grass` communicates with the host
+pub(crate) struct Connection {
+    reader: Box<dyn Read + Send>,
+    writer: Box<dyn Write + Send>,
+    /// Messages which arrived while waiting for the response to a request
+    queued: VecDeque<(u32, Vec<u8>)>,
+    next_request_id: u32,
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("queued", &self.queued.len())
+            .field("next_request_id", &self.next_request_id)
+            .finish()
+    }
+}
+
+impl Connection {
+    fn new(reader: Box<dyn Read + Send>, writer: Box<dyn Write + Send>) -> SharedConnection {
+        Arc::new(Mutex::new(Self {
+            reader,
+            writer,
+            queued: VecDeque::new(),
+            next_request_id: 0,
+        }))
+    }
+
+    fn send(&mut self, compilation_id: u32, field: u32, message: Encoder) -> io::Result<()> {
+        let mut outbound = Encoder::new();
+        outbound.message(field, message);
+        write_packet(&mut self.writer, compilation_id, &outbound.into_bytes())
+    }
+
+    fn receive(&mut self) -> io::Result<Option<(u32, Vec<u8>)>> {
+        match self.queued.pop_front() {
+            Some(packet) => Ok(Some(packet)),
+            None => read_packet(&mut self.reader),
+        }
+    }
+
+    pub fn next_request_id(&mut self) -> u32 {
+        let id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
+        id
+    }
+
+    /// Send a request to the host and wait for its response, returning the
+    /// encoded response message
+    pub fn request(
+        &mut self,
+        compilation_id: u32,
+        field: u32,
+        response_field: u32,
+        message: Encoder,
+    ) -> io::Result<Vec<u8>> {
+        self.send(compilation_id, field, message)?;
+
+        loop {
+            let (id, packet) = read_packet(&mut self.reader)?.ok_or_else(|| {
+                Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "The host closed the connection while a request was outstanding.",
+                )
+            })?;
+
+            if id == compilation_id {
+                if let Some(&(field, value)) = decode(&packet)?.first() {
+                    if field == response_field {
+                        return Ok(value.as_bytes().to_vec());
+                    }
+                }
+            }
+
+            self.queued.push_back((id, packet));
+        }
+    }
+}
+
+/// Speak the embedded protocol over stdin and stdout until the host closes
+/// the connection
+pub(crate) fn run() -> io::Result<()> {
+    let connection = Connection::new(Box::new(stdin()), Box::new(stdout()));
+
+    if let Err(e) = serve(&connection) {
+        // error type: 0 = PARSE, 1 = PARAMS, 2 = INTERNAL
+        let kind = match e.kind() {
+            ErrorKind::InvalidData | ErrorKind::UnexpectedEof => 0,
+            ErrorKind::InvalidInput => 1,
+            _ => 2,
+        };
+
+        let mut error = Encoder::new();
+        error
+            .varint(1, kind)
+            .varint(2, u64::from(NO_COMPILATION))
+            .string(3, &e.to_string());
+
+        connection
+            .lock()
+            .unwrap()
+            .send(NO_COMPILATION, PROTOCOL_ERROR, error)?;
+
+        eprintln!("Host caused error: {}", e);
+        std::process::exit(EX_PROTOCOL);
+    }
+
+    Ok(())
+}
+
+/// Handle messages from the host until it closes the connection, returning
+/// the first protocol error encountered
+fn serve(connection: &SharedConnection) -> io::Result<()> {
+    loop {
+        let packet = connection.lock().unwrap().receive()?;
+
+        match packet {
+            Some((compilation_id, message)) => {
+                handle_message(connection, compilation_id, &message)?;
+            }
+            None => return Ok(()),
+        }
+    }
+}
+
+fn handle_message(
+    connection: &SharedConnection,
+    compilation_id: u32,
+    message: &[u8],
+) -> io::Result<()> {
+    match decode(message)?.first() {
+        Some(&(COMPILE_REQUEST, request)) => {
+            compile(connection, compilation_id, request.as_bytes())
+        }
+        Some(&(VERSION_REQUEST, request)) => {
+            let id = decode(request.as_bytes())?
+                .into_iter()
+                .find(|&(field, _)| field == 1)
+                .map_or(0, |(_, id)| id.as_u64());
+
+            let mut response = Encoder::new();
+            response
+                .string(1, PROTOCOL_VERSION)
+                .string(2, env!("CARGO_PKG_VERSION"))
+                .string(3, env!("CARGO_PKG_VERSION"))
+                .string(4, "grass")
+                .varint(5, id);
+
+            connection
+                .lock()
+                .unwrap()
+                .send(0, VERSION_RESPONSE, response)
+        }
+        Some(&(3..=6, ..)) => Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Response doesn't match any outstanding request.",
+        )),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            "InboundMessage.message is not set.",
+        )),
+    }
+}
+
+enum Importer {
+    Path(PathBuf),
+    Host(HostImporter),
+}
+
+fn decode_importer(buf: &[u8]) -> io::Result<Option<Importer>> {
+    for (field, value) in decode(buf)? {
+        match field {
+            1 => return Ok(Some(Importer::Path(PathBuf::from(value.as_string()?)))),
+            2 => return Ok(Some(Importer::Host(HostImporter::Importer(value.as_u32())))),
+            3 => {
+                return Ok(Some(Importer::Host(HostImporter::FileImporter(
+                    value.as_u32(),
+                ))))
+            }
+            _ => {}
+        }
+    }
+
+    Ok(None)
+}
+
+enum Input {
+    Path(String),
+    String {
+        source: String,
+        url: String,
+        syntax: InputSyntax,
+    },
+}
+
+#[derive(Default)]
+struct CompileRequest {
+    input: Option<Input>,
+    compressed: bool,
+    importers: Vec<Importer>,
+    global_functions: Vec<String>,
+    alert_ascii: bool,
+    verbose: bool,
+    quiet_deps: bool,
+    charset: bool,
+    silent: bool,
+    fatal_deprecations: Vec<Deprecation>,
+    silenced_deprecations: Vec<Deprecation>,
+}
+
+impl CompileRequest {
+    fn decode(buf: &[u8]) -> io::Result<Self> {
+        let mut request = Self::default();
+
+        for (field, value) in decode(buf)? {
+            match field {
+                2 => {
+                    let mut source = String::new();
+                    let mut url = String::new();
+                    let mut syntax = InputSyntax::Scss;
+
+                    for (field, value) in decode(value.as_bytes())? {
+                        match field {
+                            1 => source = value.as_string()?,
+                            2 => url = value.as_string()?,
+                            3 => {
+                                syntax = match value.as_u64() {
+                                    1 => InputSyntax::Sass,
+                                    2 => InputSyntax::Css,
+                                    _ => InputSyntax::Scss,
+                                }
+                            }
+                            // relative loads from the entrypoint go through its
+                            // importer before any others
+                            4 => {
+                                if let Some(importer) = decode_importer(value.as_bytes())? {
+                                    request.importers.insert(0, importer);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    request.input = Some(Input::String {
+                        source,
+                        url,
+                        syntax,
+                    });
+                }
+                3 => request.input = Some(Input::Path(value.as_string()?)),
+                4 => request.compressed = value.as_u64() == 1,
+                6 => {
+                    if let Some(importer) = decode_importer(value.as_bytes())? {
+                        request.importers.push(importer);
+                    }
+                }
+                7 => request.global_functions.push(value.as_string()?),
+                9 => request.alert_ascii = value.as_bool(),
+                10 => request.verbose = value.as_bool(),
+                11 => request.quiet_deps = value.as_bool(),
+                13 => request.charset = value.as_bool(),
+                14 => request.silent = value.as_bool(),
+                15 => request
+                    .fatal_deprecations
+                    .extend(Deprecation::from_id(&value.as_string()?)),
+                16 => request
+                    .silenced_deprecations
+                    .extend(Deprecation::from_id(&value.as_string()?)),
+                _ => {}
+            }
+        }
+
+        Ok(request)
+    }
+}
+
+/// Encode the location of a span as a protocol `SourceSpan`
+fn encode_span(loc: &SpanLoc) -> Encoder {
+    let file = &loc.file;
+
+    let offset = |position: LineCol| {
+        (file.line_span(position.line).low() - file.span.low()) as usize + position.column
+    };
+
+    let location = |position: LineCol| {
+        let mut location = Encoder::new();
+        location
+            .varint(1, offset(position) as u64)
+            .varint(2, position.line as u64)
+            .varint(3, position.column as u64);
+        location
+    };
+
+    let text = file
+        .source()
+        .get(offset(loc.begin)..offset(loc.end))
+        .unwrap_or("");
+
+    let mut span = Encoder::new();
+    span.string(1, text)
+        .message(2, location(loc.begin))
+        .message(3, location(loc.end))
+        .string(4, file.name())
+        .string(5, file.source_line(loc.begin.line));
+    span
+}
+
+/// Create the body of a builtin which forwards calls to a function defined by
+/// the host
+fn host_function(
+    connection: &SharedConnection,
+    compilation_id: u32,
+    name: String,
+) -> impl Fn(ArgumentResult, &mut Visitor) -> SassResult<Value> + Send + Sync + 'static {
+    let connection = Arc::clone(connection);
+
+    move |mut args, _| {
+        let span = args.span();
+        let protocol_error = |e: io::Error| (e.to_string(), span);
+
+        let mut arguments = Vec::new();
+        while let Some(arg) = args.get_positional(arguments.len()) {
+            arguments.push(encode_value(&arg.node, span)?);
+        }
+
+        let mut connection = connection.lock().unwrap();
+
+        let mut request = Encoder::new();
+        request
+            .varint(1, u64::from(connection.next_request_id()))
+            .string(2, &name);
+        for argument in arguments {
+            request.message(4, argument);
+        }
+
+        let response = connection
+            .request(
+                compilation_id,
+                FUNCTION_CALL_REQUEST,
+                FUNCTION_CALL_RESPONSE,
+                request,
+            )
+            .map_err(protocol_error)?;
+
+        drop(connection);
+
+        for (field, value) in decode(&response).map_err(protocol_error)? {
+            match field {
+                2 => return decode_value(value.as_bytes(), span).map_err(protocol_error)?,
+                3 => return Err((value.as_string().map_err(protocol_error)?, span).into()),
+                _ => {}
+            }
+        }
+
+        Err(("FunctionCallResponse.result is not set.", span).into())
+    }
+}
+
+fn compile(connection: &SharedConnection, compilation_id: u32, request: &[u8]) -> io::Result<()> {
+    let request = CompileRequest::decode(request)?;
+
+    let mut load_paths = Vec::new();
+    let mut host_importers = Vec::new();
+
+    for importer in request.importers {
+        match importer {
+            Importer::Path(path) => load_paths.push(path),
+            Importer::Host(importer) => {
+                load_paths.push(ImporterFs::load_path(host_importers.len()));
+                host_importers.push(importer);
+            }
+        }
+    }
+
+    let fs = ImporterFs::new(Arc::clone(connection), compilation_id, host_importers);
+
+    let mut options = Options::default()
+        .fs(&fs)
+        .load_paths(&load_paths)
+        .style(if request.compressed {
+            OutputStyle::Compressed
+        } else {
+            OutputStyle::Expanded
+        })
+        .quiet(request.silent)
+        .quiet_deps(request.quiet_deps)
+        .verbose(request.verbose)
+        .fatal_deprecations(&request.fatal_deprecations)
+        .silence_deprecations(&request.silenced_deprecations)
+        .unicode_error_messages(!request.alert_ascii)
+        .allows_charset(request.charset);
+
+    for signature in request.global_functions {
+        let (name, arguments) = match signature.find('(') {
+            Some(idx) => signature.split_at(idx),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid function signature \"{}\".", signature),
+                ))
+            }
+        };
+
+        let name = name.trim().to_owned();
+        let builtin = Builtin::with_signature(
+            arguments,
+            host_function(connection, compilation_id, name.clone()),
+        );
+
+        options = options.add_custom_fn(name, builtin);
+    }
+
+    let result = match request.input {
+        Some(Input::Path(path)) => from_path(path, &options),
+        Some(Input::String {
+            source,
+            url,
+            syntax,
+        }) => {
+            let path = match url.strip_prefix("file://") {
+                Some(path) => path.to_owned(),
+                None if url.is_empty() => "-".to_owned(),
+                None => url,
+            };
+
+            from_string_with_path(source, path, &options.input_syntax(syntax))
+        }
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "CompileRequest.input is not set.",
+            ))
+        }
+    };
+
+    let mut response = Encoder::new();
+
+    match result {
+        Ok(css) => {
+            let mut success = Encoder::new();
+            success.string(1, css.trim_end());
+            response.message(2, success);
+        }
+        Err(e) => {
+            let formatted = e.to_string();
+
+            let mut failure = Encoder::new();
+            match (*e).clone().kind() {
+                SassErrorKind::ParseError { message, loc, .. } => {
+                    failure.string(1, &message).message(2, encode_span(&loc));
+                }
+                SassErrorKind::IoError(e) => {
+                    failure.string(1, &e.to_string());
+                }
+                SassErrorKind::FromUtf8Error(message) => {
+                    failure.string(1, &message);
+                }
+                _ => {
+                    failure.string(1, formatted.trim_end());
+                }
+            }
+            failure.string(4, formatted.trim_end());
+
+            response.message(3, failure);
+        }
+    }
+
+    for url in fs.loaded_urls() {
+        response.string(4, &url);
+    }
+
+    connection
+        .lock()
+        .unwrap()
+        .send(compilation_id, COMPILE_RESPONSE, response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// A writer whose contents can be inspected once the connection is done
+    /// with it
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn inbound(packets: &mut Vec<u8>, compilation_id: u32, field: u32, message: Encoder) {
+        let mut inbound = Encoder::new();
+        inbound.message(field, message);
+        write_packet(packets, compilation_id, &inbound.into_bytes()).unwrap();
+    }
+
+    /// Runs a session in which the host sends `packets`, returning the
+    /// compilation id, field and message of each packet sent to the host
+    fn session(packets: Vec<u8>) -> io::Result<Vec<(u32, u32, Vec<u8>)>> {
+        let output = SharedBuffer::default();
+        let connection = Connection::new(Box::new(Cursor::new(packets)), Box::new(output.clone()));
+
+        let result = serve(&connection);
+
+        let output = output.0.lock().unwrap().clone();
+        let mut reader = Cursor::new(output);
+        let mut outbound = Vec::new();
+
+        while let Some((compilation_id, packet)) = read_packet(&mut reader).unwrap() {
+            let (field, message) = decode(&packet).unwrap()[0];
+            outbound.push((compilation_id, field, message.as_bytes().to_vec()));
+        }
+
+        result.map(|()| outbound)
+    }
+
+    fn field<'a>(message: &'a [u8], number: u32) -> Option<proto::Field<'a>> {
+        decode(message)
+            .unwrap()
+            .into_iter()
+            .find(|&(field, _)| field == number)
+            .map(|(_, value)| value)
+    }
+
+    fn string_field(message: &[u8], number: u32) -> String {
+        field(message, number).unwrap().as_string().unwrap()
+    }
+
+    #[test]
+    fn scripted_session() {
+        let mut packets = Vec::new();
+
+        let mut version_request = Encoder::new();
+        version_request.varint(1, 3);
+        inbound(&mut packets, 0, VERSION_REQUEST, version_request);
+
+        let mut input = Encoder::new();
+        input
+            .string(1, "@use \"lib\";\na {\n  b: double(2px);\n}")
+            .string(2, "")
+            .varint(3, 0);
+        let mut importer = Encoder::new();
+        importer.varint(2, 5);
+        let mut compile_request = Encoder::new();
+        compile_request
+            .message(2, input)
+            .varint(4, 0)
+            .message(6, importer)
+            .string(7, "double($n)");
+        inbound(&mut packets, 1, COMPILE_REQUEST, compile_request);
+
+        let mut canonicalize_response = Encoder::new();
+        canonicalize_response.varint(1, 0).string(2, "custom:lib");
+        inbound(&mut packets, 1, 3, canonicalize_response);
+
+        let mut stylesheet = Encoder::new();
+        stylesheet.string(1, "c {\n  d: e;\n}").varint(2, 0);
+        let mut import_response = Encoder::new();
+        import_response.varint(1, 1).message(2, stylesheet);
+        inbound(&mut packets, 1, 4, import_response);
+
+        let mut number = Encoder::new();
+        number.double(1, 4.0).string(2, "px");
+        let mut value = Encoder::new();
+        value.message(2, number);
+        let mut function_call_response = Encoder::new();
+        function_call_response.varint(1, 2).message(2, value);
+        inbound(
+            &mut packets,
+            1,
+            FUNCTION_CALL_RESPONSE,
+            function_call_response,
+        );
+
+        let outbound = session(packets).unwrap();
+
+        assert_eq!(
+            outbound
+                .iter()
+                .map(|(compilation_id, field, _)| (*compilation_id, *field))
+                .collect::<Vec<_>>(),
+            [
+                (0, VERSION_RESPONSE),
+                (1, 4),
+                (1, 5),
+                (1, FUNCTION_CALL_REQUEST),
+                (1, COMPILE_RESPONSE),
+            ]
+        );
+
+        let version_response = &outbound[0].2;
+        assert_eq!(string_field(version_response, 1), PROTOCOL_VERSION);
+        assert_eq!(string_field(version_response, 4), "grass");
+        assert_eq!(field(version_response, 5).unwrap().as_u64(), 3);
+
+        let canonicalize_request = &outbound[1].2;
+        assert_eq!(field(canonicalize_request, 3).unwrap().as_u32(), 5);
+        assert_eq!(string_field(canonicalize_request, 4), "lib");
+
+        let import_request = &outbound[2].2;
+        assert_eq!(field(import_request, 3).unwrap().as_u32(), 5);
+        assert_eq!(string_field(import_request, 4), "custom:lib");
+
+        let function_call_request = &outbound[3].2;
+        assert_eq!(string_field(function_call_request, 2), "double");
+        let argument = field(function_call_request, 4).unwrap().as_bytes();
+        let number = field(argument, 2).unwrap().as_bytes();
+        assert_eq!(field(number, 1).unwrap().as_f64(), 2.0);
+        assert_eq!(string_field(number, 2), "px");
+
+        let compile_response = &outbound[4].2;
+        let success = field(compile_response, 2).unwrap().as_bytes();
+        assert_eq!(
+            string_field(success, 1),
+            "c {\n  d: e;\n}\n\na {\n  b: 4px;\n}"
+        );
+        assert_eq!(string_field(compile_response, 4), "custom:lib");
+    }
+
+    #[test]
+    fn compile_failure() {
+        let mut packets = Vec::new();
+
+        let mut input = Encoder::new();
+        input.string(1, "a {");
+        let mut compile_request = Encoder::new();
+        compile_request.message(2, input);
+        inbound(&mut packets, 2, COMPILE_REQUEST, compile_request);
+
+        let outbound = session(packets).unwrap();
+
+        assert_eq!(outbound.len(), 1);
+        assert_eq!(outbound[0].1, COMPILE_RESPONSE);
+
+        let failure = field(&outbound[0].2, 3).unwrap().as_bytes();
+        assert_eq!(string_field(failure, 1), "expected \"}\".");
+        assert!(field(failure, 2).is_some());
+    }
+
+    #[test]
+    fn unexpected_response() {
+        let mut packets = Vec::new();
+        inbound(&mut packets, 1, FUNCTION_CALL_RESPONSE, Encoder::new());
+
+        assert_eq!(
+            session(packets).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+}
@@ -0,0 +1,332 @@
+//! A minimal implementation of the protocol buffer wire format, covering only
+//! what is needed to speak the embedded Sass protocol.
+
+use std::io::{self, Error, ErrorKind, Read, Write};
+
+const VARINT: u64 = 0;
+const FIXED64: u64 = 1;
+const LENGTH_DELIMITED: u64 = 2;
+const FIXED32: u64 = 5;
+
+/// A single field read from an encoded message
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Field<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> Field<'a> {
+    pub fn as_u64(self) -> u64 {
+        match self {
+            Field::Varint(v) | Field::Fixed64(v) => v,
+            Field::Fixed32(v) => u64::from(v),
+            Field::Bytes(..) => 0,
+        }
+    }
+
+    pub fn as_u32(self) -> u32 {
+        self.as_u64() as u32
+    }
+
+    pub fn as_bool(self) -> bool {
+        self.as_u64() != 0
+    }
+
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Field::Fixed64(v) => f64::from_bits(v),
+            Field::Fixed32(v) => f64::from(f32::from_bits(v)),
+            Field::Varint(v) => v as f64,
+            Field::Bytes(..) => 0.0,
+        }
+    }
+
+    pub fn as_bytes(self) -> &'a [u8] {
+        match self {
+            Field::Bytes(b) => b,
+            _ => &[],
+        }
+    }
+
+    pub fn as_string(self) -> io::Result<String> {
+        String::from_utf8(self.as_bytes().to_vec())
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "string field was not valid UTF-8"))
+    }
+}
+
+fn truncated() -> Error {
+    Error::new(ErrorKind::InvalidData, "protobuf message was truncated")
+}
+
+fn read_varint(buf: &[u8], cursor: &mut usize) -> io::Result<u64> {
+    let mut value = 0_u64;
+    let mut shift = 0;
+
+    loop {
+        let byte = *buf.get(*cursor).ok_or_else(truncated)?;
+        *cursor += 1;
+
+        if shift >= 64 {
+            return Err(Error::new(ErrorKind::InvalidData, "varint was too long"));
+        }
+
+        value |= u64::from(byte & 0x7f) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+/// Decode every field in `buf`, in the order they appear
+pub(crate) fn decode(buf: &[u8]) -> io::Result<Vec<(u32, Field<'_>)>> {
+    let mut cursor = 0;
+    let mut fields = Vec::new();
+
+    while cursor < buf.len() {
+        let key = read_varint(buf, &mut cursor)?;
+        let number = (key >> 3) as u32;
+
+        let field = match key & 0b111 {
+            VARINT => Field::Varint(read_varint(buf, &mut cursor)?),
+            FIXED64 => {
+                let bytes = buf.get(cursor..cursor + 8).ok_or_else(truncated)?;
+                cursor += 8;
+                Field::Fixed64(u64::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            LENGTH_DELIMITED => {
+                let len = read_varint(buf, &mut cursor)? as usize;
+                let bytes = buf
+                    .get(cursor..cursor.checked_add(len).ok_or_else(truncated)?)
+                    .ok_or_else(truncated)?;
+                cursor += len;
+                Field::Bytes(bytes)
+            }
+            FIXED32 => {
+                let bytes = buf.get(cursor..cursor + 4).ok_or_else(truncated)?;
+                cursor += 4;
+                Field::Fixed32(u32::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            wire_type => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unsupported protobuf wire type {}", wire_type),
+                ))
+            }
+        };
+
+        fields.push((number, field));
+    }
+
+    Ok(fields)
+}
+
+/// Builds up an encoded message one field at a time
+#[derive(Debug, Default)]
+pub(crate) struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    fn key(&mut self, number: u32, wire_type: u64) {
+        self.raw_varint((u64::from(number) << 3) | wire_type);
+    }
+
+    pub fn varint(&mut self, number: u32, value: u64) -> &mut Self {
+        self.key(number, VARINT);
+        self.raw_varint(value);
+        self
+    }
+
+    pub fn bool(&mut self, number: u32, value: bool) -> &mut Self {
+        self.varint(number, u64::from(value))
+    }
+
+    pub fn double(&mut self, number: u32, value: f64) -> &mut Self {
+        self.key(number, FIXED64);
+        self.buf.extend_from_slice(&value.to_bits().to_le_bytes());
+        self
+    }
+
+    pub fn bytes(&mut self, number: u32, value: &[u8]) -> &mut Self {
+        self.key(number, LENGTH_DELIMITED);
+        self.raw_varint(value.len() as u64);
+        self.buf.extend_from_slice(value);
+        self
+    }
+
+    pub fn string(&mut self, number: u32, value: &str) -> &mut Self {
+        self.bytes(number, value.as_bytes())
+    }
+
+    pub fn message(&mut self, number: u32, message: Encoder) -> &mut Self {
+        self.bytes(number, &message.buf)
+    }
+}
+
+/// Read a single packet, returning its compilation id and encoded message, or
+/// `None` if the host has closed its end of the connection
+pub(crate) fn read_packet(reader: &mut impl Read) -> io::Result<Option<(u32, Vec<u8>)>> {
+    let mut length = 0_u64;
+    let mut shift = 0;
+
+    loop {
+        let mut byte = [0];
+        if reader.read(&mut byte)? == 0 {
+            if shift == 0 {
+                return Ok(None);
+            }
+            return Err(truncated());
+        }
+
+        length |= u64::from(byte[0] & 0x7f) << shift;
+        shift += 7;
+
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+
+        if shift >= 64 {
+            return Err(Error::new(ErrorKind::InvalidData, "varint was too long"));
+        }
+    }
+
+    let mut packet = vec![0; length as usize];
+    reader.read_exact(&mut packet)?;
+
+    let mut cursor = 0;
+    let compilation_id = read_varint(&packet, &mut cursor)? as u32;
+
+    Ok(Some((compilation_id, packet.split_off(cursor))))
+}
+
+/// Write a single packet for the given compilation
+pub(crate) fn write_packet(
+    writer: &mut impl Write,
+    compilation_id: u32,
+    message: &[u8],
+) -> io::Result<()> {
+    let mut header = Encoder::new();
+    header.raw_varint(u64::from(compilation_id));
+    let header = header.into_bytes();
+
+    let mut length = Encoder::new();
+    length.raw_varint((header.len() + message.len()) as u64);
+
+    writer.write_all(&length.into_bytes())?;
+    writer.write_all(&header)?;
+    writer.write_all(message)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn message_round_trip() {
+        let mut nested = Encoder::new();
+        nested.string(1, "nested");
+
+        let mut message = Encoder::new();
+        message
+            .varint(1, 300)
+            .varint(2, u64::MAX)
+            .bool(3, true)
+            .double(4, -1.5)
+            .string(5, "ü")
+            .message(6, nested)
+            .bytes(7, &[]);
+        let message = message.into_bytes();
+
+        let fields = decode(&message).unwrap();
+
+        assert_eq!(
+            fields.iter().map(|&(field, _)| field).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5, 6, 7]
+        );
+        assert_eq!(fields[0].1.as_u64(), 300);
+        assert_eq!(fields[1].1.as_u64(), u64::MAX);
+        assert!(fields[2].1.as_bool());
+        assert_eq!(fields[3].1.as_f64(), -1.5);
+        assert_eq!(fields[4].1.as_string().unwrap(), "ü");
+        assert_eq!(
+            decode(fields[5].1.as_bytes()).unwrap()[0]
+                .1
+                .as_string()
+                .unwrap(),
+            "nested"
+        );
+        assert!(fields[6].1.as_bytes().is_empty());
+    }
+
+    #[test]
+    fn decode_truncated_message() {
+        let mut message = Encoder::new();
+        message.string(1, "truncated");
+        let message = message.into_bytes();
+
+        let err = decode(&message[..message.len() - 1]).unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn decode_invalid_utf8() {
+        let mut message = Encoder::new();
+        message.bytes(1, &[0xff]);
+        let message = message.into_bytes();
+
+        assert!(decode(&message).unwrap()[0].1.as_string().is_err());
+    }
+
+    #[test]
+    fn packet_round_trip() {
+        let long = vec![b'a'; 200];
+
+        let mut buf = Vec::new();
+        write_packet(&mut buf, 0, b"first").unwrap();
+        write_packet(&mut buf, 0xffff_ffff, &long).unwrap();
+        write_packet(&mut buf, 12, &[]).unwrap();
+
+        let mut reader = Cursor::new(buf);
+
+        assert_eq!(
+            read_packet(&mut reader).unwrap(),
+            Some((0, b"first".to_vec()))
+        );
+        assert_eq!(read_packet(&mut reader).unwrap(), Some((0xffff_ffff, long)));
+        assert_eq!(read_packet(&mut reader).unwrap(), Some((12, Vec::new())));
+        assert_eq!(read_packet(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn read_truncated_packet() {
+        let mut buf = Vec::new();
+        write_packet(&mut buf, 1, b"message").unwrap();
+        buf.pop();
+
+        assert!(read_packet(&mut Cursor::new(buf)).is_err());
+    }
+}
@@ -0,0 +1,524 @@
+//! Conversion between Sass values and their embedded protocol representation
+
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use grass_compiler::{
+    codemap::{Span, Spanned},
+    sass_value::{
+        ArgList, Brackets, Color, ColorSpace, ListSeparator, Number, QuoteKind, SassMap,
+        SassNumber, Unit, Value,
+    },
+    Result as SassResult,
+};
+
+use super::proto::{decode, Encoder};
+
+const SINGLETON_TRUE: u64 = 0;
+const SINGLETON_FALSE: u64 = 1;
+const SINGLETON_NULL: u64 = 2;
+
+static ARGUMENT_LIST_ID: AtomicU32 = AtomicU32::new(1);
+
+fn encode_separator(separator: ListSeparator) -> u64 {
+    match separator {
+        ListSeparator::Comma => 0,
+        ListSeparator::Space => 1,
+        ListSeparator::Slash => 2,
+        ListSeparator::Undecided => 3,
+    }
+}
+
+fn decode_separator(separator: u64) -> ListSeparator {
+    match separator {
+        0 => ListSeparator::Comma,
+        1 => ListSeparator::Space,
+        2 => ListSeparator::Slash,
+        _ => ListSeparator::Undecided,
+    }
+}
+
+fn units(unit: &Unit) -> (Vec<String>, Vec<String>) {
+    match unit {
+        Unit::None => (Vec::new(), Vec::new()),
+        Unit::Complex(complex) => (
+            complex.numer.iter().map(ToString::to_string).collect(),
+            complex.denom.iter().map(ToString::to_string).collect(),
+        ),
+        unit => (vec![unit.to_string()], Vec::new()),
+    }
+}
+
+/// Encode a Sass value as a protocol `Value` message
+pub(crate) fn encode_value(value: &Value, span: Span) -> SassResult<Encoder> {
+    let mut message = Encoder::new();
+
+    match value {
+        Value::String(text, quotes) => {
+            let mut string = Encoder::new();
+            string
+                .string(1, text)
+                .bool(2, matches!(quotes, QuoteKind::Quoted));
+            message.message(1, string);
+        }
+        Value::Dimension(number) => {
+            let mut encoded = Encoder::new();
+            encoded.double(1, number.num.0);
+
+            let (numerators, denominators) = units(&number.unit);
+            for unit in &numerators {
+                encoded.string(2, unit);
+            }
+            for unit in &denominators {
+                encoded.string(3, unit);
+            }
+
+            message.message(2, encoded);
+        }
+        Value::Color(color) => {
+            let mut encoded = Encoder::new();
+            encoded.string(1, color.space().name());
+
+            // missing channels are represented by unset fields
+            for (field, channel) in (2..).zip(color.channels()) {
+                if let Some(channel) = channel {
+                    encoded.double(field, channel.0);
+                }
+            }
+
+            if !color.is_alpha_missing() {
+                encoded.double(5, color.alpha().0);
+            }
+
+            message.message(3, encoded);
+        }
+        Value::List(elems, separator, brackets) => {
+            let mut list = Encoder::new();
+            list.varint(1, encode_separator(*separator))
+                .bool(2, matches!(brackets, Brackets::Bracketed));
+            for elem in elems {
+                list.message(3, encode_value(elem, span)?);
+            }
+            message.message(5, list);
+        }
+        Value::Map(map) => {
+            let mut encoded = Encoder::new();
+            for (key, value) in map.iter() {
+                let mut entry = Encoder::new();
+                entry
+                    .message(1, encode_value(&key.node, span)?)
+                    .message(2, encode_value(value, span)?);
+                encoded.message(1, entry);
+            }
+            message.message(6, encoded);
+        }
+        Value::True => {
+            message.varint(7, SINGLETON_TRUE);
+        }
+        Value::False => {
+            message.varint(7, SINGLETON_FALSE);
+        }
+        Value::Null => {
+            message.varint(7, SINGLETON_NULL);
+        }
+        Value::ArgList(args) => {
+            let mut list = Encoder::new();
+            list.varint(
+                1,
+                u64::from(ARGUMENT_LIST_ID.fetch_add(1, Ordering::Relaxed)),
+            )
+            .varint(2, encode_separator(args.separator));
+            for elem in &args.elems {
+                list.message(3, encode_value(elem, span)?);
+            }
+            for (name, value) in args.keywords() {
+                let mut entry = Encoder::new();
+                entry
+                    .string(1, &name.to_string())
+                    .message(2, encode_value(value, span)?);
+                list.message(4, entry);
+            }
+            message.message(10, list);
+        }
+        Value::Calculation(..) => {
+            // calculations are passed to the host in their serialized form
+            let mut string = Encoder::new();
+            string
+                .string(1, &value.to_css_string(span, false)?)
+                .bool(2, false);
+            message.message(1, string);
+        }
+        Value::FunctionRef(..) => {
+            return Err((
+                "Function references can't be passed to host functions.",
+                span,
+            )
+                .into())
+        }
+        Value::MixinRef(..) => {
+            return Err(("Mixin references can't be passed to host functions.", span).into())
+        }
+    }
+
+    Ok(message)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+fn decode_number(buf: &[u8]) -> io::Result<SassResult<Value>> {
+    let mut number = SassNumber {
+        num: Number(0.0),
+        unit: Unit::None,
+        as_slash: None,
+    };
+    let mut numerators = Vec::new();
+    let mut denominators = Vec::new();
+
+    for (field, value) in decode(buf)? {
+        match field {
+            1 => number.num = Number(value.as_f64()),
+            2 => numerators.push(value.as_string()?),
+            3 => denominators.push(value.as_string()?),
+            _ => {}
+        }
+    }
+
+    let unit_number = |unit: String| SassNumber {
+        num: Number(1.0),
+        unit: Unit::from(unit),
+        as_slash: None,
+    };
+
+    for unit in numerators {
+        number = number * unit_number(unit);
+    }
+
+    for unit in denominators {
+        number = number / unit_number(unit);
+    }
+
+    Ok(Ok(Value::Dimension(number)))
+}
+
+fn decode_color(buf: &[u8], span: Span) -> io::Result<SassResult<Value>> {
+    let mut space = String::from("rgb");
+    let mut channels = [None; 3];
+    let mut alpha = None;
+
+    for (field, value) in decode(buf)? {
+        match field {
+            1 => space = value.as_string()?,
+            2..=4 => channels[field as usize - 2] = Some(Number(value.as_f64())),
+            5 => alpha = Some(Number(value.as_f64())),
+            _ => {}
+        }
+    }
+
+    let space = match ColorSpace::from_name(&space) {
+        Some(space) => space,
+        None => {
+            return Ok(Err((
+                format!("The color space {} is not supported.", space),
+                span,
+            )
+                .into()))
+        }
+    };
+
+    Ok(Ok(Value::Color(Arc::new(Color::for_space(
+        space, channels, alpha,
+    )))))
+}
+
+fn decode_list(
+    buf: &[u8],
+    separator_field: u32,
+    contents_field: u32,
+    span: Span,
+) -> io::Result<SassResult<(Vec<Value>, ListSeparator, bool)>> {
+    let mut elems = Vec::new();
+    let mut separator = ListSeparator::Comma;
+    let mut brackets = false;
+
+    for (field, value) in decode(buf)? {
+        if field == separator_field {
+            separator = decode_separator(value.as_u64());
+        } else if field == contents_field {
+            match decode_value(value.as_bytes(), span)? {
+                Ok(value) => elems.push(value),
+                Err(e) => return Ok(Err(e)),
+            }
+        } else if field == 2 && separator_field == 1 {
+            brackets = value.as_bool();
+        }
+    }
+
+    Ok(Ok((elems, separator, brackets)))
+}
+
+/// Decode a protocol `Value` message into a Sass value
+///
+/// The outer result fails if the message itself is malformed, while the inner
+/// result fails if the value is well-formed but can't be represented by `grass`.
+pub(crate) fn decode_value(buf: &[u8], span: Span) -> io::Result<SassResult<Value>> {
+    let (field, value) = match decode(buf)?.pop() {
+        Some(field) => field,
+        None => return Err(invalid("Value.value is not set")),
+    };
+
+    Ok(Ok(match field {
+        1 => {
+            let mut text = String::new();
+            let mut quoted = false;
+
+            for (field, value) in decode(value.as_bytes())? {
+                match field {
+                    1 => text = value.as_string()?,
+                    2 => quoted = value.as_bool(),
+                    _ => {}
+                }
+            }
+
+            Value::String(
+                text,
+                if quoted {
+                    QuoteKind::Quoted
+                } else {
+                    QuoteKind::None
+                },
+            )
+        }
+        2 => return decode_number(value.as_bytes()),
+        3 => return decode_color(value.as_bytes(), span),
+        5 => match decode_list(value.as_bytes(), 1, 3, span)? {
+            Ok((elems, separator, brackets)) => Value::List(
+                elems,
+                separator,
+                if brackets {
+                    Brackets::Bracketed
+                } else {
+                    Brackets::None
+                },
+            ),
+            Err(e) => return Ok(Err(e)),
+        },
+        6 => {
+            let mut map = SassMap::new();
+
+            for (_, entry) in decode(value.as_bytes())? {
+                let mut key = None;
+                let mut value = None;
+
+                for (field, encoded) in decode(entry.as_bytes())? {
+                    let decoded = match decode_value(encoded.as_bytes(), span)? {
+                        Ok(decoded) => decoded,
+                        Err(e) => return Ok(Err(e)),
+                    };
+
+                    match field {
+                        1 => key = Some(decoded),
+                        2 => value = Some(decoded),
+                        _ => {}
+                    }
+                }
+
+                match (key, value) {
+                    (Some(key), Some(value)) => {
+                        map.insert(Spanned { node: key, span }, value);
+                    }
+                    _ => return Err(invalid("Value.Map.Entry is missing a key or value")),
+                }
+            }
+
+            Value::Map(map)
+        }
+        7 => match value.as_u64() {
+            SINGLETON_TRUE => Value::True,
+            SINGLETON_FALSE => Value::False,
+            _ => Value::Null,
+        },
+        10 => match decode_list(value.as_bytes(), 2, 3, span)? {
+            Ok((elems, separator, ..)) => Value::ArgList(ArgList::new(
+                elems,
+                Arc::default(),
+                BTreeMap::new(),
+                separator,
+            )),
+            Err(e) => return Ok(Err(e)),
+        },
+        8 | 9 | 11 | 12 | 13 => {
+            return Ok(Err((
+                "Functions, mixins and calculations returned by the host are not supported.",
+                span,
+            )
+                .into()))
+        }
+        _ => return Err(invalid("Value.value has an unknown type")),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use grass_compiler::codemap::CodeMap;
+
+    use super::*;
+
+    fn span() -> Span {
+        CodeMap::new()
+            .add_file("test".to_owned(), String::new())
+            .span
+    }
+
+    fn round_trip(value: &Value) -> Value {
+        let encoded = encode_value(value, span()).unwrap().into_bytes();
+        decode_value(&encoded, span()).unwrap().unwrap()
+    }
+
+    fn sass_number(num: f64, unit: &str) -> SassNumber {
+        SassNumber {
+            num: Number(num),
+            unit: Unit::from(unit.to_owned()),
+            as_slash: None,
+        }
+    }
+
+    fn number(num: f64, unit: &str) -> Value {
+        Value::Dimension(sass_number(num, unit))
+    }
+
+    #[test]
+    fn string_round_trip() {
+        for quotes in [QuoteKind::Quoted, QuoteKind::None] {
+            match round_trip(&Value::String("a b".to_owned(), quotes)) {
+                Value::String(text, decoded_quotes) => {
+                    assert_eq!(text, "a b");
+                    assert_eq!(decoded_quotes, quotes);
+                }
+                value => panic!("expected a string, found {:?}", value),
+            }
+        }
+    }
+
+    #[test]
+    fn number_round_trip() {
+        for value in [
+            number(1.5, ""),
+            number(-2.0, "px"),
+            number(3.0, "%"),
+            Value::Dimension(sass_number(10.0, "px") / sass_number(1.0, "s")),
+        ] {
+            assert_eq!(round_trip(&value), value);
+        }
+    }
+
+    #[test]
+    fn singletons_round_trip() {
+        for value in [Value::True, Value::False, Value::Null] {
+            assert_eq!(round_trip(&value), value);
+        }
+    }
+
+    #[test]
+    fn list_round_trip() {
+        let value = Value::List(
+            vec![
+                number(1.0, "px"),
+                Value::String("a".to_owned(), QuoteKind::None),
+            ],
+            ListSeparator::Slash,
+            Brackets::Bracketed,
+        );
+
+        assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let mut map = SassMap::new();
+        map.insert(
+            Spanned {
+                node: Value::String("key".to_owned(), QuoteKind::Quoted),
+                span: span(),
+            },
+            number(1.0, "em"),
+        );
+        let value = Value::Map(map);
+
+        assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn color_round_trip() {
+        let value = Value::Color(Arc::new(Color::for_space(
+            ColorSpace::Rgb,
+            [Some(Number(255.0)), Some(Number(0.0)), Some(Number(51.0))],
+            Some(Number(0.5)),
+        )));
+
+        assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn decode_host_number() {
+        let mut number_message = Encoder::new();
+        number_message.double(1, 2.0).string(2, "px");
+
+        let mut message = Encoder::new();
+        message.message(2, number_message);
+
+        assert_eq!(
+            decode_value(&message.into_bytes(), span())
+                .unwrap()
+                .unwrap(),
+            number(2.0, "px")
+        );
+    }
+
+    #[test]
+    fn decode_host_argument_list() {
+        let mut list = Encoder::new();
+        list.varint(1, 1)
+            .varint(2, 1)
+            .message(3, encode_value(&Value::True, span()).unwrap());
+
+        let mut message = Encoder::new();
+        message.message(10, list);
+
+        match decode_value(&message.into_bytes(), span())
+            .unwrap()
+            .unwrap()
+        {
+            Value::ArgList(args) => {
+                assert_eq!(args.elems, vec![Value::True]);
+                assert_eq!(args.separator, ListSeparator::Space);
+            }
+            value => panic!("expected an argument list, found {:?}", value),
+        }
+    }
+
+    #[test]
+    fn decode_unsupported_color_space() {
+        let mut color = Encoder::new();
+        color.string(1, "nonsense");
+
+        let mut message = Encoder::new();
+        message.message(3, color);
+
+        assert!(decode_value(&message.into_bytes(), span())
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn decode_unset_value() {
+        assert!(decode_value(&[], span()).is_err());
+    }
+}
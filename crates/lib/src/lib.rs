@@ -66,8 +66,10 @@ grass input.scss
 )]
 
 pub use grass_compiler::{
-    from_path, from_string, Error, ErrorKind, Fs, InputSyntax, NullFs, Options, OutputStyle,
-    Result, StdFs,
+    compile_to_writer, from_path, from_path_as_css_module, from_path_with_rtl, from_string,
+    from_string_as_css_module, from_string_with_path, from_string_with_rtl, BrowserTargets,
+    CssModule, Deprecation, Error, ErrorKind, Fs, IndentType, InputSyntax, LineFeed,
+    MediaQueryGrouping, NullFs, Options, OutputStyle, Result, StdFs, UrlRewrite, UsedSelectors,
 };
 
 /// Include CSS in your binary at compile time from a Sass source file
@@ -1,28 +1,105 @@
 use std::{
-    fs::OpenOptions,
-    io::{stdin, stdout, Read, Write},
-    path::Path,
+    fs::{self, File},
+    io::{stdin, stdout, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process,
 };
 
 use clap::{builder::PossibleValue, value_parser, Arg, ArgAction, Command, ValueEnum};
 
-use grass::{from_path, from_string, Options, OutputStyle};
+use grass::{
+    compile_to_writer, from_path_as_css_module, from_path_with_rtl, from_string_with_path,
+    BrowserTargets, Deprecation, IndentType, InputSyntax, LineFeed, MediaQueryGrouping, Options,
+    OutputStyle, UrlRewrite, UsedSelectors,
+};
+
+mod embedded;
 
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub enum Style {
     Expanded,
     Compressed,
+    Nested,
+    Compact,
 }
 
 impl ValueEnum for Style {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Self::Expanded, Self::Compressed]
+        &[
+            Self::Expanded,
+            Self::Compressed,
+            Self::Nested,
+            Self::Compact,
+        ]
     }
 
     fn to_possible_value(&self) -> Option<PossibleValue> {
         Some(match self {
             Self::Expanded => PossibleValue::new("expanded"),
             Self::Compressed => PossibleValue::new("compressed"),
+            Self::Nested => PossibleValue::new("nested"),
+            Self::Compact => PossibleValue::new("compact"),
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Indent {
+    Space,
+    Tab,
+}
+
+impl ValueEnum for Indent {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Space, Self::Tab]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Space => PossibleValue::new("space"),
+            Self::Tab => PossibleValue::new("tab"),
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Linefeed {
+    Lf,
+    LfCr,
+    Cr,
+    CrLf,
+}
+
+impl ValueEnum for Linefeed {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Lf, Self::LfCr, Self::Cr, Self::CrLf]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Lf => PossibleValue::new("lf"),
+            Self::LfCr => PossibleValue::new("lfcr"),
+            Self::Cr => PossibleValue::new("cr"),
+            Self::CrLf => PossibleValue::new("crlf"),
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum MediaGrouping {
+    Safe,
+    Aggressive,
+}
+
+impl ValueEnum for MediaGrouping {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Safe, Self::Aggressive]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Safe => PossibleValue::new("safe"),
+            Self::Aggressive => PossibleValue::new("aggressive"),
         })
     }
 }
@@ -58,6 +135,13 @@ fn cli() -> Command {
                 .short('v')
                 .global(true)
         )
+        .arg(
+            Arg::new("EMBEDDED")
+                .action(ArgAction::SetTrue)
+                .long("embedded")
+                .exclusive(true)
+                .help("Run as a compiler for an embedded Sass host, communicating over stdin and stdout"),
+        )
         .arg(
             Arg::new("STDIN")
                 .action(ArgAction::SetTrue)
@@ -66,9 +150,25 @@ fn cli() -> Command {
         )
         .arg(
             Arg::new("INDENTED")
+                .action(ArgAction::SetTrue)
                 .long("indented")
-                .hide(true)
-                .help("Use the indented syntax for input from stdin"),
+                .overrides_with("NO_INDENTED")
+                .help("Use the indented syntax for input from stdin."),
+        )
+        .arg(
+            Arg::new("NO_INDENTED")
+                .action(ArgAction::SetTrue)
+                .long("no-indented")
+                .overrides_with("INDENTED")
+                .help("Use the SCSS syntax for input from stdin."),
+        )
+        .arg(
+            Arg::new("STDIN_FILE_PATH")
+                .long("stdin-file-path")
+                .requires("STDIN")
+                .value_parser(value_parser!(String))
+                .num_args(1)
+                .help("The path of the stylesheet read from stdin. Relative imports are resolved from it and it is shown in messages."),
         )
         .arg(
             Arg::new("LOAD_PATH")
@@ -85,12 +185,123 @@ fn cli() -> Command {
                 .short_alias('t')
                 .short('s')
                 .long("style")
-                .help("Minified or expanded output")
+                .help("Output style")
                 .default_value("expanded")
                 .ignore_case(true)
                 .num_args(1)
                 .value_parser(value_parser!(Style)),
         )
+        .arg(
+            Arg::new("INDENT_TYPE")
+                .long("indent-type")
+                .help("Indent output using spaces or tabs")
+                .default_value("space")
+                .ignore_case(true)
+                .num_args(1)
+                .value_parser(value_parser!(Indent)),
+        )
+        .arg(
+            Arg::new("INDENT_WIDTH")
+                .long("indent-width")
+                .help("The number of spaces or tabs used for each level of indentation")
+                .default_value("2")
+                .num_args(1)
+                .value_parser(value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("LINEFEED")
+                .long("linefeed")
+                .help("The line ending used in output")
+                .default_value("lf")
+                .ignore_case(true)
+                .num_args(1)
+                .value_parser(value_parser!(Linefeed)),
+        )
+        .arg(
+            Arg::new("NATIVE_NESTING")
+                .action(ArgAction::SetTrue)
+                .long("native-nesting")
+                .help("Emit nested style rules using native CSS nesting where possible."),
+        )
+        .arg(
+            Arg::new("MINIFY")
+                .action(ArgAction::SetTrue)
+                .long("minify")
+                .help("Optimize the generated CSS to reduce its size."),
+        )
+        .arg(
+            Arg::new("GROUP_MEDIA_QUERIES")
+                .long("group-media-queries")
+                .help("Merge @media rules with identical queries.")
+                .ignore_case(true)
+                .num_args(1)
+                .value_parser(value_parser!(MediaGrouping)),
+        )
+        .arg(
+            Arg::new("BROWSER_TARGETS")
+                .long("browser-targets")
+                .help("Add vendor prefixes for the browsers matching a browserslist-style query.")
+                .num_args(1)
+                .value_parser(BrowserTargets::parse),
+        )
+        .arg(
+            Arg::new("PURGE_CONTENT")
+                .long("purge-content")
+                .help("Remove CSS which isn't used by this HTML or template file. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(String))
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("PURGE_SAFELIST")
+                .long("purge-safelist")
+                .help("A class, ID, or element name to keep when removing unused CSS. May be passed multiple times.")
+                .requires("PURGE_CONTENT")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(String))
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("RTL")
+                .action(ArgAction::SetTrue)
+                .long("rtl")
+                .help("Mirror the generated CSS for right-to-left languages."),
+        )
+        .arg(
+            Arg::new("RTL_OUTPUT")
+                .long("rtl-output")
+                .help("Also write CSS mirrored for right-to-left languages to this file.")
+                .requires("INPUT")
+                .conflicts_with("RTL")
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("REWRITE_URLS")
+                .action(ArgAction::SetTrue)
+                .long("rewrite-urls")
+                .help("Rewrite relative url()s to be relative to the output file rather than the stylesheet they were written in."),
+        )
+        .arg(
+            Arg::new("PUBLIC_PATH")
+                .long("public-path")
+                .help("Rewrite relative url()s to begin with this path, followed by their location relative to the input file.")
+                .conflicts_with("REWRITE_URLS")
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("URL_HASH")
+                .action(ArgAction::SetTrue)
+                .long("url-hash")
+                .help("Add a hash of each file's contents to the query string of rewritten url()s."),
+        )
+        .arg(
+            Arg::new("CSS_MODULES")
+                .long("css-modules")
+                .help("Compile as a CSS Module, writing the JSON map of class and @keyframes names to this file.")
+                .requires("INPUT")
+                .conflicts_with("RTL_OUTPUT")
+                .num_args(1),
+        )
         .arg(
             Arg::new("NO_CHARSET")
                 .action(ArgAction::SetTrue)
@@ -103,10 +314,18 @@ fn cli() -> Command {
                 .hide(true)
                 .help("Only compile out-of-date stylesheets."),
         )
+        .arg(
+            Arg::new("ERROR_CSS")
+                .action(ArgAction::SetTrue)
+                .long("error-css")
+                .overrides_with("NO_ERROR_CSS")
+                .help("When an error occurs, emit a stylesheet describing it. Defaults to true when compiling to a file."),
+        )
         .arg(
             Arg::new("NO_ERROR_CSS")
+                .action(ArgAction::SetTrue)
                 .long("no-error-css")
-                .hide(true)
+                .overrides_with("ERROR_CSS")
                 .help("When an error occurs, don't emit a stylesheet describing it."),
         )
         // Source maps
@@ -174,10 +393,26 @@ fn cli() -> Command {
         )
         .arg(
             Arg::new("VERBOSE")
+                .action(ArgAction::SetTrue)
                 .long("verbose")
-                .hide(true)
                 .help("Print all deprecation warnings even when they're repetitive.")
         )
+        .arg(
+            Arg::new("FATAL_DEPRECATION")
+                .long("fatal-deprecation")
+                .help("Deprecations to treat as errors. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(parse_deprecation)
+                .num_args(1)
+        )
+        .arg(
+            Arg::new("SILENCE_DEPRECATION")
+                .long("silence-deprecation")
+                .help("Deprecations to ignore. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(parse_deprecation)
+                .num_args(1)
+        )
         .arg(
             Arg::new("NO_UNICODE")
                 .action(ArgAction::SetTrue)
@@ -191,10 +426,16 @@ fn cli() -> Command {
                 .long("quiet")
                 .help("Don't print warnings."),
         )
+        .arg(
+            Arg::new("QUIET_DEPS")
+                .action(ArgAction::SetTrue)
+                .long("quiet-deps")
+                .help("Don't print compiler warnings from dependencies loaded through load paths."),
+        )
         .arg(
             Arg::new("INPUT")
                 .value_parser(value_parser!(String))
-                .required_unless_present("STDIN")
+                .required_unless_present_any(["STDIN", "EMBEDDED"])
                 .help("Sass files"),
         )
         .arg(
@@ -211,9 +452,17 @@ fn cli() -> Command {
         )
 }
 
+fn parse_deprecation(id: &str) -> Result<Deprecation, String> {
+    Deprecation::from_id(id).ok_or_else(|| format!("Invalid deprecation \"{}\".", id))
+}
+
 fn main() -> std::io::Result<()> {
     let matches = cli().get_matches();
 
+    if matches.get_flag("EMBEDDED") {
+        return embedded::run();
+    }
+
     let load_paths = matches
         .get_many::<String>("LOAD_PATH")
         .map_or_else(Vec::new, |vals| vals.map(Path::new).collect());
@@ -221,52 +470,188 @@ fn main() -> std::io::Result<()> {
     let style = match &matches.get_one::<Style>("STYLE").unwrap() {
         Style::Expanded => OutputStyle::Expanded,
         Style::Compressed => OutputStyle::Compressed,
+        Style::Nested => OutputStyle::Nested,
+        Style::Compact => OutputStyle::Compact,
     };
 
-    let options = &Options::default()
+    let indent_type = match &matches.get_one::<Indent>("INDENT_TYPE").unwrap() {
+        Indent::Space => IndentType::Space,
+        Indent::Tab => IndentType::Tab,
+    };
+
+    let linefeed = match &matches.get_one::<Linefeed>("LINEFEED").unwrap() {
+        Linefeed::Lf => LineFeed::Lf,
+        Linefeed::LfCr => LineFeed::LfCr,
+        Linefeed::Cr => LineFeed::Cr,
+        Linefeed::CrLf => LineFeed::CrLf,
+    };
+
+    let fatal_deprecations = matches
+        .get_many::<Deprecation>("FATAL_DEPRECATION")
+        .map_or_else(Vec::new, |vals| vals.copied().collect());
+
+    let silenced_deprecations = matches
+        .get_many::<Deprecation>("SILENCE_DEPRECATION")
+        .map_or_else(Vec::new, |vals| vals.copied().collect());
+
+    let used_selectors = matches.get_many::<String>("PURGE_CONTENT").map(|paths| {
+        paths
+            .fold(UsedSelectors::new(), UsedSelectors::content_path)
+            .safelist(
+                matches
+                    .get_many::<String>("PURGE_SAFELIST")
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            )
+    });
+
+    // Without an output file, the CSS is assumed to be used from the current
+    // directory
+    let output_dir = matches
+        .get_one::<String>("OUTPUT")
+        .and_then(|path| Path::new(path).parent())
+        .unwrap_or_else(|| Path::new(""));
+
+    let mut options = Options::default()
         .load_paths(&load_paths)
         .style(style)
+        .indent_type(indent_type)
+        .indent_width(*matches.get_one::<usize>("INDENT_WIDTH").unwrap())
+        .linefeed(linefeed)
         .quiet(matches.get_flag("QUIET"))
+        .quiet_deps(matches.get_flag("QUIET_DEPS"))
+        .verbose(matches.get_flag("VERBOSE"))
+        .fatal_deprecations(&fatal_deprecations)
+        .silence_deprecations(&silenced_deprecations)
         .unicode_error_messages(!matches.get_flag("NO_UNICODE"))
-        .allows_charset(!matches.get_flag("NO_CHARSET"));
-
-    let (mut stdout_write, mut file_write);
-    let buf_out: &mut dyn Write = if let Some(path) = matches.get_one::<String>("OUTPUT") {
-        file_write = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(path)?;
-        &mut file_write
+        .allows_charset(!matches.get_flag("NO_CHARSET"))
+        .native_nesting(matches.get_flag("NATIVE_NESTING"))
+        .minify(matches.get_flag("MINIFY"))
+        .rtl(matches.get_flag("RTL"))
+        .cache_bust_urls(matches.get_flag("URL_HASH"));
+
+    if let Some(grouping) = matches.get_one::<MediaGrouping>("GROUP_MEDIA_QUERIES") {
+        options = options.group_media_queries(match grouping {
+            MediaGrouping::Safe => MediaQueryGrouping::Safe,
+            MediaGrouping::Aggressive => MediaQueryGrouping::Aggressive,
+        });
+    }
+
+    if let Some(used) = &used_selectors {
+        options = options.purge_unused(used);
+    }
+
+    if let Some(targets) = matches.get_one::<BrowserTargets>("BROWSER_TARGETS") {
+        options = options.browser_targets(targets);
+    }
+
+    if let Some(public_path) = matches.get_one::<String>("PUBLIC_PATH") {
+        options = options.rewrite_urls(UrlRewrite::PublicPath(public_path));
+    } else if matches.get_flag("REWRITE_URLS") {
+        options = options.rewrite_urls(UrlRewrite::RelativeTo(output_dir));
+    }
+
+    if matches.get_flag("STDIN") {
+        if matches.get_flag("INDENTED") {
+            options = options.input_syntax(InputSyntax::Sass);
+        } else if matches.get_flag("NO_INDENTED") {
+            options = options.input_syntax(InputSyntax::Scss);
+        }
+    }
+
+    let options = &options;
+
+    let output = matches.get_one::<String>("OUTPUT");
+
+    let emit_error_css = if matches.get_flag("NO_ERROR_CSS") {
+        false
     } else {
-        stdout_write = stdout();
-        &mut stdout_write
+        matches.get_flag("ERROR_CSS") || output.is_some()
     };
 
-    buf_out.write_all(
-        if let Some(name) = matches.get_one::<String>("INPUT") {
-            from_path(name, options)
-        } else if matches.get_flag("STDIN") {
-            from_string(
-                {
-                    let mut buffer = String::new();
-                    stdin().read_to_string(&mut buffer)?;
-                    buffer
-                },
-                options,
-            )
-        } else {
-            unreachable!()
-        }
-        .unwrap_or_else(|e| {
-            eprintln!("{}", e);
-            std::process::exit(1)
+    // Output is streamed to a temporary file, which only replaces the output
+    // file once compilation has succeeded
+    let temp_path = output.map(|path| temp_path_for(Path::new(path)));
+
+    let mut buf_out: Box<dyn Write> = match &temp_path {
+        Some(temp_path) => Box::new(BufWriter::new(File::create(temp_path)?)),
+        None => Box::new(BufWriter::new(stdout())),
+    };
+
+    let result = if let (Some(name), Some(rtl_path)) = (
+        matches.get_one::<String>("INPUT"),
+        matches.get_one::<String>("RTL_OUTPUT"),
+    ) {
+        from_path_with_rtl(name, options).and_then(|(css, rtl_css)| {
+            fs::write(rtl_path, rtl_css)?;
+            Ok(buf_out.write_all(css.as_bytes())?)
+        })
+    } else if let (Some(name), Some(json_path)) = (
+        matches.get_one::<String>("INPUT"),
+        matches.get_one::<String>("CSS_MODULES"),
+    ) {
+        from_path_as_css_module(name, options).and_then(|module| {
+            fs::write(json_path, module.exports_json())?;
+            Ok(buf_out.write_all(module.css.as_bytes())?)
         })
-        .as_bytes(),
-    )?;
+    } else if let Some(name) = matches.get_one::<String>("INPUT") {
+        compile_to_writer(name, options, &mut buf_out)
+    } else if matches.get_flag("STDIN") {
+        from_string_with_path(
+            {
+                let mut buffer = String::new();
+                stdin().read_to_string(&mut buffer)?;
+                buffer
+            },
+            matches
+                .get_one::<String>("STDIN_FILE_PATH")
+                .map_or("stdin", String::as_str),
+            options,
+        )
+        .and_then(|css| Ok(buf_out.write_all(css.as_bytes())?))
+    } else {
+        unreachable!()
+    };
+
+    if let Err(e) = result {
+        drop(buf_out);
+
+        if let Some(temp_path) = &temp_path {
+            fs::remove_file(temp_path)?;
+        }
+
+        if emit_error_css {
+            match output {
+                Some(path) => fs::write(path, e.to_css_string())?,
+                None => stdout().write_all(e.to_css_string().as_bytes())?,
+            }
+        }
+
+        eprintln!("{}", e);
+        process::exit(1)
+    }
+
+    buf_out.flush()?;
+    drop(buf_out);
+
+    if let (Some(path), Some(temp_path)) = (output, &temp_path) {
+        fs::rename(temp_path, path)?;
+    }
+
     Ok(())
 }
 
+/// A path in the same directory as `path`, so that it can be atomically renamed
+/// to `path`
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map_or_else(|| "output".into(), |name| name.to_string_lossy());
+
+    path.with_file_name(format!(".{}.{}.tmp", file_name, process::id()))
+}
+
 #[cfg(test)]
 mod test {
     use crate::cli;
@@ -0,0 +1,227 @@
+#[macro_use]
+mod macros;
+
+test!(
+    lab_literal,
+    "a {\n  color: lab(50% 40 -20);\n}\n",
+    "a {\n  color: lab(50% 40 -20);\n}\n"
+);
+test!(
+    lab_percent_channels,
+    "a {\n  color: lab(50% 40% -20%);\n}\n",
+    "a {\n  color: lab(50% 50 -25);\n}\n"
+);
+test!(
+    lab_lightness_is_clamped,
+    "a {\n  color: lab(120% 0 0);\n}\n",
+    "a {\n  color: lab(100% 0 0);\n}\n"
+);
+test!(
+    lch_hue_is_normalized,
+    "a {\n  color: lch(50% 30 400deg);\n}\n",
+    "a {\n  color: lch(50% 30 40deg);\n}\n"
+);
+test!(
+    oklab_with_alpha,
+    "a {\n  color: oklab(60% 0.1 -0.1 / 0.5);\n}\n",
+    "a {\n  color: oklab(60% 0.1 -0.1 / 0.5);\n}\n"
+);
+test!(
+    oklch_unitless_lightness,
+    "a {\n  color: oklch(0.7 0.1 120);\n}\n",
+    "a {\n  color: oklch(70% 0.1 120deg);\n}\n"
+);
+test!(
+    color_display_p3,
+    "a {\n  color: color(display-p3 1 0 0);\n}\n",
+    "a {\n  color: color(display-p3 1 0 0);\n}\n"
+);
+test!(
+    color_srgb_linear_percent_alpha,
+    "a {\n  color: color(srgb-linear 0.5 0.5 0.5 / 50%);\n}\n",
+    "a {\n  color: color(srgb-linear 0.5 0.5 0.5 / 0.5);\n}\n"
+);
+test!(
+    color_xyz_d65_alias,
+    "a {\n  color: color(xyz-d65 0.1 0.2 0.3);\n}\n",
+    "a {\n  color: color(xyz 0.1 0.2 0.3);\n}\n"
+);
+test!(
+    color_with_var_channel,
+    "a {\n  color: color(display-p3 var(--x) 0 0);\n}\n",
+    "a {\n  color: color(display-p3 var(--x) 0 0);\n}\n"
+);
+test!(
+    lab_with_var,
+    "a {\n  color: lab(var(--x));\n}\n",
+    "a {\n  color: lab(var(--x));\n}\n"
+);
+test!(
+    rgb_missing_channel,
+    "a {\n  color: rgb(none 0 0);\n}\n",
+    "a {\n  color: rgb(none 0 0);\n}\n"
+);
+test!(
+    hsl_missing_channel,
+    "a {\n  color: hsl(120 none 50%);\n}\n",
+    "a {\n  color: hsl(120deg none 50%);\n}\n"
+);
+test!(
+    hwb_missing_channel,
+    "@use \"sass:color\";\na {\n  color: color.hwb(120 none 0%);\n}\n",
+    "a {\n  color: hwb(120deg none 0%);\n}\n"
+);
+test!(
+    type_of_space_color,
+    "@use \"sass:meta\";\na {\n  color: meta.type-of(lab(50% 0 0));\n}\n",
+    "a {\n  color: color;\n}\n"
+);
+test!(
+    space_colors_equal,
+    "a {\n  color: lab(50% 40 -20) == lab(50% 40 -20);\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    space_colors_different_channels_not_equal,
+    "a {\n  color: lab(50% 40 -20) == lab(50% 40 -21);\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    space_colors_different_spaces_not_equal,
+    "a {\n  color: rgb(255 0 0) == color(srgb 1 0 0);\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    missing_channel_not_equal_to_zero,
+    "a {\n  color: rgb(none 0 0) == rgb(0 0 0);\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+error!(
+    color_unknown_space,
+    "a {\n  color: color(foo 1 2 3);\n}\n", "Error: Unknown color space \"foo\"."
+);
+error!(
+    lab_invalid_unit,
+    "a {\n  color: lab(50px 1 2);\n}\n",
+    "Error: $lightness: Expected 50px to have unit \"%\" or no units."
+);
+test!(
+    space_of_legacy_color,
+    "@use \"sass:color\";\na {\n  color: color.space(red);\n}\n",
+    "a {\n  color: rgb;\n}\n"
+);
+test!(
+    space_of_lab_color,
+    "@use \"sass:color\";\na {\n  color: color.space(lab(50% 0 0));\n}\n",
+    "a {\n  color: lab;\n}\n"
+);
+test!(
+    to_space_lab,
+    "@use \"sass:color\";\na {\n  color: color.to-space(red, lab);\n}\n",
+    "a {\n  color: lab(54.290542947% 80.8049203346 69.890988259);\n}\n"
+);
+test!(
+    to_space_round_trip,
+    "@use \"sass:color\";\na {\n  color: color.to-space(color.to-space(red, oklch), rgb);\n}\n",
+    "a {\n  color: red;\n}\n"
+);
+test!(
+    channel_in_other_space,
+    "@use \"sass:color\";\na {\n  color: color.channel(red, \"hue\", $space: hsl);\n}\n",
+    "a {\n  color: 0deg;\n}\n"
+);
+test!(
+    channel_oklch_lightness,
+    "@use \"sass:color\";\na {\n  color: color.channel(oklch(70% 0.1 120), \"lightness\");\n}\n",
+    "a {\n  color: 70%;\n}\n"
+);
+test!(
+    channel_legacy_red,
+    "@use \"sass:color\";\na {\n  color: color.channel(red, \"red\");\n}\n",
+    "a {\n  color: 255;\n}\n"
+);
+test!(
+    is_legacy,
+    "@use \"sass:color\";\na {\n  color: color.is-legacy(red) color.is-legacy(lab(50% 0 0));\n}\n",
+    "a {\n  color: true false;\n}\n"
+);
+test!(
+    is_missing,
+    "@use \"sass:color\";\na {\n  color: color.is-missing(rgb(none 0 0), \"red\") color.is-missing(red, \"red\");\n}\n",
+    "a {\n  color: true false;\n}\n"
+);
+test!(
+    is_powerless_gray_hue,
+    "@use \"sass:color\";\na {\n  color: color.is-powerless(hsl(120 0% 50%), \"hue\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    is_in_gamut,
+    "@use \"sass:color\";\na {\n  color: color.is-in-gamut(color(display-p3 1 0 0)) color.is-in-gamut(color(display-p3 1 0 0), srgb);\n}\n",
+    "a {\n  color: true false;\n}\n"
+);
+test!(
+    to_gamut_clip,
+    "@use \"sass:color\";\na {\n  color: color.to-gamut(color(display-p3 1 0 0), srgb, $method: clip);\n}\n",
+    "a {\n  color: color(display-p3 0.9174875573 0.2002868077 0.1385605912);\n}\n"
+);
+test!(
+    to_gamut_local_minde,
+    "@use \"sass:color\";\na {\n  color: color.to-gamut(color(display-p3 1 0 0), srgb, $method: local-minde);\n}\n",
+    "a {\n  color: color(display-p3 0.9177905633 0.2107213818 0.1542354933);\n}\n"
+);
+test!(
+    same_across_spaces,
+    "@use \"sass:color\";\na {\n  color: color.same(red, color(srgb 1 0 0));\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    adjust_oklch_lightness,
+    "@use \"sass:color\";\na {\n  color: color.adjust(oklch(50% 0.1 120), $lightness: 10%);\n}\n",
+    "a {\n  color: oklch(60% 0.1 120deg);\n}\n"
+);
+test!(
+    scale_oklch_chroma,
+    "@use \"sass:color\";\na {\n  color: color.scale(oklch(50% 0.1 120), $chroma: 50%);\n}\n",
+    "a {\n  color: oklch(50% 0.25 120deg);\n}\n"
+);
+test!(
+    change_with_space_returns_original_space,
+    "@use \"sass:color\";\na {\n  color: color.space(color.change(red, $hue: 120, $space: oklch));\n}\n",
+    "a {\n  color: rgb;\n}\n"
+);
+test!(
+    adjust_lab_channel_and_alpha,
+    "@use \"sass:color\";\na {\n  color: color.adjust(lab(50% 0 0), $a: 10, $alpha: -0.5);\n}\n",
+    "a {\n  color: lab(50% 10 0 / 0.5);\n}\n"
+);
+test!(
+    mix_with_method,
+    "@use \"sass:color\";\na {\n  color: color.mix(lab(50% 0 0), lab(70% 20 20), 50%, lab);\n}\n",
+    "a {\n  color: lab(60% 10 10);\n}\n"
+);
+test!(
+    mix_legacy_with_method,
+    "@use \"sass:color\";\na {\n  color: color.mix(red, blue, $method: srgb);\n}\n",
+    "a {\n  color: purple;\n}\n"
+);
+error!(
+    to_gamut_requires_method,
+    "@use \"sass:color\";\na {\n  color: color.to-gamut(red);\n}\n",
+    "Error: $method: color.to-gamut() requires a $method argument for forwards-compatibility with changes in the CSS spec. Suggestion:"
+);
+error!(
+    mix_non_legacy_requires_method,
+    "@use \"sass:color\";\na {\n  color: color.mix(lab(50% 0 0), red);\n}\n",
+    "Error: $method: To use color.mix() with non-legacy colors, you must provide a $method."
+);
+error!(
+    scale_hue_not_scalable,
+    "@use \"sass:color\";\na {\n  color: color.scale(oklch(50% 0.1 120), $hue: 10%);\n}\n",
+    "Error: $hue: Channel isn't scalable."
+);
+error!(
+    channel_unknown_name,
+    "@use \"sass:color\";\na {\n  color: color.channel(red, \"chroma\");\n}\n",
+    "Error: $channel: Color red has no channel named chroma."
+);
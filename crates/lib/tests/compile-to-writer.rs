@@ -0,0 +1,158 @@
+use macros::TestFs;
+
+#[macro_use]
+mod macros;
+
+fn compile<'a>(fs: &'a TestFs, options: grass::Options<'a>) -> grass::Result<String> {
+    let mut output = Vec::new();
+    grass::compile_to_writer("input.scss", &options.fs(fs), &mut output)?;
+    Ok(String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn matches_from_path() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "input.scss",
+        "a {\n  color: red;\n}\n@media screen {\n  b {\n    color: blue;\n  }\n}\nc {\n  d {\n    color: green;\n  }\n}\n",
+    );
+
+    assert_eq!(
+        grass::from_path("input.scss", &grass::Options::default().fs(&fs)).unwrap(),
+        compile(&fs, grass::Options::default()).unwrap()
+    );
+}
+
+#[test]
+fn compressed_matches_from_path() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "input.scss",
+        "a {\n  color: red;\n}\n@import \"foo.css\";\nb {\n  color: blue;\n}\n",
+    );
+
+    assert_eq!(
+        grass::from_path(
+            "input.scss",
+            &grass::Options::default()
+                .style(grass::OutputStyle::Compressed)
+                .fs(&fs)
+        )
+        .unwrap(),
+        compile(
+            &fs,
+            grass::Options::default().style(grass::OutputStyle::Compressed)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn writes_charset_for_non_ascii_in_later_group() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "input.scss",
+        "a {\n  color: red;\n}\nb {\n  content: \"👭\";\n}\n",
+    );
+
+    assert_eq!(
+        "@charset \"UTF-8\";\na {\n  color: red;\n}\n\nb {\n  content: \"👭\";\n}\n",
+        compile(&fs, grass::Options::default()).unwrap()
+    );
+}
+
+#[test]
+fn writes_bom_when_compressed() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "input.scss",
+        "a {\n  color: red;\n}\nb {\n  content: \"👭\";\n}\n",
+    );
+
+    assert_eq!(
+        "\u{FEFF}a{color:red}b{content:\"👭\"}",
+        compile(
+            &fs,
+            grass::Options::default().style(grass::OutputStyle::Compressed)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn empty_stylesheet_writes_nothing() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "$a: red;\n");
+
+    assert_eq!("", compile(&fs, grass::Options::default()).unwrap());
+}
+
+#[test]
+fn writes_nothing_on_error() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "input.scss",
+        "a {\n  color: red;\n}\nb {\n  color: $a;\n}\n",
+    );
+
+    let mut output = Vec::new();
+    assert!(grass::compile_to_writer(
+        "input.scss",
+        &grass::Options::default().fs(&fs),
+        &mut output
+    )
+    .is_err());
+    assert!(output.is_empty());
+}
+
+#[test]
+fn serialization_error_leaves_preceding_groups() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "input.scss",
+        "a {\n  content: \"👭\";\n}\nb {\n  c: (d: e);\n}\n",
+    );
+
+    let mut output = Vec::new();
+    assert!(grass::compile_to_writer(
+        "input.scss",
+        &grass::Options::default().fs(&fs),
+        &mut output
+    )
+    .is_err());
+    assert_eq!(
+        "@charset \"UTF-8\";\na {\n  content: \"👭\";\n}",
+        String::from_utf8(output).unwrap()
+    );
+}
+
+#[test]
+fn charset_matches_from_path() {
+    for input in [
+        "a {\n  content: \"\\2022\";\n}\n",
+        ".café {\n  color: red;\n}\n",
+        "a {\n  width: calc(1px + #{\"é\"});\n}\n",
+        "@media (min-width: 1px) {\n  a {\n    b: 1é;\n  }\n}\n",
+        "@keyframes é {\n  to {\n    color: red;\n  }\n}\n",
+        "/* é */\na {\n  color: red;\n}\n",
+        "/*! é */\na {\n  color: red;\n}\n",
+        "%é {\n  color: red;\n}\na {\n  color: red;\n}\n",
+        "a {\n  b: é/c;\n}\n",
+    ] {
+        let mut fs = TestFs::new();
+        fs.add_file("input.scss", input);
+
+        for style in [grass::OutputStyle::Expanded, grass::OutputStyle::Compressed] {
+            assert_eq!(
+                grass::from_path(
+                    "input.scss",
+                    &grass::Options::default().style(style).fs(&fs)
+                )
+                .unwrap(),
+                compile(&fs, grass::Options::default().style(style)).unwrap(),
+                "{}",
+                input
+            );
+        }
+    }
+}
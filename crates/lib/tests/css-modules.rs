@@ -0,0 +1,190 @@
+#[macro_use]
+mod macros;
+
+use std::collections::BTreeMap;
+
+use macros::TestFs;
+
+fn compile(input: &str) -> grass::CssModule {
+    grass::from_string_as_css_module(
+        input,
+        &grass::Options::default().style(grass::OutputStyle::Compressed),
+    )
+    .unwrap()
+}
+
+#[test]
+fn rewrites_class_names() {
+    let module = compile(".button { color: red; }");
+
+    assert_eq!(module.css, ".button_lxn4z3{color:red}");
+    assert_eq!(
+        module.exports,
+        BTreeMap::from([("button".to_owned(), "button_lxn4z3".to_owned())])
+    );
+}
+
+#[test]
+fn uses_same_name_for_each_occurrence() {
+    let module = compile(".a { color: red; } .b .a { color: blue; }");
+
+    assert_eq!(
+        module.css,
+        format!(
+            ".{a}{{color:red}}.{b} .{a}{{color:blue}}",
+            a = module.exports["a"],
+            b = module.exports["b"]
+        )
+    );
+}
+
+#[test]
+fn leaves_ids_and_elements_unchanged() {
+    let module = compile("#main a { color: red; }");
+
+    assert_eq!(module.css, "#main a{color:red}");
+    assert!(module.exports.is_empty());
+}
+
+#[test]
+fn rewrites_classes_within_selector_pseudo_classes() {
+    let module = compile(".a:not(.b) { color: red; }");
+
+    assert_eq!(
+        module.css,
+        format!(
+            ".{}:not(.{}){{color:red}}",
+            module.exports["a"], module.exports["b"]
+        )
+    );
+}
+
+#[test]
+fn global_class_is_left_unchanged() {
+    let module = compile(":global(.active) .a { color: red; } .b:global(.c) { color: blue; }");
+
+    assert_eq!(
+        module.css,
+        format!(
+            ".active .{}{{color:red}}.{}.c{{color:blue}}",
+            module.exports["a"], module.exports["b"]
+        )
+    );
+    assert!(!module.exports.contains_key("active"));
+    assert!(!module.exports.contains_key("c"));
+}
+
+#[test]
+fn global_complex_selector() {
+    let module = compile(":global(.a .b) { color: red; }");
+
+    assert_eq!(module.css, ".a .b{color:red}");
+    assert!(module.exports.is_empty());
+}
+
+#[test]
+fn local_class_is_rewritten() {
+    let module = compile(":local(.a) { color: red; }");
+
+    assert_eq!(module.css, format!(".{}{{color:red}}", module.exports["a"]));
+}
+
+#[test]
+fn rewrites_keyframes_and_animations() {
+    let module = compile(
+        "@keyframes fade { to { opacity: 0; } }
+         a { animation: fade 1s; animation-name: fade, other; }",
+    );
+
+    let fade = &module.exports["fade"];
+
+    assert_eq!(
+        module.css,
+        format!(
+            "@keyframes {fade}{{to{{opacity:0}}}}a{{animation:{fade} 1s;animation-name:{fade},other}}",
+            fade = fade
+        )
+    );
+}
+
+#[test]
+fn global_keyframes_are_left_unchanged() {
+    let module = compile(
+        "@keyframes :global(fade) { to { opacity: 0; } }
+         a { animation: fade 1s; }",
+    );
+
+    assert_eq!(
+        module.css,
+        "@keyframes fade{to{opacity:0}}a{animation:fade 1s}"
+    );
+    assert!(module.exports.is_empty());
+}
+
+#[test]
+fn class_names_do_not_rename_animations() {
+    let module = compile(".fade { animation: fade 1s; }");
+
+    assert_eq!(
+        module.css,
+        format!(".{}{{animation:fade 1s}}", module.exports["fade"])
+    );
+}
+
+#[test]
+fn escaped_class_names_are_exported_unescaped() {
+    let module = compile(".hover\\:underline { color: red; }");
+
+    let generated = &module.exports["hover:underline"];
+
+    assert!(generated.starts_with("hover:underline_"));
+    assert_eq!(
+        module.css,
+        format!(".hover\\:{}{{color:red}}", &generated["hover:".len()..])
+    );
+}
+
+#[test]
+fn names_depend_on_path() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", ".button { color: red; }");
+    fs.add_file("b.scss", ".button { color: red; }");
+
+    let options = grass::Options::default().fs(&fs);
+
+    let a = grass::from_path_as_css_module("a.scss", &options).unwrap();
+    let b = grass::from_path_as_css_module("b.scss", &options).unwrap();
+
+    assert_ne!(a.exports["button"], b.exports["button"]);
+    assert_eq!(
+        a,
+        grass::from_path_as_css_module("a.scss", &options).unwrap()
+    );
+}
+
+#[test]
+fn exports_json() {
+    let module = compile(".b { color: red; } .a { color: blue; }");
+
+    assert_eq!(
+        module.exports_json(),
+        format!(
+            "{{\n  \"a\": \"{}\",\n  \"b\": \"{}\"\n}}",
+            module.exports["a"], module.exports["b"]
+        )
+    );
+    assert_eq!(compile("a { color: red; }").exports_json(), "{}");
+}
+
+#[test]
+fn global_with_complex_selector_in_compound() {
+    match grass::from_string_as_css_module(
+        ".a:global(.b .c) { color: red; }",
+        &grass::Options::default(),
+    ) {
+        Ok(..) => panic!("expected an error"),
+        Err(e) => assert!(e
+            .to_string()
+            .starts_with("Error: Only a compound selector may be combined with :global().")),
+    }
+}
@@ -0,0 +1,213 @@
+use grass::Deprecation;
+
+use macros::TestFs;
+
+#[macro_use]
+mod macros;
+
+test!(
+    slash_div_is_only_a_warning_by_default,
+    "a {\n  color: (1 / 2);\n}\n",
+    "a {\n  color: 0.5;\n}\n"
+);
+test!(
+    silenced_deprecation_still_evaluates,
+    "a {\n  color: (1 / 2);\n}\n",
+    "a {\n  color: 0.5;\n}\n",
+    grass::Options::default().silence_deprecation(Deprecation::SlashDiv)
+);
+test!(
+    fatal_deprecation_does_not_affect_other_deprecations,
+    "a {\n  color: (1 / 2);\n}\n",
+    "a {\n  color: 0.5;\n}\n",
+    grass::Options::default().fatal_deprecation(Deprecation::Import)
+);
+test!(
+    slash_separated_number_in_style_is_not_deprecated,
+    "a {\n  font: 1/2;\n}\n",
+    "a {\n  font: 1/2;\n}\n",
+    grass::Options::default().fatal_deprecation(Deprecation::SlashDiv)
+);
+test!(
+    module_function_is_not_global_builtin,
+    "@use 'sass:map';\na {\n  color: map.get((a: b), a);\n}\n",
+    "a {\n  color: b;\n}\n",
+    grass::Options::default().fatal_deprecation(Deprecation::GlobalBuiltin)
+);
+test!(
+    css_function_is_not_global_builtin,
+    "a {\n  color: rgb(1, 2, 3);\n  filter: saturate(50%);\n}\n",
+    "a {\n  color: rgb(1, 2, 3);\n  filter: saturate(50%);\n}\n",
+    grass::Options::default()
+        .fatal_deprecation(Deprecation::GlobalBuiltin)
+        .fatal_deprecation(Deprecation::ColorFunctions)
+);
+test!(
+    plain_css_import_is_not_deprecated,
+    "@import \"foo.css\";\n",
+    "@import \"foo.css\";\n",
+    grass::Options::default().fatal_deprecation(Deprecation::Import)
+);
+test!(
+    global_assignment_to_existing_variable,
+    "$a: 1;\na {\n  $a: 2 !global;\n}\nb {\n  color: $a;\n}\n",
+    "b {\n  color: 2;\n}\n",
+    grass::Options::default().fatal_deprecation(Deprecation::NewGlobal)
+);
+error!(
+    fatal_slash_div,
+    "a {\n  color: (1 / 2);\n}\n",
+    "Error: Using / for division outside of calc() is deprecated and will be removed in Dart Sass 2.0.0.",
+    grass::Options::default().fatal_deprecation(Deprecation::SlashDiv)
+);
+error!(
+    fatal_slash_div_in_variable,
+    "$a: 1/2;\na {\n  color: $a;\n}\n",
+    "Error: Using / for division is deprecated and will be removed in Dart Sass 2.0.0.",
+    grass::Options::default().fatal_deprecation(Deprecation::SlashDiv)
+);
+error!(
+    fatal_slash_div_in_each,
+    "@each $a in 1/2 3/4 {\n  a {\n    color: $a;\n  }\n}\n",
+    "Error: Using / for division is deprecated and will be removed in Dart Sass 2.0.0.",
+    grass::Options::default().fatal_deprecation(Deprecation::SlashDiv)
+);
+error!(
+    fatal_slash_div_in_builtin_result,
+    "a {\n  color: nth(1/2 3, 1);\n}\n",
+    "Error: Using / for division is deprecated and will be removed in Dart Sass 2.0.0.",
+    grass::Options::default().fatal_deprecation(Deprecation::SlashDiv)
+);
+error!(
+    fatal_takes_precedence_over_silenced,
+    "a {\n  color: (1 / 2);\n}\n",
+    "Error: Using / for division outside of calc() is deprecated and will be removed in Dart Sass 2.0.0.",
+    grass::Options::default()
+        .silence_deprecation(Deprecation::SlashDiv)
+        .fatal_deprecation(Deprecation::SlashDiv)
+);
+error!(
+    fatal_elseif,
+    "@if false {}\n@elseif true {}\n",
+    "Error: @elseif is deprecated and will not be supported in future Sass versions.",
+    grass::Options::default().fatal_deprecation(Deprecation::Elseif)
+);
+error!(
+    fatal_import,
+    "@import \"a\";\n",
+    "Error: Sass @import rules are deprecated and will be removed in Dart Sass 3.0.0.",
+    grass::Options::default().fatal_deprecation(Deprecation::Import)
+);
+error!(
+    fatal_global_builtin,
+    "a {\n  color: map-get((a: b), a);\n}\n",
+    "Error: Global built-in functions are deprecated and will be removed in Dart Sass 3.0.0.",
+    grass::Options::default().fatal_deprecation(Deprecation::GlobalBuiltin)
+);
+error!(
+    fatal_color_functions,
+    "a {\n  color: lighten(red, 10%);\n}\n",
+    "Error: lighten() is deprecated. Suggestion:",
+    grass::Options::default().fatal_deprecation(Deprecation::ColorFunctions)
+);
+error!(
+    fatal_color_functions_alias,
+    "a {\n  color: fade-out(red, 0.5);\n}\n",
+    "Error: fade-out() is deprecated. Suggestion:",
+    grass::Options::default().fatal_deprecation(Deprecation::ColorFunctions)
+);
+error!(
+    fatal_new_global,
+    "a {\n  $a: 2 !global;\n}\n",
+    "Error: As of Dart Sass 2.0.0, !global assignments won't be able to declare new variables.",
+    grass::Options::default().fatal_deprecation(Deprecation::NewGlobal)
+);
+error!(
+    fatal_feature_exists,
+    "a {\n  color: feature-exists(at-error);\n}\n",
+    "Error: The feature-exists() function is deprecated.",
+    grass::Options::default().fatal_deprecation(Deprecation::FeatureExists)
+);
+error!(
+    fatal_call_string,
+    "a {\n  color: call(\"unquote\", a);\n}\n",
+    "Error: Passing a string to call() is deprecated and will be illegal in Dart Sass 2.0.0.",
+    grass::Options::default().fatal_deprecation(Deprecation::CallString)
+);
+
+#[test]
+fn fatal_deprecation_explains_how_to_recover() {
+    let err = grass::from_string(
+        "a {\n  color: (1 / 2);\n}\n".to_owned(),
+        &grass::Options::default().fatal_deprecation(Deprecation::SlashDiv),
+    )
+    .unwrap_err();
+
+    assert!(err.to_string().contains(
+        "This is only an error because you've set the slash-div deprecation to be fatal.\nRemove this setting if you need to keep using this feature."
+    ));
+}
+
+#[test]
+fn deprecation_ids_round_trip() {
+    for &deprecation in Deprecation::ALL {
+        assert_eq!(Deprecation::from_id(deprecation.id()), Some(deprecation));
+    }
+
+    assert_eq!(Deprecation::from_id("not-a-deprecation"), None);
+}
+
+#[test]
+fn quiet_deps_silences_deprecations_from_load_paths() {
+    let mut fs = TestFs::new();
+    fs.add_file("lib/_a.scss", "$a: (1 / 2);");
+
+    let input = "@use 'a';\nb {\n  color: a.$a;\n}\n";
+
+    let options = grass::Options::default()
+        .fs(&fs)
+        .load_path("lib")
+        .fatal_deprecation(Deprecation::SlashDiv);
+
+    assert!(grass::from_string(input.to_owned(), &options).is_err());
+    assert_eq!(
+        "b {\n  color: 0.5;\n}\n",
+        &grass::from_string(input.to_owned(), &options.quiet_deps(true)).unwrap()
+    );
+}
+
+#[test]
+fn quiet_deps_silences_files_loaded_by_dependencies() {
+    let mut fs = TestFs::new();
+    fs.add_file("lib/_a.scss", "@forward 'b';");
+    fs.add_file("lib/_b.scss", "$b: (1 / 2);");
+
+    let input = "@use 'a';\nb {\n  color: a.$b;\n}\n";
+
+    let options = grass::Options::default()
+        .fs(&fs)
+        .load_path("lib")
+        .fatal_deprecation(Deprecation::SlashDiv)
+        .quiet_deps(true);
+
+    assert_eq!(
+        "b {\n  color: 0.5;\n}\n",
+        &grass::from_string(input.to_owned(), &options).unwrap()
+    );
+}
+
+#[test]
+fn quiet_deps_does_not_silence_relative_files() {
+    let mut fs = TestFs::new();
+    fs.add_file("_a.scss", "$a: (1 / 2);");
+
+    let input = "@use 'a';\nb {\n  color: a.$a;\n}\n";
+
+    let options = grass::Options::default()
+        .fs(&fs)
+        .load_path("lib")
+        .fatal_deprecation(Deprecation::SlashDiv)
+        .quiet_deps(true);
+
+    assert!(grass::from_string(input.to_owned(), &options).is_err());
+}
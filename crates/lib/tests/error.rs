@@ -277,3 +277,40 @@ error!(
     nothing_after_bang_in_space_separated_list,
     "a { color: a !", r#"Error: Expected "important"."#
 );
+
+#[test]
+fn error_css_displays_message_and_location() {
+    let err = grass::from_string(
+        "a {\n  color: 1 + red;\n}\n".to_owned(),
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        "/* Error: Undefined operation \"1 + red\".\n *   ,\n * 2 |   color: 1 + red;\n *   |          ^^^^^^^\n *   '\n *   stdin 2:10  root stylesheet */\n\nbody::before {\n  font-family: \"Source Code Pro\", \"SF Mono\", Monaco, Inconsolata, \"Fira Mono\",\n      \"Droid Sans Mono\", monospace, monospace;\n  white-space: pre;\n  display: block;\n  padding: 1em;\n  margin-bottom: 1em;\n  border-bottom: 2px solid black;\n  content: 'Error: Undefined operation \"1 + red\".\\a   \\2577 \\a 2 \\2502    color: 1 + red;\\a   \\2502           ^^^^^^^\\a   \\2575 \\a./stdin:2:10';\n}\n",
+        err.to_css_string()
+    );
+}
+
+#[test]
+fn error_css_does_not_close_comment_early() {
+    let err = grass::from_string(
+        "@error \"*/\";".to_owned(),
+        &grass::Options::default().unicode_error_messages(false),
+    )
+    .unwrap_err();
+
+    assert!(err.to_css_string().starts_with("/* Error: \"*∕\"\n"));
+}
+
+#[test]
+fn from_string_with_path_reports_file_name() {
+    let err = grass::from_string_with_path(
+        "a {\n  color: 1 + red;\n}\n",
+        "src/input.scss",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+
+    assert!(err.to_string().ends_with("./src/input.scss:2:10\n"));
+}
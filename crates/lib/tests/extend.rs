@@ -1936,7 +1936,6 @@ error!(
     "Error: Parent selectors aren't allowed here."
 );
 error!(
-    #[ignore = "we do not currently respect this"]
     extend_across_media_boundary,
     "a {
         display: none;
@@ -1950,13 +1949,71 @@ error!(
     "Error: You may not @extend selectors across media queries."
 );
 error!(
-    #[ignore = "we do not error for this"]
     extend_target_does_not_exist,
     "a {
         @extend dne;
     }",
     "Error: The target selector was not found."
 );
+error!(
+    extend_target_does_not_exist_within_media,
+    "@media screen {
+        a {
+            @extend dne;
+        }
+    }",
+    "Error: The target selector was not found."
+);
+error!(
+    extend_target_only_optional_in_other_rule,
+    "a {
+        @extend dne !optional;
+    }
+
+    b {
+        @extend dne;
+    }",
+    "Error: The target selector was not found."
+);
+error!(
+    extend_across_media_boundary_target_defined_later,
+    "@media screen {
+        a {
+            @extend b;
+        }
+    }
+
+    b {
+        color: red;
+    }",
+    "Error: You may not @extend selectors across media queries."
+);
+test!(
+    extend_within_media_target_defined_later,
+    "@media screen {
+        a {
+            @extend b;
+        }
+
+        b {
+            color: red;
+        }
+    }",
+    "@media screen {\n  b, a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    extend_without_media_applies_within_media,
+    "@media screen {
+        b {
+            color: red;
+        }
+    }
+
+    a {
+        @extend b;
+    }",
+    "@media screen {\n  b, a {\n    color: red;\n  }\n}\n"
+);
 error!(
     #[ignore = "crash"]
     extends_self_is_has_invalid_combinator,
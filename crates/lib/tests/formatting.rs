@@ -0,0 +1,97 @@
+#[macro_use]
+mod macros;
+
+test!(
+    nested_simple_rule,
+    "a {\n  color: red;\n  background: blue;\n}\n",
+    "a {\n  color: red;\n  background: blue; }\n",
+    grass::Options::default().style(grass::OutputStyle::Nested)
+);
+test!(
+    nested_indents_rules_within_parent_with_declarations,
+    "a {\n  color: red;\n  b {\n    color: blue;\n    c {\n      d: e;\n    }\n  }\n}\n",
+    "a {\n  color: red; }\n  a b {\n    color: blue; }\n    a b c {\n      d: e; }\n",
+    grass::Options::default().style(grass::OutputStyle::Nested)
+);
+test!(
+    nested_does_not_indent_rules_within_parent_without_declarations,
+    "a {\n  b {\n    color: red;\n  }\n}\n",
+    "a b {\n  color: red; }\n",
+    grass::Options::default().style(grass::OutputStyle::Nested)
+);
+test!(
+    nested_declarations_after_child_rule,
+    "a {\n  b {\n    color: red;\n  }\n  color: blue;\n}\n",
+    "a {\n  color: blue; }\n  a b {\n    color: red; }\n",
+    grass::Options::default().style(grass::OutputStyle::Nested)
+);
+test!(
+    nested_media_rule,
+    "@media screen {\n  a {\n    color: red;\n  }\n}\n",
+    "@media screen {\n  a {\n    color: red; } }\n",
+    grass::Options::default().style(grass::OutputStyle::Nested)
+);
+test!(
+    nested_separates_groups,
+    "a {\n  color: red;\n}\nb {\n  color: blue;\n}\n",
+    "a {\n  color: red; }\n\nb {\n  color: blue; }\n",
+    grass::Options::default().style(grass::OutputStyle::Nested)
+);
+test!(
+    compact_simple_rule,
+    "a {\n  color: red;\n  background: blue;\n}\n",
+    "a { color: red; background: blue; }\n",
+    grass::Options::default().style(grass::OutputStyle::Compact)
+);
+test!(
+    compact_nested_rules,
+    "a {\n  color: red;\n  b {\n    color: blue;\n  }\n}\nc {\n  d: e;\n}\n",
+    "a { color: red; }\na b { color: blue; }\n\nc { d: e; }\n",
+    grass::Options::default().style(grass::OutputStyle::Compact)
+);
+test!(
+    compact_media_rule,
+    "@media screen {\n  a {\n    color: red;\n  }\n  b {\n    color: blue;\n  }\n}\n",
+    "@media screen { a { color: red; }\n  b { color: blue; } }\n",
+    grass::Options::default().style(grass::OutputStyle::Compact)
+);
+test!(
+    compact_selector_with_newline,
+    "a,\nb {\n  color: red;\n}\n",
+    "a, b { color: red; }\n",
+    grass::Options::default().style(grass::OutputStyle::Compact)
+);
+test!(
+    indent_width,
+    "@media screen {\n  a {\n    color: red;\n  }\n}\n",
+    "@media screen {\n    a {\n        color: red;\n    }\n}\n",
+    grass::Options::default().indent_width(4)
+);
+test!(
+    indent_with_tabs,
+    "@media screen {\n  a {\n    color: red;\n  }\n}\n",
+    "@media screen {\n\ta {\n\t\tcolor: red;\n\t}\n}\n",
+    grass::Options::default()
+        .indent_type(grass::IndentType::Tab)
+        .indent_width(1)
+);
+test!(
+    linefeed_crlf,
+    "a {\n  color: red;\n}\nb {\n  color: blue;\n}\n",
+    "a {\r\n  color: red;\r\n}\r\n\r\nb {\r\n  color: blue;\r\n}\r\n",
+    grass::Options::default().linefeed(grass::LineFeed::CrLf)
+);
+test!(
+    linefeed_applies_to_charset,
+    "a {\n  color: \"👭\";\n}\n",
+    "@charset \"UTF-8\";\r\na {\r\n  color: \"👭\";\r\n}\r\n",
+    grass::Options::default().linefeed(grass::LineFeed::CrLf)
+);
+test!(
+    linefeed_does_not_affect_compressed,
+    "a {\n  color: red;\n}\n",
+    "a{color:red}",
+    grass::Options::default()
+        .style(grass::OutputStyle::Compressed)
+        .linefeed(grass::LineFeed::CrLf)
+);
@@ -426,4 +426,80 @@ error!(
     "Error: Function finished without @return."
 );
 
+#[cfg(feature = "commandline")]
+mod custom_fns {
+    use grass_compiler::{
+        sass_value::{ArgumentResult, ListSeparator, Value},
+        Builtin, Result, Visitor,
+    };
+
+    fn list_of_args(mut args: ArgumentResult, _: &mut Visitor) -> Result<Value> {
+        let mut values = Vec::new();
+        while let Some(value) = args.get_positional(values.len()) {
+            values.push(value.node);
+        }
+        Ok(Value::List(
+            values,
+            ListSeparator::Comma,
+            grass_compiler::sass_value::Brackets::Bracketed,
+        ))
+    }
+
+    fn compile(input: &str, signature: &str) -> Result<String> {
+        grass::from_string(
+            input.to_owned(),
+            &grass::Options::default()
+                .add_custom_fn("args", Builtin::with_signature(signature, list_of_args)),
+        )
+    }
+
+    #[test]
+    fn signature_binds_named_and_default_arguments() {
+        assert_eq!(
+            "a {\n  color: [1, 3, 2];\n}\n",
+            compile("a { color: args(1, $c: 2); }", "($a, $b: $a + 2, $c: 0)").unwrap()
+        );
+    }
+
+    #[test]
+    fn signature_rest_argument() {
+        assert_eq!(
+            "a {\n  color: 2;\n}\n",
+            compile(
+                "a { color: length(nth(args(1, 2, 3), 2)); }",
+                "($a, $rest...)"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn signature_missing_argument() {
+        assert!(compile("a { color: args(); }", "($a)")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Error: Missing argument $a."));
+    }
+
+    #[test]
+    fn closure_captures_state() {
+        let prefix = String::from("foo");
+        let builtin = Builtin::from_closure(move |_, _| {
+            Ok(Value::String(
+                prefix.clone(),
+                grass_compiler::sass_value::QuoteKind::None,
+            ))
+        });
+
+        assert_eq!(
+            "a {\n  color: foo;\n}\n",
+            grass::from_string(
+                "a { color: prefix(); }".to_owned(),
+                &grass::Options::default().add_custom_fn("prefix", builtin),
+            )
+            .unwrap()
+        );
+    }
+}
+
 // todo: return inside if, return inside while, return inside for
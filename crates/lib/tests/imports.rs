@@ -43,6 +43,30 @@ fn imports_variable() {
     );
 }
 
+#[test]
+fn from_string_with_path_resolves_relative_imports() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("src/_a.scss", r#"$a: red;"#);
+
+    let input = r#"
+        @import "a";
+        a {
+            color: $a;
+        }
+    "#;
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &grass::from_string_with_path(
+            input.to_string(),
+            "src/input.scss",
+            &grass::Options::default().fs(&fs)
+        )
+        .expect(input)
+    );
+}
+
 #[test]
 #[ignore = "we don't actually check if the semicolon exists"]
 fn import_no_semicolon() {
@@ -0,0 +1,159 @@
+#[macro_use]
+mod macros;
+
+test!(
+    round_single_number,
+    "a {\n  color: round(10.6px);\n}\n",
+    "a {\n  color: 11px;\n}\n"
+);
+test!(
+    round_with_step,
+    "a {\n  color: round(12px, 5px);\n}\n",
+    "a {\n  color: 10px;\n}\n"
+);
+test!(
+    round_up_with_step,
+    "a {\n  color: round(up, 10.1px, 5px);\n}\n",
+    "a {\n  color: 15px;\n}\n"
+);
+test!(
+    round_to_zero_negative,
+    "a {\n  color: round(to-zero, -7.5, 2);\n}\n",
+    "a {\n  color: -6;\n}\n"
+);
+test!(
+    round_preserves_var,
+    "a {\n  color: round(up, var(--x), 2px);\n}\n",
+    "a {\n  color: round(up, var(--x), 2px);\n}\n"
+);
+test!(
+    mod_same_units,
+    "a {\n  color: mod(7px, 3px);\n}\n",
+    "a {\n  color: 1px;\n}\n"
+);
+test!(
+    mod_negative_dividend,
+    "a {\n  color: mod(-7, 3);\n}\n",
+    "a {\n  color: 2;\n}\n"
+);
+test!(
+    rem_negative_dividend,
+    "a {\n  color: rem(-7, 3);\n}\n",
+    "a {\n  color: -1;\n}\n"
+);
+test!(
+    mod_compatible_units,
+    "a {\n  color: mod(1in, 5px);\n}\n",
+    "a {\n  color: 0.0104166667in;\n}\n"
+);
+test!(
+    sin_deg,
+    "a {\n  color: sin(90deg);\n}\n",
+    "a {\n  color: 1;\n}\n"
+);
+test!(
+    cos_preserves_var,
+    "a {\n  color: cos(var(--a));\n}\n",
+    "a {\n  color: cos(var(--a));\n}\n"
+);
+test!(
+    asin_returns_deg,
+    "a {\n  color: asin(1);\n}\n",
+    "a {\n  color: 90deg;\n}\n"
+);
+test!(
+    atan2_lengths,
+    "a {\n  color: atan2(1px, -1px);\n}\n",
+    "a {\n  color: 135deg;\n}\n"
+);
+test!(
+    pow_unitless,
+    "a {\n  color: pow(2, 10);\n}\n",
+    "a {\n  color: 1024;\n}\n"
+);
+test!(
+    sqrt_unitless,
+    "a {\n  color: sqrt(16);\n}\n",
+    "a {\n  color: 4;\n}\n"
+);
+test!(
+    hypot_lengths,
+    "a {\n  color: hypot(3px, 4px);\n}\n",
+    "a {\n  color: 5px;\n}\n"
+);
+test!(
+    hypot_percent_is_preserved,
+    "a {\n  color: hypot(3px, 4%);\n}\n",
+    "a {\n  color: hypot(3px, 4%);\n}\n"
+);
+test!(
+    log_with_base,
+    "a {\n  color: log(8, 2);\n}\n",
+    "a {\n  color: 3;\n}\n"
+);
+test!(
+    exp_zero,
+    "a {\n  color: exp(0);\n}\n",
+    "a {\n  color: 1;\n}\n"
+);
+test!(
+    abs_preserves_var,
+    "a {\n  color: abs(var(--x));\n}\n",
+    "a {\n  color: abs(var(--x));\n}\n"
+);
+test!(
+    sign_keeps_unit,
+    "a {\n  color: sign(-5px);\n}\n",
+    "a {\n  color: -1px;\n}\n"
+);
+test!(
+    sign_percent_is_preserved,
+    "a {\n  color: sign(10%);\n}\n",
+    "a {\n  color: sign(10%);\n}\n"
+);
+test!(
+    nested_in_calc,
+    "a {\n  color: calc(1px + sin(var(--a)) * 2px);\n}\n",
+    "a {\n  color: calc(1px + sin(var(--a)) * 2px);\n}\n"
+);
+test!(
+    calc_name,
+    "@use \"sass:meta\";\na {\n  color: meta.calc-name(sign(10%));\n}\n",
+    "a {\n  color: \"sign\";\n}\n"
+);
+test!(
+    calc_args_with_strategy,
+    "@use \"sass:meta\";\na {\n  color: meta.calc-args(round(up, var(--x), 2px));\n}\n",
+    "a {\n  color: up, var(--x), 2px;\n}\n"
+);
+test!(
+    user_defined_function_shadows_math_function,
+    "@function sin($x) {\n  @return foo;\n}\na {\n  color: sin(2px);\n}\n",
+    "a {\n  color: foo;\n}\n"
+);
+test!(
+    use_as_star_shadows_math_function,
+    "@use \"sass:math\" as *;\na {\n  color: pow(2, 3);\n}\n",
+    "a {\n  color: 8;\n}\n"
+);
+error!(
+    sqrt_with_units,
+    "a {\n  color: sqrt(2px);\n}\n", "Error: Expected 2px to have no units."
+);
+error!(
+    sin_non_angle_unit,
+    "a {\n  color: sin(2px);\n}\n",
+    "Error: Expected 2px to have an angle unit (deg, grad, rad, turn)."
+);
+error!(
+    round_strategy_without_step,
+    "a {\n  color: round(up, 2px);\n}\n", "Error: If strategy is not null, step is required."
+);
+error!(
+    mod_incompatible_units,
+    "a {\n  color: mod(1px, 2s);\n}\n", "Error: 1px and 2s are incompatible."
+);
+error!(
+    atan2_too_few_arguments,
+    "a {\n  color: atan2(1px);\n}\n", "Error: 2 arguments required, but only 1 was passed."
+);
@@ -0,0 +1,114 @@
+#[macro_use]
+mod macros;
+
+test!(
+    safe_merges_media_queries_without_conflicts,
+    "a {\n  color: red;\n  @media (min-width: 100px) {\n    color: blue;\n  }\n}\nb {\n  margin: 0;\n  @media (min-width: 100px) {\n    padding: 1px;\n  }\n}\n",
+    "a{color:red}@media (min-width: 100px){a{color:blue}b{padding:1px}}b{margin:0}",
+    grass::Options::default()
+        .group_media_queries(grass::MediaQueryGrouping::Safe)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    safe_does_not_merge_when_intervening_rule_sets_same_property,
+    "a {\n  @media (min-width: 100px) {\n    color: blue;\n  }\n}\nb {\n  color: red;\n  @media (min-width: 100px) {\n    color: green;\n  }\n}\n",
+    "@media (min-width: 100px){a{color:blue}}b{color:red}@media (min-width: 100px){b{color:green}}",
+    grass::Options::default()
+        .group_media_queries(grass::MediaQueryGrouping::Safe)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    safe_does_not_merge_when_intervening_shorthand_conflicts,
+    "a {\n  @media (min-width: 100px) {\n    color: blue;\n  }\n}\nb {\n  margin: 0;\n  @media (min-width: 100px) {\n    margin-top: 1px;\n  }\n}\n",
+    "@media (min-width: 100px){a{color:blue}}b{margin:0}@media (min-width: 100px){b{margin-top:1px}}",
+    grass::Options::default()
+        .group_media_queries(grass::MediaQueryGrouping::Safe)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    safe_merges_when_specificities_differ,
+    "a {\n  @media (min-width: 100px) {\n    color: blue;\n  }\n}\n#b {\n  color: red;\n  @media (min-width: 100px) {\n    .c {\n      color: green;\n    }\n  }\n}\n",
+    "@media (min-width: 100px){a{color:blue}#b .c{color:green}}#b{color:red}",
+    grass::Options::default()
+        .group_media_queries(grass::MediaQueryGrouping::Safe)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    safe_does_not_merge_different_queries,
+    "@media (min-width: 100px) {\n  a {\n    color: red;\n  }\n}\n@media (min-width: 200px) {\n  a {\n    color: blue;\n  }\n}\n",
+    "@media (min-width: 100px){a{color:red}}@media (min-width: 200px){a{color:blue}}",
+    grass::Options::default()
+        .group_media_queries(grass::MediaQueryGrouping::Safe)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    aggressive_hoists_media_queries_to_end,
+    "a {\n  color: red;\n  @media (min-width: 100px) {\n    color: blue;\n  }\n}\nb {\n  color: red;\n  @media (min-width: 100px) {\n    color: green;\n  }\n}\n",
+    "a {\n  color: red;\n}\n\nb {\n  color: red;\n}\n\n@media (min-width: 100px) {\n  a {\n    color: blue;\n  }\n  b {\n    color: green;\n  }\n}\n",
+    grass::Options::default().group_media_queries(grass::MediaQueryGrouping::Aggressive)
+);
+test!(
+    groups_media_queries_within_supports,
+    "@supports (display: grid) {\n  a {\n    @media (min-width: 100px) {\n      color: blue;\n    }\n  }\n  b {\n    @media (min-width: 100px) {\n      color: green;\n    }\n  }\n}\n",
+    "@supports (display: grid){@media (min-width: 100px){a{color:blue}b{color:green}}}",
+    grass::Options::default()
+        .group_media_queries(grass::MediaQueryGrouping::Safe)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    media_queries_not_grouped_by_default,
+    "a {\n  @media (min-width: 100px) {\n    color: blue;\n  }\n}\nb {\n  @media (min-width: 100px) {\n    color: green;\n  }\n}\n",
+    "@media (min-width: 100px){a{color:blue}}@media (min-width: 100px){b{color:green}}",
+    grass::Options::default().style(grass::OutputStyle::Compressed)
+);
+
+#[test]
+fn safe_does_not_move_shorthand_before_its_longhands() {
+    for (shorthand, longhand) in [
+        ("border-radius: 3px", "border-top-left-radius: 2px"),
+        ("border: 1px solid", "border-block-start-color: red"),
+        ("inset: 1px", "top: 0"),
+        ("inset-inline: 1px", "left: 0"),
+        ("font: 12px serif", "line-height: 2"),
+        ("gap: 1px", "row-gap: 2px"),
+        ("place-items: center", "align-items: start"),
+        ("margin-inline-start: 1px", "margin-left: 2px"),
+        ("inline-size: 1px", "width: 2px"),
+        ("columns: 2", "column-width: 10px"),
+        ("columns: 2", "column-count: 3"),
+        ("white-space: nowrap", "text-wrap: balance"),
+        ("white-space: pre", "white-space-collapse: collapse"),
+    ] {
+        let input = format!(
+            "@media (min-width: 100px) {{\n  a {{\n    color: red;\n  }}\n}}\n.b {{\n  {longhand};\n}}\n@media (min-width: 100px) {{\n  .b {{\n    {shorthand};\n  }}\n}}\n",
+            shorthand = shorthand,
+            longhand = longhand
+        );
+
+        let css = grass::from_string(
+            input,
+            &grass::Options::default()
+                .group_media_queries(grass::MediaQueryGrouping::Safe)
+                .style(grass::OutputStyle::Compressed),
+        )
+        .unwrap();
+
+        assert_eq!(
+            css,
+            format!(
+                "@media (min-width: 100px){{a{{color:red}}}}.b{{{longhand}}}@media (min-width: 100px){{.b{{{shorthand}}}}}",
+                shorthand = shorthand.replace(": ", ":"),
+                longhand = longhand.replace(": ", ":")
+            )
+        );
+    }
+}
+
+test!(
+    safe_merges_properties_with_shared_prefix_but_no_shared_longhands,
+    "a {\n  @media (min-width: 100px) {\n    border-radius: 1px;\n  }\n}\nb {\n  border-width: 0;\n  @media (min-width: 100px) {\n    color: red;\n  }\n}\n",
+    "@media (min-width: 100px){a{border-radius:1px}b{color:red}}b{border-width:0}",
+    grass::Options::default()
+        .group_media_queries(grass::MediaQueryGrouping::Safe)
+        .style(grass::OutputStyle::Compressed)
+);
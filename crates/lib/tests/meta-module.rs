@@ -72,3 +72,130 @@ fn load_css_non_map_with() {
     let input = "@use \"sass:meta\";\na {\n @include meta.load-css(foo, 2);\n}";
     assert_err!("Error: $with: 2 is not a map.", input);
 }
+
+#[test]
+fn load_css_with_configures_variable() {
+    let input = "@use \"sass:meta\";\na {\n @include meta.load-css(load_css_with_configures_variable, $with: (color: blue));\n}";
+    tempfile!(
+        "load_css_with_configures_variable.scss",
+        "$color: red !default;\nb { color: $color; }"
+    );
+    assert_eq!(
+        "a b {\n  color: blue;\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default()).expect(input)
+    );
+}
+
+#[test]
+fn load_css_emits_module_once() {
+    let input = "@use \"sass:meta\";\na {\n @include meta.load-css(load_css_emits_module_once);\n}\nb {\n @include meta.load-css(load_css_emits_module_once);\n}";
+    tempfile!("load_css_emits_module_once.scss", "c { color: red; }");
+    assert_eq!(
+        "a c {\n  color: red;\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default()).expect(input)
+    );
+}
+
+#[test]
+fn load_css_nested_media() {
+    let input = "@use \"sass:meta\";\na {\n @include meta.load-css(load_css_nested_media);\n}";
+    tempfile!(
+        "load_css_nested_media.scss",
+        "@media print { b { color: red; } }"
+    );
+    assert_eq!(
+        "@media print {\n  a b {\n    color: red;\n  }\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default()).expect(input)
+    );
+}
+
+#[test]
+fn load_css_with_unused_variable() {
+    let input = "@use \"sass:meta\";\n@include meta.load-css(load_css_with_unused_variable, $with: (foo: blue));";
+    tempfile!("load_css_with_unused_variable.scss", "a { color: red; }");
+    assert_err!(
+        "Error: $foo was not declared with !default in the @used module.",
+        input
+    );
+}
+
+#[test]
+fn load_css_with_variable_configured_twice() {
+    let input = "@use \"sass:meta\";\n@include meta.load-css(foo, $with: (a-b: 1, a_b: 2));";
+    assert_err!("Error: The variable $a-b was configured twice.", input);
+}
+
+#[test]
+fn load_css_with_builtin_module() {
+    let input = "@use \"sass:meta\";\n@include meta.load-css(\"sass:color\", $with: (a: 1));";
+    assert_err!(
+        "Error: Built-in module sass:color can't be configured.",
+        input
+    );
+}
+
+test!(
+    get_mixin_inspect,
+    "@use \"sass:meta\";\n@mixin foo {}\na {\n  color: meta.inspect(meta.get-mixin(\"foo\"));\n}\n",
+    "a {\n  color: get-mixin(\"foo\");\n}\n"
+);
+test!(
+    get_mixin_type_of,
+    "@use \"sass:meta\";\n@mixin foo {}\na {\n  color: meta.type-of(meta.get-mixin(\"foo\"));\n}\n",
+    "a {\n  color: mixin;\n}\n"
+);
+test!(
+    get_mixin_equality,
+    "@use \"sass:meta\";\n@mixin foo {}\n@mixin bar {}\na {\n  color: meta.get-mixin(\"foo\") == meta.get-mixin(\"foo\") meta.get-mixin(\"foo\") == meta.get-mixin(\"bar\");\n}\n",
+    "a {\n  color: true false;\n}\n"
+);
+test!(
+    get_mixin_from_module,
+    "@use \"sass:meta\";\na {\n  color: meta.inspect(meta.get-mixin(\"load-css\", \"meta\"));\n}\n",
+    "a {\n  color: get-mixin(\"load-css\");\n}\n"
+);
+test!(
+    apply_with_args,
+    "@use \"sass:meta\";\n@mixin foo($a, $b: 2) {\n  a: $a;\n  b: $b;\n}\na {\n  @include meta.apply(meta.get-mixin(\"foo\"), 1, $b: 3);\n}\n",
+    "a {\n  a: 1;\n  b: 3;\n}\n"
+);
+test!(
+    apply_forwards_content,
+    "@use \"sass:meta\";\n@mixin foo {\n  b {\n    @content;\n  }\n}\na {\n  @include meta.apply(meta.get-mixin(\"foo\")) {\n    color: red;\n  }\n}\n",
+    "a b {\n  color: red;\n}\n"
+);
+test!(
+    apply_mixin_passed_as_argument,
+    "@use \"sass:meta\";\n@mixin red {\n  color: red;\n}\n@mixin component($theme) {\n  @include meta.apply($theme);\n}\na {\n  @include component(meta.get-mixin(\"red\"));\n}\n",
+    "a {\n  color: red;\n}\n"
+);
+test!(
+    accepts_content,
+    "@use \"sass:meta\";\n@mixin foo {}\n@mixin bar {\n  @content;\n}\na {\n  color: meta.accepts-content(meta.get-mixin(\"foo\")) meta.accepts-content(meta.get-mixin(\"bar\"));\n}\n",
+    "a {\n  color: false true;\n}\n"
+);
+test!(
+    module_mixins,
+    "@use \"sass:meta\";\n@use \"sass:map\";\na {\n  color: map.has-key(meta.module-mixins(\"meta\"), \"apply\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+error!(
+    get_mixin_not_found,
+    "@use \"sass:meta\";\na {\n  color: meta.get-mixin(\"foo\");\n}\n",
+    "Error: Mixin not found: foo"
+);
+error!(
+    mixin_ref_is_not_css,
+    "@use \"sass:meta\";\n@mixin foo {}\na {\n  color: meta.get-mixin(\"foo\");\n}\n",
+    "Error: get-mixin(\"foo\") isn't a valid CSS value."
+);
+error!(
+    apply_content_to_mixin_without_content,
+    "@use \"sass:meta\";\n@mixin foo {}\na {\n  @include meta.apply(meta.get-mixin(\"foo\")) {\n    color: red;\n  }\n}\n",
+    "Error: Mixin doesn't accept a content block."
+);
+error!(
+    apply_non_mixin,
+    "@use \"sass:meta\";\na {\n  @include meta.apply(1);\n}\n",
+    "Error: $mixin: 1 is not a mixin reference."
+);
@@ -0,0 +1,169 @@
+#[macro_use]
+mod macros;
+
+test!(
+    merges_adjacent_rules_with_same_selector,
+    "a {\n  color: red;\n}\na {\n  background: blue;\n}\n",
+    "a{color:red;background:blue}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    merges_adjacent_rules_with_same_declarations,
+    "a {\n  color: red;\n}\nb {\n  color: red;\n}\n",
+    "a,b{color:red}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    does_not_merge_rules_that_are_not_adjacent,
+    "a {\n  color: red;\n}\nb {\n  color: blue;\n}\na {\n  background: red;\n}\n",
+    "a{color:red}b{color:blue}a{background:red}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    does_not_merge_vendor_prefixed_selectors,
+    "a::-moz-selection {\n  color: red;\n}\na::selection {\n  color: red;\n}\n",
+    "a::-moz-selection{color:red}a::selection{color:red}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    does_not_merge_newer_pseudo_class_selectors,
+    "a:focus-visible {\n  color: red;\n}\nb {\n  color: red;\n}\nc:has(> img) {\n  color: red;\n}\nd {\n  color: red;\n}\n",
+    "a:focus-visible{color:red}b{color:red}c:has(>img){color:red}d{color:red}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    does_not_merge_newer_selector_arguments,
+    "a:is(b, c) {\n  color: red;\n}\nd {\n  color: red;\n}\ne:not(f, g) {\n  color: red;\n}\nh {\n  color: red;\n}\n",
+    "a:is(b,c){color:red}d{color:red}e:not(f,g){color:red}h{color:red}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    merges_long_supported_selectors,
+    "a:hover > b::before {\n  color: red;\n}\nc:nth-child(2n+1) {\n  color: red;\n}\n[d=e]:not(.f) {\n  color: red;\n}\n",
+    "a:hover>b::before,c:nth-child(2n+1),[d=e]:not(.f){color:red}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    removes_overridden_declaration,
+    "a {\n  color: red;\n  margin: 0;\n  color: blue;\n}\n",
+    "a{margin:0;color:blue}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    keeps_fallback_declarations,
+    "a {\n  display: -webkit-box;\n  display: flex;\n  width: 10px;\n  width: calc(100% - 10px);\n}\n",
+    "a{display:-webkit-box;display:flex;width:10px;width:calc(100% - 10px)}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    keeps_viewport_unit_fallback,
+    "a {\n  height: 100vh;\n  height: 100dvh;\n}\n",
+    "a{height:100vh;height:100dvh}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    keeps_display_keyword_fallback,
+    "a {\n  display: block;\n  display: flow-root;\n}\n",
+    "a{display:block;display:flow-root}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    keeps_position_keyword_fallback,
+    "a {\n  position: relative;\n  position: sticky;\n}\n",
+    "a{position:relative;position:sticky}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    removes_declaration_overridden_by_supported_value,
+    "a {\n  height: 100dvh;\n  height: 100px;\n  display: flow-root;\n  display: block;\n}\n",
+    "a{height:100px;display:block}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    removes_declaration_with_identical_value,
+    "a {\n  position: sticky;\n  color: red;\n  position: sticky;\n}\n",
+    "a{color:red;position:sticky}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    keeps_important_declaration_followed_by_normal,
+    "a {\n  color: red !important;\n  color: blue;\n}\n",
+    "a{color:red !important;color:blue}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    removes_overridden_custom_property,
+    "a {\n  --foo: bar(1);\n  --foo: baz;\n}\n",
+    "a{--foo: baz}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
+test!(
+    drops_empty_at_rules,
+    "@font-face {}\n@layer foo {}\na {\n  color: red;\n}\n",
+    "@layer foo {}\na {\n  color: red;\n}\n",
+    grass::Options::default().minify(true)
+);
+test!(
+    shortens_colors,
+    "a {\n  color: #ffffff;\n  background: #ff0000;\n  border-color: white;\n}\n",
+    "a {\n  color: #fff;\n  background: red;\n  border-color: #fff;\n}\n",
+    grass::Options::default().minify(true)
+);
+test!(
+    shortens_zero_lengths,
+    "a {\n  margin: 0px 0em 1px;\n  width: calc(0px + 1%);\n  flex: 1 1 0px;\n}\n",
+    "a {\n  margin: 0 0 1px;\n  width: calc(0px + 1%);\n  flex: 1 1 0px;\n}\n",
+    grass::Options::default().minify(true)
+);
+test!(
+    shortens_font_weight,
+    "a {\n  font-weight: bold;\n}\nb {\n  font-weight: normal;\n}\n",
+    "a {\n  font-weight: 700;\n}\n\nb {\n  font-weight: 400;\n}\n",
+    grass::Options::default().minify(true)
+);
+test!(
+    preserves_loud_comments,
+    "/* removed */\n/*! kept */\na {\n  /* removed */\n  color: red;\n}\n",
+    "/*! kept */\na {\n  color: red;\n}\n",
+    grass::Options::default().minify(true)
+);
+test!(
+    minifies_within_media,
+    "@media screen {\n  a {\n    color: red;\n  }\n  a {\n    color: blue;\n  }\n}\n",
+    "@media screen{a{color:blue}}",
+    grass::Options::default()
+        .minify(true)
+        .style(grass::OutputStyle::Compressed)
+);
@@ -0,0 +1,89 @@
+#[macro_use]
+mod macros;
+
+test!(
+    nests_descendant_rule,
+    "a {\n  color: red;\n  b {\n    color: blue;\n  }\n}\n",
+    "a {\n  color: red;\n  b {\n    color: blue;\n  }\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    nests_rules_with_parent_selector,
+    "a {\n  &:hover {\n    color: red;\n  }\n  .b & {\n    color: blue;\n  }\n}\n",
+    "a {\n  &:hover {\n    color: red;\n  }\n  .b & {\n    color: blue;\n  }\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    nests_rule_with_leading_combinator,
+    "a {\n  > b {\n    color: red;\n  }\n}\n",
+    "a {\n  > b {\n    color: red;\n  }\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    nests_multiple_levels,
+    "a {\n  b {\n    c {\n      color: red;\n    }\n  }\n}\n",
+    "a {\n  b {\n    c {\n      color: red;\n    }\n  }\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    declarations_are_emitted_before_nested_rules,
+    "a {\n  b {\n    color: red;\n  }\n  color: blue;\n}\n",
+    "a {\n  color: blue;\n  b {\n    color: red;\n  }\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    flattens_parent_selector_with_suffix,
+    "a {\n  &-b {\n    color: red;\n  }\n  c {\n    color: blue;\n  }\n}\n",
+    "a-b {\n  color: red;\n}\na c {\n  color: blue;\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    flattens_parent_with_pseudo_element,
+    "a::before {\n  b {\n    color: red;\n  }\n}\n",
+    "a::before b {\n  color: red;\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    flattens_parent_with_mixed_specificity,
+    "a, #b {\n  c {\n    color: red;\n  }\n}\n",
+    "a c, #b c {\n  color: red;\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    extending_parent_keeps_nesting,
+    "a {\n  b {\n    color: red;\n  }\n}\nc {\n  @extend a;\n}\n",
+    "a, c {\n  b {\n    color: red;\n  }\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    extending_nested_rule_flattens,
+    "a {\n  b {\n    color: red;\n  }\n}\nc {\n  @extend b;\n}\n",
+    "a b, a c {\n  color: red;\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    media_query_bubbles_and_preserves_order,
+    "a {\n  b {\n    color: red;\n  }\n  @media screen {\n    color: blue;\n  }\n  c {\n    color: green;\n  }\n}\n",
+    "a {\n  b {\n    color: red;\n  }\n}\n@media screen {\n  a {\n    color: blue;\n  }\n}\na c {\n  color: green;\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    at_root_preserves_order,
+    "a {\n  b {\n    color: red;\n  }\n  @at-root c {\n    color: blue;\n  }\n  d {\n    color: green;\n  }\n}\n",
+    "a {\n  b {\n    color: red;\n  }\n}\nc {\n  color: blue;\n}\n\na d {\n  color: green;\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    nests_within_media_query,
+    "@media screen {\n  a {\n    b {\n      color: red;\n    }\n  }\n}\n",
+    "@media screen {\n  a {\n    b {\n      color: red;\n    }\n  }\n}\n",
+    grass::Options::default().native_nesting(true)
+);
+test!(
+    compressed,
+    "a {\n  color: red;\n  b {\n    color: blue;\n  }\n}\n",
+    "a{color:red;b{color:blue}}",
+    grass::Options::default()
+        .native_nesting(true)
+        .style(grass::OutputStyle::Compressed)
+);
@@ -1,5 +1,7 @@
 use grass::InputSyntax;
 
+use macros::TestFs;
+
 #[macro_use]
 mod macros;
 
@@ -249,3 +251,118 @@ error!(
     "Error: Nested declarations aren't allowed in plain CSS.",
     grass::Options::default().input_syntax(InputSyntax::Css)
 );
+test!(
+    nested_style_rule_is_preserved,
+    "a {
+        color: red;
+        b {
+            color: blue;
+        }
+    }",
+    "a {\n  color: red;\n  b {\n    color: blue;\n  }\n}\n",
+    grass::Options::default().input_syntax(InputSyntax::Css)
+);
+test!(
+    nested_parent_selector_is_preserved,
+    "a {
+        &:hover {
+            color: red;
+        }
+    }",
+    "a {\n  &:hover {\n    color: red;\n  }\n}\n",
+    grass::Options::default().input_syntax(InputSyntax::Css)
+);
+test!(
+    nested_leading_combinator_is_preserved,
+    "a {
+        > b {
+            color: red;
+        }
+    }",
+    "a {\n  > b {\n    color: red;\n  }\n}\n",
+    grass::Options::default().input_syntax(InputSyntax::Css)
+);
+test!(
+    nested_style_rule_compressed,
+    "a {
+        color: red;
+        &:hover {
+            color: blue;
+        }
+    }",
+    "a{color:red;&:hover{color:blue}}",
+    grass::Options::default()
+        .input_syntax(InputSyntax::Css)
+        .style(grass::OutputStyle::Compressed)
+);
+error!(
+    nested_parent_selector_with_suffix,
+    "a {
+        &-b {
+            color: red;
+        }
+    }",
+    "Error: Parent selectors can't have suffixes in plain CSS.",
+    grass::Options::default().input_syntax(InputSyntax::Css)
+);
+
+#[test]
+fn use_nested_plain_css_is_extended() {
+    let mut fs = TestFs::new();
+
+    fs.add_file(
+        "vendor.css",
+        r#"
+        .a {
+            &:hover {
+                color: red;
+            }
+            .b {
+                color: blue;
+            }
+        }
+    "#,
+    );
+
+    let input = r#"
+        @use "vendor";
+
+        .c {
+            @extend .b;
+        }
+    "#;
+
+    assert_eq!(
+        ".a {\n  &:hover {\n    color: red;\n  }\n  .b, .c {\n    color: blue;\n  }\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default().fs(&fs)).expect(input)
+    );
+}
+
+#[test]
+fn load_css_nested_plain_css_keeps_parent_selectors() {
+    let mut fs = TestFs::new();
+
+    fs.add_file(
+        "vendor.css",
+        r#"
+        .a {
+            &:hover {
+                color: red;
+            }
+        }
+    "#,
+    );
+
+    let input = r#"
+        @use "sass:meta";
+
+        .p {
+            @include meta.load-css("vendor");
+        }
+    "#;
+
+    assert_eq!(
+        ".p .a {\n  &:hover {\n    color: red;\n  }\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default().fs(&fs)).expect(input)
+    );
+}
@@ -0,0 +1,99 @@
+use macros::TestFs;
+
+#[macro_use]
+mod macros;
+
+test!(
+    removes_rule_with_unused_class,
+    ".used {\n  color: red;\n}\n.unused {\n  color: blue;\n}\n",
+    ".used {\n  color: red;\n}\n",
+    grass::Options::default().purge_unused(&grass::UsedSelectors::new().classes(["used"]))
+);
+test!(
+    removes_unused_selectors_from_list,
+    ".used, .unused {\n  color: red;\n}\n",
+    ".used {\n  color: red;\n}\n",
+    grass::Options::default().purge_unused(&grass::UsedSelectors::new().classes(["used"]))
+);
+test!(
+    keeps_kinds_of_selectors_not_described,
+    "#main a {\n  color: red;\n}\n.unused {\n  color: blue;\n}\n",
+    "#main a {\n  color: red;\n}\n",
+    grass::Options::default().purge_unused(&grass::UsedSelectors::new().classes(["used"]))
+);
+test!(
+    removes_unused_ids_and_elements,
+    "#main {\n  color: red;\n}\n#other {\n  color: red;\n}\nDIV {\n  color: red;\n}\nspan {\n  color: red;\n}\n",
+    "#main {\n  color: red;\n}\n\nDIV {\n  color: red;\n}\n",
+    grass::Options::default().purge_unused(
+        &grass::UsedSelectors::new()
+            .ids(["main"])
+            .elements(["div"])
+    )
+);
+test!(
+    scans_content_for_names,
+    "nav a.link {\n  color: red;\n}\n#menu {\n  color: red;\n}\nfooter {\n  color: red;\n}\n.hover\\:underline {\n  color: red;\n}\n",
+    "nav a.link {\n  color: red;\n}\n\n#menu {\n  color: red;\n}\n\n.hover\\:underline {\n  color: red;\n}\n",
+    grass::Options::default().purge_unused(
+        &grass::UsedSelectors::new()
+            .content("<nav id=\"menu\"><a class=\"link hover:underline\">Home</a></nav>")
+    )
+);
+test!(
+    keeps_pseudo_selectors_and_attributes,
+    ".used:hover::before, [data-x], :not(.unused) {\n  color: red;\n}\n",
+    ".used:hover::before, [data-x], :not(.unused) {\n  color: red;\n}\n",
+    grass::Options::default().purge_unused(&grass::UsedSelectors::new().classes(["used"]))
+);
+test!(
+    checks_arguments_of_is,
+    ":is(.a, .b) {\n  color: red;\n}\n:is(.c, .d) {\n  color: red;\n}\n",
+    ":is(.a, .b) {\n  color: red;\n}\n",
+    grass::Options::default().purge_unused(&grass::UsedSelectors::new().classes(["b"]))
+);
+test!(
+    removes_emptied_media_and_supports,
+    "@media screen {\n  .unused {\n    color: red;\n  }\n}\n@supports (display: grid) {\n  .unused {\n    color: red;\n  }\n}\n.used {\n  color: red;\n}\n",
+    ".used {\n  color: red;\n}\n",
+    grass::Options::default().purge_unused(&grass::UsedSelectors::new().classes(["used"]))
+);
+test!(
+    removes_unused_keyframes,
+    "@keyframes spin {\n  to {\n    opacity: 0;\n  }\n}\n@keyframes fade {\n  to {\n    opacity: 0;\n  }\n}\n.used {\n  animation: fade 1s;\n}\n.unused {\n  animation-name: spin;\n}\n",
+    "@keyframes fade {\n  to {\n    opacity: 0;\n  }\n}\n.used {\n  animation: fade 1s;\n}\n",
+    grass::Options::default().purge_unused(&grass::UsedSelectors::new().classes(["used"]))
+);
+test!(
+    keeps_keyframes_when_animation_uses_var,
+    "@keyframes spin {\n  to {\n    opacity: 0;\n  }\n}\n.used {\n  animation: var(--animation);\n}\n",
+    "@keyframes spin {\n  to {\n    opacity: 0;\n  }\n}\n.used {\n  animation: var(--animation);\n}\n",
+    grass::Options::default().purge_unused(&grass::UsedSelectors::new().classes(["used"]))
+);
+test!(
+    safelist_patterns,
+    ".js-toggle {\n  color: red;\n}\n.is-open {\n  color: red;\n}\n.other {\n  color: red;\n}\n@keyframes pulse {\n  to {\n    opacity: 0;\n  }\n}\n",
+    ".js-toggle {\n  color: red;\n}\n\n.is-open {\n  color: red;\n}\n\n@keyframes pulse {\n  to {\n    opacity: 0;\n  }\n}\n",
+    grass::Options::default().purge_unused(
+        &grass::UsedSelectors::new()
+            .classes(["used"])
+            .safelist(["js-*", "is-open", "pulse"])
+    )
+);
+
+#[test]
+fn scans_content_paths() {
+    let mut fs = TestFs::new();
+    fs.add_file("index.html", "<p class=\"intro\">Hello</p>");
+
+    let used = grass::UsedSelectors::new().content_path("index.html");
+
+    assert_eq!(
+        grass::from_string(
+            "p.intro {\n  color: red;\n}\np.outro {\n  color: red;\n}\n",
+            &grass::Options::default().fs(&fs).purge_unused(&used)
+        )
+        .unwrap(),
+        "p.intro {\n  color: red;\n}\n"
+    );
+}
@@ -0,0 +1,105 @@
+#[macro_use]
+mod macros;
+
+test!(
+    swaps_directional_properties,
+    "a {\n  margin-left: 1px;\n  border-top-right-radius: 2px;\n}\n",
+    "a {\n  margin-right: 1px;\n  border-top-left-radius: 2px;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    swaps_directional_values,
+    "a {\n  float: left;\n  text-align: right;\n  transition: padding-left 1s;\n}\n",
+    "a {\n  float: right;\n  text-align: left;\n  transition: padding-right 1s;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    does_not_mirror_animation_names,
+    "a {\n  animation: slide-left 1s;\n  animation-name: left, to-right;\n}\n",
+    "a {\n  animation: slide-left 1s;\n  animation-name: left, to-right;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    does_not_mirror_grid_names,
+    "a {\n  grid-area: nav-left;\n  grid-template-areas: \"left main\";\n  grid-column: left-start / right-end;\n}\n",
+    "a {\n  grid-area: nav-left;\n  grid-template-areas: \"left main\";\n  grid-column: left-start/right-end;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    only_swaps_exact_keywords,
+    "a {\n  background-position: left-ish right;\n}\n",
+    "a {\n  background-position: left-ish left;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    swaps_four_value_shorthand,
+    "a {\n  padding: 1px 2px 3px 4px;\n  margin: 1px 2px 3px;\n}\n",
+    "a {\n  padding: 1px 4px 3px 2px;\n  margin: 1px 2px 3px;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    swaps_four_value_shorthand_with_important,
+    "a {\n  margin: 1px 2px 3px 4px !important;\n}\n",
+    "a {\n  margin: 1px 4px 3px 2px !important;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    swaps_border_radius_corners,
+    "a {\n  border-radius: 1px 2px 3px 4px;\n}\nb {\n  border-radius: 1px 2px 3px;\n}\n",
+    "a {\n  border-radius: 2px 1px 4px 3px;\n}\n\nb {\n  border-radius: 2px 1px 2px 3px;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    swaps_elliptical_border_radius_corners,
+    "a {\n  border-radius: 1px 2px / 3px 4px;\n}\n",
+    "a {\n  border-radius: 2px 1px / 4px 3px;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    negates_horizontal_translation,
+    "a {\n  transform: translateX(10px) translate(-5px, 2px) rotate(5deg);\n}\n",
+    "a {\n  transform: translateX(-10px) translate(5px, 2px) rotate(5deg);\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    does_not_mirror_quoted_content,
+    "a {\n  content: \"left\";\n  background: url(left.png) left top;\n}\n",
+    "a {\n  content: \"left\";\n  background: url(left.png) right top;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    ignore_directive_before_declaration,
+    "a {\n  /*rtl:ignore*/\n  left: 0;\n  right: 1px;\n}\n",
+    "a {\n  left: 0;\n  left: 1px;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    ignore_directive_before_rule,
+    "/*rtl:ignore*/\na {\n  left: 0;\n}\nb {\n  left: 0;\n}\n",
+    "a {\n  left: 0;\n}\n\nb {\n  right: 0;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    begin_and_end_ignore_directives,
+    "/*rtl:begin:ignore*/\na {\n  left: 0;\n}\nb {\n  left: 0;\n}\n/*rtl:end:ignore*/\nc {\n  left: 0;\n}\n",
+    "a {\n  left: 0;\n}\n\nb {\n  left: 0;\n}\n\nc {\n  right: 0;\n}\n",
+    grass::Options::default().rtl(true)
+);
+test!(
+    does_not_mirror_by_default,
+    "a {\n  margin-left: 1px;\n}\n",
+    "a {\n  margin-left: 1px;\n}\n",
+    grass::Options::default()
+);
+
+#[test]
+fn from_string_with_rtl_produces_both_stylesheets() {
+    let (css, rtl_css) = grass::from_string_with_rtl(
+        "a {\n  /*rtl:ignore*/\n  float: left;\n  margin-left: 1px;\n}\n",
+        &grass::Options::default().style(grass::OutputStyle::Compressed),
+    )
+    .unwrap();
+
+    assert_eq!(css, "a{float:left;margin-left:1px}");
+    assert_eq!(rtl_css, "a{float:left;margin-right:1px}");
+}
@@ -308,3 +308,48 @@ test!(
     ",
     "a {\n  color: \"aaa\";\n}\n"
 );
+test!(
+    string_split_basic,
+    "@use 'sass:string';\na {\n  color: string.split(\"a b c\", \" \");\n}\n",
+    "a {\n  color: [\"a\", \"b\", \"c\"];\n}\n"
+);
+test!(
+    string_split_unquoted,
+    "@use 'sass:string';\na {\n  color: string.split(a-b-c, \"-\");\n}\n",
+    "a {\n  color: [a, b, c];\n}\n"
+);
+test!(
+    string_split_limit,
+    "@use 'sass:string';\na {\n  color: string.split(\"a b c d\", \" \", 2);\n}\n",
+    "a {\n  color: [\"a\", \"b\", \"c d\"];\n}\n"
+);
+test!(
+    string_split_empty_separator_splits_code_points,
+    "@use 'sass:string';\na {\n  color: string.split(\"a😊b\", \"\");\n}\n",
+    "@charset \"UTF-8\";\na {\n  color: [\"a\", \"😊\", \"b\"];\n}\n"
+);
+test!(
+    string_split_empty_string,
+    "@use 'sass:string';\n@use 'sass:list';\na {\n  color: list.length(string.split(\"\", \",\"));\n}\n",
+    "a {\n  color: 0;\n}\n"
+);
+test!(
+    string_split_separator_not_found,
+    "@use 'sass:string';\na {\n  color: string.split(\"abc\", \",\");\n}\n",
+    "a {\n  color: [\"abc\"];\n}\n"
+);
+test!(
+    string_split_is_comma_separated,
+    "@use 'sass:string';\n@use 'sass:list';\na {\n  color: list.separator(string.split(\"a b\", \" \"));\n}\n",
+    "a {\n  color: comma;\n}\n"
+);
+error!(
+    string_split_limit_zero,
+    "@use 'sass:string';\na {\n  color: string.split(\"a b\", \" \", 0);\n}\n",
+    "Error: $limit: Must be 1 or greater, was 0."
+);
+error!(
+    string_split_limit_not_int,
+    "@use 'sass:string';\na {\n  color: string.split(\"a b\", \" \", 1.5);\n}\n",
+    "Error: $limit: 1.5 is not an int."
+);
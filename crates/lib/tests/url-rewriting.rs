@@ -0,0 +1,205 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use grass::{Fs, UrlRewrite};
+use macros::TestFs;
+
+#[macro_use]
+mod macros;
+
+/// Wraps [`TestFs`] so that reading an asset which doesn't exist returns an
+/// error, as it would on a real file system, rather than panicking
+#[derive(Debug)]
+struct AssetFs<'a>(&'a TestFs);
+
+impl Fs for AssetFs<'_> {
+    fn is_file(&self, path: &Path) -> bool {
+        self.0.is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.0.is_dir(path)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        if self.0.is_file(path) {
+            self.0.read(path)
+        } else {
+            Err(io::Error::new(io::ErrorKind::NotFound, "file not found"))
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.0.canonicalize(path)
+    }
+}
+
+fn compile(fs: &TestFs, rewrite: UrlRewrite, cache_bust: bool) -> String {
+    grass::from_path(
+        "input.scss",
+        &grass::Options::default()
+            .style(grass::OutputStyle::Compressed)
+            .rewrite_urls(rewrite)
+            .cache_bust_urls(cache_bust)
+            .fs(&AssetFs(fs)),
+    )
+    .unwrap()
+}
+
+fn hero_fs() -> TestFs {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"partials/hero\";");
+    fs.add_file(
+        "partials/_hero.scss",
+        "a { background: url(\"../img/bg.png\"); }",
+    );
+    fs
+}
+
+#[test]
+fn rewrites_url_from_partial_relative_to_output() {
+    assert_eq!(
+        compile(&hero_fs(), UrlRewrite::RelativeTo(Path::new("")), false),
+        "a{background:url(\"img/bg.png\")}"
+    );
+    assert_eq!(
+        compile(&hero_fs(), UrlRewrite::RelativeTo(Path::new("dist")), false),
+        "a{background:url(\"../img/bg.png\")}"
+    );
+}
+
+#[test]
+fn rewrites_unquoted_url() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"partials/hero\";");
+    fs.add_file(
+        "partials/_hero.scss",
+        "a { background: url(bg.png) no-repeat, url(../img/x.png); }",
+    );
+
+    assert_eq!(
+        compile(&fs, UrlRewrite::RelativeTo(Path::new("")), false),
+        "a{background:url(partials/bg.png) no-repeat,url(img/x.png)}"
+    );
+}
+
+#[test]
+fn rewrites_url_within_function() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"partials/hero\";");
+    fs.add_file(
+        "partials/_hero.scss",
+        "a { background-image: image-set(url(a.png) 1x, url(b.png) 2x); }",
+    );
+
+    assert_eq!(
+        compile(&fs, UrlRewrite::RelativeTo(Path::new("")), false),
+        "a{background-image:image-set(url(partials/a.png) 1x, url(partials/b.png) 2x)}"
+    );
+}
+
+#[test]
+fn url_is_resolved_from_file_containing_declaration() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "input.scss",
+        "@use \"partials/mixins\";\na { @include mixins.bg(url(img/a.png)); }",
+    );
+    fs.add_file(
+        "partials/_mixins.scss",
+        "@mixin bg($url) { background: $url; cursor: url(cursor.cur), auto; }",
+    );
+
+    assert_eq!(
+        compile(&fs, UrlRewrite::RelativeTo(Path::new("")), false),
+        "a{background:url(partials/img/a.png);cursor:url(partials/cursor.cur),auto}"
+    );
+}
+
+#[test]
+fn leaves_absolute_urls_unchanged() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"partials/hero\";");
+    fs.add_file(
+        "partials/_hero.scss",
+        "a { a: url(/a.png); b: url(https://example.com/b.png); c: url(data:image/png;base64,AA); d: url(#e); f: url(//example.com/f.png); }",
+    );
+
+    assert_eq!(
+        compile(&fs, UrlRewrite::RelativeTo(Path::new("dist")), false),
+        "a{a:url(/a.png);b:url(https://example.com/b.png);c:url(data:image/png;base64,AA);d:url(#e);f:url(//example.com/f.png)}"
+    );
+}
+
+#[test]
+fn leaves_other_functions_unchanged() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "a { a: \"url(a.png)\"; b: attr(url); }");
+
+    assert_eq!(
+        compile(&fs, UrlRewrite::RelativeTo(Path::new("dist")), false),
+        "a{a:\"url(a.png)\";b:attr(url)}"
+    );
+}
+
+#[test]
+fn rewrites_url_with_public_path() {
+    assert_eq!(
+        compile(&hero_fs(), UrlRewrite::PublicPath("/static/"), false),
+        "a{background:url(\"/static/img/bg.png\")}"
+    );
+}
+
+#[test]
+fn preserves_query_and_fragment() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"partials/hero\";");
+    fs.add_file(
+        "partials/_hero.scss",
+        "@font-face { src: url(font.svg?x=1#icons); }",
+    );
+
+    assert_eq!(
+        compile(&fs, UrlRewrite::PublicPath("/static"), false),
+        "@font-face{src:url(/static/partials/font.svg?x=1#icons)}"
+    );
+}
+
+#[test]
+fn adds_content_hash() {
+    let mut fs = hero_fs();
+    fs.add_file("img/bg.png", "first");
+
+    let first = compile(&fs, UrlRewrite::RelativeTo(Path::new("")), true);
+
+    assert!(first.starts_with("a{background:url(\"img/bg.png?v="));
+
+    fs.add_file("img/bg.png", "second");
+
+    assert_ne!(
+        first,
+        compile(&fs, UrlRewrite::RelativeTo(Path::new("")), true)
+    );
+}
+
+#[test]
+fn appends_content_hash_to_query() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "a { b: url(a.png?x=1#y); }");
+    fs.add_file("a.png", "");
+
+    assert_eq!(
+        compile(&fs, UrlRewrite::RelativeTo(Path::new("")), true),
+        "a{b:url(a.png?x=1&v=84222325#y)}"
+    );
+}
+
+#[test]
+fn omits_content_hash_for_missing_file() {
+    assert_eq!(
+        compile(&hero_fs(), UrlRewrite::RelativeTo(Path::new("")), true),
+        "a{background:url(\"img/bg.png\")}"
+    );
+}
@@ -877,7 +877,6 @@ fn use_member_global_variable_assignment_toplevel() {
 }
 
 #[test]
-#[ignore = "we don't hermetically evaluate @extend"]
 fn use_module_with_extend() {
     let mut fs = TestFs::new();
 
@@ -904,4 +903,116 @@ fn use_module_with_extend() {
     );
 }
 
+#[test]
+fn use_module_extends_upstream_selector() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("_a.scss", r#".a { x: y; }"#);
+
+    let input = r#"
+        @use "a";
+
+        .b {
+            @extend .a;
+        }
+    "#;
+
+    assert_eq!(
+        ".a, .b {\n  x: y;\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default().fs(&fs)).expect(input)
+    );
+}
+
+#[test]
+fn use_module_extend_does_not_apply_downstream() {
+    let mut fs = TestFs::new();
+
+    fs.add_file(
+        "_a.scss",
+        r#"
+        .a { x: y; }
+        .b { @extend .a; }
+    "#,
+    );
+
+    let input = r#"
+        @use "a";
+
+        .a {
+            z: w;
+        }
+    "#;
+
+    assert_eq!(
+        ".a, .b {\n  x: y;\n}\n\n.a {\n  z: w;\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default().fs(&fs)).expect(input)
+    );
+}
+
+#[test]
+fn use_module_extend_does_not_apply_to_sibling() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("_a.scss", r#".a { x: y; }"#);
+    fs.add_file("_b.scss", r#".b { @extend .a; }"#);
+
+    let input = r#"
+        @use "a";
+        @use "b";
+    "#;
+
+    assert_err!(
+        input,
+        "Error: The target selector was not found.",
+        grass::Options::default().fs(&fs)
+    );
+}
+
+#[test]
+fn use_module_extend_is_transitive() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("_a.scss", r#".a { x: y; }"#);
+    fs.add_file(
+        "_b.scss",
+        r#"
+        @use "a";
+        .b { @extend .a; }
+    "#,
+    );
+
+    let input = r#"
+        @use "b";
+
+        .c {
+            @extend .b;
+        }
+    "#;
+
+    assert_eq!(
+        ".a, .b, .c {\n  x: y;\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default().fs(&fs)).expect(input)
+    );
+}
+
+#[test]
+fn use_module_private_placeholder_not_extended_across_modules() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("_a.scss", r#"%-a { x: y; }"#);
+
+    let input = r#"
+        @use "a";
+
+        .b {
+            @extend %-a !optional;
+        }
+    "#;
+
+    assert_eq!(
+        "",
+        &grass::from_string(input.to_string(), &grass::Options::default().fs(&fs)).expect(input)
+    );
+}
+
 // todo: refactor these tests to use testfs where possible
@@ -0,0 +1,88 @@
+#[macro_use]
+mod macros;
+
+test!(
+    prefixes_property,
+    "a {\n  user-select: none;\n}\n",
+    "a {\n  -webkit-user-select: none;\n  -moz-user-select: none;\n  user-select: none;\n}\n",
+    grass::Options::default()
+        .browser_targets(&grass::BrowserTargets::parse("firefox >= 60, safari >= 14").unwrap())
+);
+test!(
+    does_not_prefix_property_for_newer_browsers,
+    "a {\n  user-select: none;\n}\n",
+    "a {\n  -webkit-user-select: none;\n  user-select: none;\n}\n",
+    grass::Options::default()
+        .browser_targets(&grass::BrowserTargets::parse("firefox >= 100, safari >= 14").unwrap())
+);
+test!(
+    does_not_duplicate_existing_prefixed_property,
+    "a {\n  -moz-appearance: none;\n  appearance: none;\n}\n",
+    "a {\n  -moz-appearance: none;\n  appearance: none;\n}\n",
+    grass::Options::default().browser_targets(&grass::BrowserTargets::parse("firefox 70").unwrap())
+);
+test!(
+    prefixes_property_names_in_transition,
+    "a {\n  transition: transform 1s;\n}\n",
+    "a {\n  -webkit-transition: -webkit-transform 1s;\n  transition: transform 1s;\n}\n",
+    grass::Options::default().browser_targets(&grass::BrowserTargets::parse("safari 7").unwrap())
+);
+test!(
+    prefixes_value,
+    "a {\n  position: sticky;\n  width: max-content;\n}\n",
+    "a {\n  position: -webkit-sticky;\n  position: sticky;\n  width: -moz-max-content;\n  width: max-content;\n}\n",
+    grass::Options::default().browser_targets(&grass::BrowserTargets::parse("safari 12, firefox 60").unwrap())
+);
+test!(
+    prefixes_function_value,
+    "a {\n  background: image-set(\"a.png\" 1x, \"b.png\" 2x);\n}\n",
+    "a {\n  background: -webkit-image-set(\"a.png\" 1x, \"b.png\" 2x);\n  background: image-set(\"a.png\" 1x, \"b.png\" 2x);\n}\n",
+    grass::Options::default().browser_targets(&grass::BrowserTargets::parse("chrome >= 100").unwrap())
+);
+test!(
+    prefixes_pseudo_element_in_separate_rule,
+    "a, input::placeholder {\n  color: gray;\n}\n",
+    "input::-moz-placeholder {\n  color: gray;\n}\na, input::placeholder {\n  color: gray;\n}\n",
+    grass::Options::default().browser_targets(&grass::BrowserTargets::parse("firefox 45").unwrap())
+);
+test!(
+    prefixes_keyframes,
+    "@keyframes spin {\n  to {\n    transform: rotate(360deg);\n  }\n}\n",
+    "@-webkit-keyframes spin {\n  to {\n    -webkit-transform: rotate(360deg);\n    transform: rotate(360deg);\n  }\n}\n@keyframes spin {\n  to {\n    -webkit-transform: rotate(360deg);\n    transform: rotate(360deg);\n  }\n}\n",
+    grass::Options::default().browser_targets(&grass::BrowserTargets::parse("chrome 30").unwrap())
+);
+test!(
+    last_versions_requires_no_legacy_prefixes,
+    "a {\n  transform: none;\n  box-sizing: border-box;\n}\n",
+    "a {\n  transform: none;\n  box-sizing: border-box;\n}\n",
+    grass::Options::default()
+        .browser_targets(&grass::BrowserTargets::parse("last 2 versions").unwrap())
+);
+test!(
+    does_not_prefix_without_targets,
+    "a {\n  user-select: none;\n}\n",
+    "a {\n  user-select: none;\n}\n",
+    grass::Options::default()
+);
+test!(
+    prefixes_flexbox_for_old_webkit,
+    "a {\n  display: flex;\n  flex: 1;\n  justify-content: center;\n}\n",
+    "a {\n  display: -webkit-flex;\n  display: flex;\n  -webkit-flex: 1;\n  flex: 1;\n  -webkit-justify-content: center;\n  justify-content: center;\n}\n",
+    grass::Options::default().browser_targets(&grass::BrowserTargets::parse("safari 8").unwrap())
+);
+test!(
+    does_not_prefix_flexbox_for_newer_browsers,
+    "a {\n  display: inline-flex;\n  flex: 1;\n}\n",
+    "a {\n  display: inline-flex;\n  flex: 1;\n}\n",
+    grass::Options::default().browser_targets(&grass::BrowserTargets::parse("safari 9").unwrap())
+);
+
+#[test]
+fn unsupported_query() {
+    for query in ["> 1%", "bogus", "not dead"] {
+        assert_eq!(
+            grass::BrowserTargets::parse(query).unwrap_err(),
+            format!("Unsupported browser query \"{}\".", query)
+        );
+    }
+}